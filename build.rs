@@ -48,6 +48,7 @@ struct Hitbox {
     width: Option<f32>,
     height: Option<f32>,
     radius: Option<f32>,
+    category: Option<String>,
 }
 
 fn main() {
@@ -175,6 +176,20 @@ fn write_object(object_data: ObjectData, output: &mut String) {
         output.write_str("hitbox: None,").unwrap();
     }
 
+    if let Some(category) = object_data
+        .hitbox
+        .as_ref()
+        .and_then(|hitbox| hitbox.category.as_ref())
+    {
+        write_value_raw(
+            "hitbox_category",
+            &format!("HitboxCategory::{}", category),
+            output,
+        );
+    } else {
+        write_value_raw("hitbox_category", "HitboxCategory::Solid", output);
+    }
+
     if let Some(children) = &object_data.children {
         output.write_str("children: &[").unwrap();
 
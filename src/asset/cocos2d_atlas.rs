@@ -25,12 +25,33 @@ pub struct Cocos2dAtlas {
     texture: Handle<Image>,
     squared: Handle<Image>,
     frames: HashMap<String, Cocos2dFrame>,
+    animations: HashMap<String, Cocos2dAnimation>,
 }
 
 #[derive(Clone, Resource, Default)]
 pub(crate) struct Cocos2dFrames {
     pub(crate) index: HashMap<String, usize>,
     pub(crate) frames: Vec<(Cocos2dFrame, AssetId<Image>, AssetId<Image>)>,
+    /// Sprite animations by their name, e.g. `GJBeast01_bite`
+    pub(crate) animations: HashMap<String, Cocos2dAnimation>,
+    /// Names of the animations of each description, e.g. `bite` for `GJBeast01`
+    pub(crate) animation_names: HashMap<String, Vec<String>>,
+}
+
+impl Cocos2dFrames {
+    /// Frames for simulating levels without any textures loaded, where every object is empty
+    pub(crate) fn headless() -> Cocos2dFrames {
+        Cocos2dFrames {
+            index: [("emptyFrame.png".to_string(), 0)].into_iter().collect(),
+            frames: vec![(
+                Cocos2dFrame::default(),
+                AssetId::default(),
+                AssetId::default(),
+            )],
+            animations: HashMap::new(),
+            animation_names: HashMap::new(),
+        }
+    }
 }
 
 pub(crate) fn move_frames_to_resource(
@@ -49,6 +70,16 @@ pub(crate) fn move_frames_to_resource(
                             .frames
                             .push((frame_info, atlas.texture.id(), atlas.squared.id()));
                     }
+                    for (animation_name, animation) in std::mem::take(&mut atlas.animations) {
+                        if let Some((description, name)) = animation_name.split_once('_') {
+                            frames
+                                .animation_names
+                                .entry(description.to_string())
+                                .or_default()
+                                .push(name.to_string());
+                        }
+                        frames.animations.insert(animation_name, animation);
+                    }
                 }
             }
             AssetEvent::Removed { .. } => {
@@ -66,6 +97,60 @@ pub(crate) struct Cocos2dFrame {
     pub(crate) rotated: bool,
 }
 
+/// A sprite drawn as part of an animation frame
+#[derive(Clone, Debug)]
+pub(crate) struct Cocos2dAnimationPart {
+    pub(crate) texture: String,
+    pub(crate) offset: Vec2,
+    pub(crate) scale: Vec2,
+    /// In degrees
+    pub(crate) rotation: f32,
+    pub(crate) flip_x: bool,
+    pub(crate) flip_y: bool,
+    pub(crate) z: i32,
+}
+
+pub(crate) type Cocos2dAnimationFrame = Vec<Cocos2dAnimationPart>;
+
+#[derive(Clone, Debug, Default)]
+pub(crate) struct Cocos2dAnimation {
+    pub(crate) frames: Vec<Cocos2dAnimationFrame>,
+    /// How long each frame is shown, if the description has it
+    pub(crate) delay: Option<f32>,
+    /// Whether the animation repeats, if the description has it
+    pub(crate) looped: Option<bool>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct AnimationDescriptionFile {
+    animation_container: HashMap<String, HashMap<String, AnimationPart>>,
+    /// Timing of the animations, laid out like cocos2d animation files
+    #[serde(default)]
+    animations: HashMap<String, AnimationDefinition>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct AnimationDefinition {
+    delay_per_unit: Option<f32>,
+    loops: Option<i64>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct AnimationPart {
+    texture: String,
+    #[serde(deserialize_with = "to_vec2")]
+    position: Vec2,
+    #[serde(deserialize_with = "to_vec2")]
+    scale: Vec2,
+    rotation: f32,
+    #[serde(deserialize_with = "to_vec2")]
+    flipped: Vec2,
+    z_value: i32,
+}
+
 #[derive(Deserialize)]
 struct AtlasFile {
     frames: HashMap<String, Frame>,
@@ -127,6 +212,29 @@ impl AssetLoader for Cocos2dAtlasLoader {
 
             let mut manifest_bytes = Vec::new();
             reader.read_to_end(&mut manifest_bytes).await?;
+
+            let file_name = load_context
+                .asset_path()
+                .path()
+                .file_name()
+                .unwrap()
+                .to_str()
+                .unwrap()
+                .to_string();
+
+            // Animation descriptions share the extension with the atlases but have no texture
+            if file_name.contains("AnimDesc") {
+                let description: AnimationDescriptionFile = plist::from_bytes(&manifest_bytes)?;
+
+                info!("Loaded {}, took {:?}.", file_name, timer.elapsed());
+                return Ok(Cocos2dAtlas {
+                    texture: Handle::default(),
+                    squared: Handle::default(),
+                    frames: HashMap::new(),
+                    animations: parse_animations(description),
+                });
+            }
+
             let manifest: AtlasFile = plist::from_bytes(&manifest_bytes)?;
 
             let async_compute = AsyncComputeTaskPool::get();
@@ -220,21 +328,12 @@ impl AssetLoader for Cocos2dAtlasLoader {
 
             let squared_handle = load_context.add_labeled_asset("squared".to_string(), squared);
 
-            info!(
-                "Loaded {}, took {:?}.",
-                load_context
-                    .asset_path()
-                    .path()
-                    .file_name()
-                    .unwrap()
-                    .to_str()
-                    .unwrap(),
-                timer.elapsed()
-            );
+            info!("Loaded {}, took {:?}.", file_name, timer.elapsed());
             Ok(Cocos2dAtlas {
                 texture: texture_handle,
                 squared: squared_handle,
                 frames: frames_future.await,
+                animations: HashMap::new(),
             })
         })
     }
@@ -244,6 +343,67 @@ impl AssetLoader for Cocos2dAtlasLoader {
     }
 }
 
+/// Groups the frames of the description into animations
+///
+/// Frames are named like `GJBeast01_bite_001.png`, which becomes frame 1 of `GJBeast01_bite`
+fn parse_animations(description: AnimationDescriptionFile) -> HashMap<String, Cocos2dAnimation> {
+    let mut numbered_frames: HashMap<String, Vec<(u32, Cocos2dAnimationFrame)>> = HashMap::new();
+
+    for (frame_name, parts) in description.animation_container {
+        let frame_name = frame_name.trim_end_matches(".png");
+        let Some((animation_name, frame_number)) = frame_name.rsplit_once('_') else {
+            continue;
+        };
+        let Ok(frame_number) = frame_number.parse() else {
+            continue;
+        };
+
+        let mut parts: Vec<(u32, AnimationPart)> = parts
+            .into_iter()
+            .filter_map(|(index, part)| Some((index.parse().ok()?, part)))
+            .collect();
+        parts.sort_unstable_by_key(|(index, _)| *index);
+
+        let frame = parts
+            .into_iter()
+            .map(|(_, part)| Cocos2dAnimationPart {
+                texture: part.texture,
+                offset: part.position,
+                scale: part.scale,
+                rotation: part.rotation,
+                flip_x: part.flipped.x != 0.,
+                flip_y: part.flipped.y != 0.,
+                z: part.z_value,
+            })
+            .collect();
+
+        numbered_frames
+            .entry(animation_name.to_string())
+            .or_default()
+            .push((frame_number, frame));
+    }
+
+    numbered_frames
+        .into_iter()
+        .map(|(animation_name, mut frames)| {
+            frames.sort_unstable_by_key(|(frame_number, _)| *frame_number);
+
+            let definition = description.animations.get(&animation_name);
+
+            let animation = Cocos2dAnimation {
+                frames: frames.into_iter().map(|(_, frame)| frame).collect(),
+                delay: definition.and_then(|definition| definition.delay_per_unit),
+                // Anything but a single play through is repeated
+                looped: definition
+                    .and_then(|definition| definition.loops)
+                    .map(|loops| loops != 1),
+            };
+
+            (animation_name, animation)
+        })
+        .collect()
+}
+
 async fn load_texture<'a>(
     load_context: &mut LoadContext<'a>,
     filename: &str,
@@ -277,7 +437,7 @@ where
     let split_str: Vec<f32> = s
         .trim_matches(|c| c == '{' || c == '}')
         .split(',')
-        .map(|str| str.parse().unwrap())
+        .map(|str| str.trim().parse().unwrap())
         .collect();
     Ok(Vec2 {
         x: split_str[0],
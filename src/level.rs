@@ -1,25 +1,51 @@
 use std::marker::PhantomData;
-use std::time::Instant;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
 
-use bevy::app::{App, PostUpdate, PreUpdate, Update};
+use bevy::app::{App, Last, PostUpdate, PreUpdate, Update};
 use bevy::core::FrameCountPlugin;
 use bevy::log::{info, warn};
 use bevy::math::{Vec3, Vec4};
 use bevy::prelude::{IntoSystemConfigs, Resource, World};
 use bevy::tasks::{AsyncComputeTaskPool, Task};
-use bevy::time::TimePlugin;
+use bevy::time::{TimePlugin, TimeUpdateStrategy};
 use bevy::utils::default;
 use indexmap::IndexMap;
 use serde::de::Error;
 use serde::{Deserialize, Deserializer};
 
 use crate::asset::cocos2d_atlas::Cocos2dFrames;
-use crate::level::animation::update_animation;
-use crate::level::collision::{update_collision, Hitbox};
+use crate::level::animation::{update_animation, update_frame_animation};
+use crate::level::checkpoint::{update_checkpoints, Checkpoints};
+use crate::level::collision::{update_collision, ActiveCollider, GlobalHitbox, Hitbox};
 use crate::level::color::{GlobalColorChannelKind, HsvMod, Pulses};
-use crate::level::player::{update_player_pos, Player};
+use crate::level::death::{
+    player_alive, update_player_death, update_respawn, LevelStart, PlayerDeath,
+};
+use crate::level::event::PlayerEvent;
+use crate::level::mode::wave::WaveTrail;
+use crate::level::mode::{apply_game_mode_portals, update_game_modes, GameModeKind, PlayerMode};
+use crate::level::orb::update_orbs;
+use crate::level::player::{
+    update_ghost_trail, update_player_collision, update_player_input, update_player_pos,
+    GhostTrail, Player, PlayerInput,
+};
+use crate::level::portal::{apply_portals, PortalContacts};
+use crate::level::replay::{level_hash, set_level, update_replay, ReplayState, TICK_RATE};
 use crate::level::transform::{GlobalTransform2d, Transform2d};
-use crate::level::trigger::{process_triggers, SpeedChange, TriggerActivator, TriggerData};
+use crate::level::trigger::advanced_follow::update_advanced_follow;
+use crate::level::trigger::area::{apply_area_colors, update_area_effects};
+use crate::level::trigger::camera::{update_camera_settings, update_ui_groups};
+use crate::level::trigger::count::update_count_listeners;
+use crate::level::trigger::end::update_level_end;
+use crate::level::trigger::event::dispatch_player_events;
+use crate::level::trigger::gradient::update_gradients;
+use crate::level::trigger::pickup::{update_level_time, update_main_time, LevelTime};
+use crate::level::trigger::random::LevelRng;
+use crate::level::trigger::shader::update_shader_state;
+use crate::level::trigger::teleport::apply_teleports;
+use crate::level::trigger::time::update_timers;
+use crate::level::trigger::{process_triggers, SpeedChange, TriggerActivator};
 use crate::level::{
     color::{
         clear_pulses, update_color_channel_calculated, update_object_color, ColorChannelCalculated,
@@ -35,13 +61,21 @@ use crate::level::{
 use crate::utils::{decompress, decrypt, str_to_bool, ObjectStorage, StartObjectStorage, U64Hash};
 
 mod animation;
+pub(crate) mod bot;
+pub(crate) mod checkpoint;
 pub(crate) mod collision;
 pub(crate) mod color;
 pub(crate) mod de;
+pub(crate) mod death;
 mod easing;
+pub(crate) mod event;
 pub(crate) mod group;
+pub(crate) mod mode;
 pub(crate) mod object;
+pub(crate) mod orb;
 pub(crate) mod player;
+pub(crate) mod portal;
+pub(crate) mod replay;
 pub(crate) mod section;
 pub(crate) mod transform;
 pub(crate) mod trigger;
@@ -136,6 +170,21 @@ impl LevelData {
             }))
         })
     }
+
+    /// Creates the world in low detail, for simulating the level next to the one being played
+    pub(crate) fn create_world(
+        &self,
+        cocos2d_frames: &Cocos2dFrames,
+    ) -> Result<World, anyhow::Error> {
+        let decompressed = self
+            .decompress_inner_level()
+            .ok_or_else(|| anyhow::anyhow!("Level has no data"))??;
+
+        let mut world = decompressed.parse()?.create_world(cocos2d_frames, true);
+        set_level(&mut world, self.id, level_hash(&decompressed.0));
+
+        Ok(world)
+    }
 }
 
 pub(crate) struct DecompressedInnerLevel(pub(crate) String);
@@ -199,27 +248,76 @@ pub(crate) struct ParsedInnerLevel<'a> {
 #[derive(Resource)]
 pub(crate) struct SongOffset(pub(crate) f32);
 
+/// Data the level world was created from
+#[derive(Resource)]
+pub(crate) struct LevelSource(pub(crate) Arc<LevelData>);
+
 impl<'a> ParsedInnerLevel<'a> {
     pub(crate) fn create_world(&self, cocos2d_frames: &Cocos2dFrames, low_detail: bool) -> World {
         let mut sub_app = App::new();
 
         sub_app.add_plugins((TimePlugin, FrameCountPlugin));
+        sub_app.insert_resource(TimeUpdateStrategy::ManualDuration(Duration::from_secs_f64(
+            1. / TICK_RATE,
+        )));
+
+        sub_app.add_event::<PlayerEvent>();
+        sub_app.init_resource::<PlayerInput>();
+        sub_app.init_resource::<LevelTime>();
+        sub_app.init_resource::<LevelRng>();
 
         sub_app.add_systems(PreUpdate, clear_group_delta);
 
         sub_app.add_systems(
             Update,
             (
-                update_collision,
-                (update_player_pos, clear_pulses).before(process_triggers),
+                update_respawn.before(update_player_input),
+                update_replay
+                    .after(update_respawn)
+                    .before(update_player_input),
+                (
+                    update_player_input.before(update_orbs),
+                    update_orbs.before(update_game_modes),
+                    update_game_modes.before(update_player_pos),
+                    update_player_pos.before(process_triggers),
+                    update_collision.after(update_player_pos),
+                    update_player_collision.after(update_collision),
+                    apply_game_mode_portals.after(update_player_collision),
+                    apply_portals.after(apply_game_mode_portals),
+                    update_player_death
+                        .after(apply_portals)
+                        .before(dispatch_player_events),
+                    update_ghost_trail.after(apply_portals),
+                )
+                    .run_if(player_alive),
+                (
+                    clear_pulses,
+                    update_level_time,
+                    update_main_time.after(update_level_time),
+                )
+                    .before(process_triggers),
                 process_triggers.after(update_player_pos),
+                apply_teleports.after(process_triggers),
+                update_level_end.after(apply_teleports),
+                dispatch_player_events.after(update_level_end),
+                update_ui_groups
+                    .after(process_triggers)
+                    .before(apply_group_delta),
+                update_timers.after(apply_teleports),
+                update_count_listeners.after(process_triggers),
+                update_advanced_follow
+                    .after(apply_teleports)
+                    .before(apply_group_delta),
                 (
                     update_group_archetype,
                     update_group_archetype_calculated.after(update_group_archetype),
                     update_color_channel_calculated,
                     apply_group_delta,
-                    update_sections.after(apply_group_delta),
+                    update_area_effects.after(apply_group_delta),
+                    update_camera_settings.after(update_area_effects),
+                    update_sections.after(update_area_effects),
                     update_animation.after(update_sections),
+                    update_frame_animation.after(update_sections),
                 )
                     .after(process_triggers),
             ),
@@ -230,9 +328,14 @@ impl<'a> ParsedInnerLevel<'a> {
             (
                 limit_sections,
                 (update_transform, update_object_color).after(limit_sections),
+                apply_area_colors.after(update_object_color),
+                update_gradients,
+                update_shader_state.after(update_transform),
             ),
         );
 
+        sub_app.add_systems(Last, update_checkpoints);
+
         let mut world = sub_app.world;
 
         let mut global_color_channels = GlobalColorChannels::default();
@@ -430,8 +533,15 @@ impl<'a> ParsedInnerLevel<'a> {
         let player = world
             .spawn((
                 Player::default(),
+                PlayerMode::default(),
+                WaveTrail::default(),
+                GhostTrail::default(),
+                PortalContacts::default(),
                 Transform2d::default(),
                 GlobalTransform2d::default(),
+                GameModeKind::default().hitbox(),
+                GlobalHitbox::default(),
+                ActiveCollider::default(),
                 Section::default(),
                 TriggerActivator::default(),
             ))
@@ -466,6 +576,7 @@ impl<'a> ParsedInnerLevel<'a> {
             SpeedChange {
                 forward_velocity: default_speed.0,
                 speed: default_speed.1,
+                on_path: true,
             },
             Hitbox::default(),
         ));
@@ -474,7 +585,18 @@ impl<'a> ParsedInnerLevel<'a> {
         trigger::construct_trigger_index(&mut world);
         info!("Trigger timeline construction took {:?}", start.elapsed());
 
-        world.init_resource::<TriggerData>();
+        let level_start = LevelStart::capture(&mut world);
+        world.insert_resource(level_start);
+        world.init_resource::<PlayerDeath>();
+        world.init_resource::<Checkpoints>();
+        world.init_resource::<ReplayState>();
+
+        let two_player = self
+            .start_object
+            .get(&"kA10")
+            .is_some_and(|two_player| str_to_bool(two_player));
+        world.resource_mut::<PlayerInput>().two_player = two_player;
+        world.resource_mut::<ReplayState>().two_player = two_player;
 
         world.insert_resource(SongOffset(
             self.start_object
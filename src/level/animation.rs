@@ -1,14 +1,21 @@
 use std::f32::consts::PI;
 
+use bevy::asset::{AssetId, Handle};
 use bevy::ecs::query::{QueryData, QueryFilter};
 use bevy::hierarchy::Parent;
+use bevy::log::debug;
 use bevy::math::Vec2;
-use bevy::prelude::{Children, Component, EntityWorldMut, Mut, Query, Res, With, Without};
+use bevy::prelude::{
+    Children, Component, DetectChangesMut, Entity, EntityWorldMut, Image, Mut, Query, Res, With,
+    Without,
+};
 use bevy::tasks::ComputeTaskPool;
 use bevy::time::Time;
 
+use crate::asset::cocos2d_atlas::{Cocos2dFrame, Cocos2dFrames};
 use crate::level::color::{ObjectColor, ObjectColorCalculated};
 use crate::level::easing::Easing;
+use crate::level::object::Object;
 use crate::level::section::GlobalSections;
 use crate::level::transform::Transform2d;
 use crate::utils::{str_to_bool, ObjectStorage};
@@ -19,12 +26,286 @@ pub(crate) enum Animation {
     ScaleAndFade(bool, f32, Vec2),
 }
 
+/// How long each frame of a sprite animation is shown, unless its description says otherwise
+const FRAME_DURATION: f32 = 1. / 24.;
+
+#[derive(Clone)]
+struct AnimationPart {
+    frame: Cocos2dFrame,
+    texture_ids: (AssetId<Image>, AssetId<Image>),
+    transform: Transform2d,
+}
+
+struct FrameSequence {
+    frames: Vec<Vec<AnimationPart>>,
+    /// How long each frame is shown, in seconds
+    delay: f32,
+    /// Animations that don't loop stay on their last frame
+    looped: bool,
+}
+
+/// Frame based animation of monsters and decorations, switched between with the animate trigger
+#[derive(Component)]
+pub(crate) struct FrameAnimation {
+    /// Frames of each animation in the order of the animation ids
+    animations: Vec<FrameSequence>,
+    /// Entities the parts of a frame are drawn with
+    pub(crate) parts: Vec<Entity>,
+    current: usize,
+    frame: usize,
+    elapsed: f32,
+    speed: f32,
+    changed: bool,
+}
+
+impl FrameAnimation {
+    fn new(animations: Vec<FrameSequence>) -> FrameAnimation {
+        FrameAnimation {
+            animations,
+            parts: Vec::new(),
+            current: 0,
+            frame: 0,
+            elapsed: 0.,
+            speed: 1.,
+            changed: false,
+        }
+    }
+
+    pub(crate) fn part_count(&self) -> usize {
+        self.animations
+            .iter()
+            .flat_map(|sequence| &sequence.frames)
+            .map(|frame| frame.len())
+            .max()
+            .unwrap_or_default()
+    }
+
+    pub(crate) fn play(&mut self, animation: usize) {
+        if animation >= self.animations.len() {
+            return;
+        }
+
+        self.current = animation;
+        self.frame = 0;
+        self.elapsed = 0.;
+        self.changed = true;
+    }
+}
+
+/// Animations of each kind of monster in the order of their animation ids
+const ANIMATION_ORDERS: [&[&str]; 3] = [
+    // Beasts
+    &["bite", "attack01", "attack01_end", "idle01"],
+    // Bats
+    &[
+        "idle01",
+        "idle02",
+        "idle03",
+        "attack01",
+        "attack02",
+        "attack02_end",
+        "sleep",
+        "sleep_loop",
+        "sleep_end",
+        "attack02_loop",
+    ],
+    // Spikeballs
+    &[
+        "idle01",
+        "idle02",
+        "toAttack01",
+        "attack01",
+        "attack02",
+        "toAttack03",
+        "attack03",
+        "idle03",
+        "fromAttack03",
+    ],
+];
+
+/// Orders the animations of a description by their animation id, picking the kind of monster
+/// whose animations it has the most of
+fn animation_order(names: &[String]) -> Vec<&str> {
+    let known = ANIMATION_ORDERS
+        .iter()
+        .max_by_key(|order| {
+            order
+                .iter()
+                .filter(|name| names.iter().any(|other| other == *name))
+                .count()
+        })
+        .filter(|order| {
+            order
+                .iter()
+                .any(|name| names.iter().any(|other| other == name))
+        });
+
+    if let Some(order) = known {
+        return order.to_vec();
+    }
+
+    // Descriptions of other monsters go by name
+    let mut names: Vec<&str> = names.iter().map(String::as_str).collect();
+    names.sort_unstable();
+    names.dedup();
+    names
+}
+
+/// Transitions between animations are only played once when their description doesn't say
+fn looped_by_default(name: &str) -> bool {
+    !(name.ends_with("_end") || name.starts_with("to") || name.starts_with("from"))
+}
+
+fn resolve_animation(
+    description: &str,
+    name: &str,
+    cocos2d_frames: &Cocos2dFrames,
+) -> FrameSequence {
+    let animation_name = format!("{}_{}", description, name);
+
+    let Some(animation) = cocos2d_frames.animations.get(&animation_name) else {
+        debug!("Cannot find animation with name \"{}\".", animation_name);
+        return FrameSequence {
+            frames: Vec::new(),
+            delay: FRAME_DURATION,
+            looped: true,
+        };
+    };
+
+    let frames = animation
+        .frames
+        .iter()
+        .map(|frame| {
+            frame
+                .iter()
+                .filter_map(|part| {
+                    let Some(frame_index) = cocos2d_frames.index.get(&part.texture) else {
+                        debug!(
+                            "Animation {}: Cannot find texture with name \"{}\". Skipping part.",
+                            animation_name, part.texture
+                        );
+                        return None;
+                    };
+
+                    let (frame, image_asset_id, squared_asset_id) =
+                        &cocos2d_frames.frames[*frame_index];
+
+                    let flip = Vec2::new(
+                        if part.flip_x { -1. } else { 1. },
+                        if part.flip_y { -1. } else { 1. },
+                    );
+
+                    Some(AnimationPart {
+                        frame: *frame,
+                        texture_ids: (*image_asset_id, *squared_asset_id),
+                        transform: Transform2d {
+                            translation: part.offset.extend(part.z as f32 / 1000.),
+                            angle: -part.rotation.to_radians(),
+                            scale: part.scale * flip,
+                            ..Default::default()
+                        },
+                    })
+                })
+                .collect()
+        })
+        .collect();
+
+    FrameSequence {
+        frames,
+        delay: animation.delay.unwrap_or(FRAME_DURATION),
+        looped: animation.looped.unwrap_or_else(|| looped_by_default(name)),
+    }
+}
+
+/// Decorations animated by going through the numbered frames of their texture
+fn numbered_frame_animation(
+    texture: &str,
+    cocos2d_frames: &Cocos2dFrames,
+) -> Option<FrameSequence> {
+    // Textures end with their frame number, like `Fire_01_looped_001.png`
+    let (prefix, _) = texture.trim_end_matches(".png").rsplit_once('_')?;
+
+    let frames: Vec<Vec<AnimationPart>> = (1..)
+        .map_while(|number| {
+            let frame_index = cocos2d_frames
+                .index
+                .get(&format!("{}_{:03}.png", prefix, number))?;
+            let (frame, image_asset_id, squared_asset_id) = &cocos2d_frames.frames[*frame_index];
+
+            Some(vec![AnimationPart {
+                frame: *frame,
+                texture_ids: (*image_asset_id, *squared_asset_id),
+                transform: Transform2d::default(),
+            }])
+        })
+        .collect();
+
+    if frames.len() < 2 {
+        return None;
+    }
+
+    Some(FrameSequence {
+        frames,
+        delay: FRAME_DURATION,
+        looped: true,
+    })
+}
+
 pub(crate) fn insert_animation_data(
     entity_world_mut: &mut EntityWorldMut,
     object_id: u64,
     object_data: &ObjectStorage,
+    texture: &str,
+    cocos2d_frames: &Cocos2dFrames,
 ) -> Result<(), anyhow::Error> {
+    // Monster textures start with their animation description, like `GJBeast01_01_001.png`
+    let description = texture.split_once('_').map(|(description, _)| description);
+
+    if let Some((description, names)) = description
+        .and_then(|description| cocos2d_frames.animation_names.get_key_value(description))
+    {
+        let names = animation_order(names);
+
+        let animations = names
+            .iter()
+            .map(|name| resolve_animation(description, name, cocos2d_frames))
+            .collect();
+
+        let mut frame_animation = FrameAnimation::new(animations);
+
+        frame_animation.play(
+            names
+                .iter()
+                .position(|name| *name == "idle01")
+                .unwrap_or_default(),
+        );
+
+        entity_world_mut.insert(frame_animation);
+        return Ok(());
+    }
+
     match object_id {
+        920 | 921 | 923 | 924 | 1050..=1054 | 1517..=1519 | 1591..=1593 | 1844..=1858 | 1860 => {
+            let Some(sequence) = numbered_frame_animation(texture, cocos2d_frames) else {
+                return Ok(());
+            };
+
+            let mut frame_animation = FrameAnimation::new(vec![sequence]);
+            frame_animation.play(0);
+
+            if let Some(speed) = object_data.get("107") {
+                frame_animation.speed = speed.parse()?;
+            }
+            if let Some(randomize) = object_data.get("106") {
+                if str_to_bool(randomize) {
+                    frame_animation.frame =
+                        fastrand::usize(..frame_animation.animations[0].frames.len());
+                }
+            }
+
+            entity_world_mut.insert(frame_animation);
+            Ok(())
+        }
         740 | 1705 | 741 | 742 | 1706 | 675 | 676 | 678 | 1707 | 679 | 1708 | 680 | 1709 | 1619
         | 1710 | 1620 | 1734 | 183 | 1735 | 184 | 1736 | 185 | 186 | 85 | 187 | 188 | 86 | 97
         | 137 | 138 | 139 | 154 | 155 | 156 | 180 | 181 | 182 | 222 | 224 | 223 | 375 | 376
@@ -149,6 +430,96 @@ pub(crate) fn update_animation(
     });
 }
 
+pub(crate) fn update_frame_animation(
+    global_sections: Res<GlobalSections>,
+    mut animates: Query<
+        (
+            &ObjectColorCalculated,
+            &mut Transform2d,
+            &mut FrameAnimation,
+        ),
+        Without<Parent>,
+    >,
+    mut parts: Query<
+        (
+            &mut Object,
+            &mut ObjectColor,
+            &mut Transform2d,
+            Option<&mut Handle<Image>>,
+        ),
+        With<Parent>,
+    >,
+    time: Res<Time>,
+) {
+    for section in &global_sections.sections[global_sections.visible.clone()] {
+        let mut iter = animates.iter_many_mut(section);
+        while let Some((calculated, mut transform, mut frame_animation)) = iter.fetch_next() {
+            if !calculated.enabled {
+                continue;
+            }
+
+            let frame_animation = &mut *frame_animation;
+
+            let sequence = &frame_animation.animations[frame_animation.current];
+            let frames = &sequence.frames;
+
+            if frames.is_empty() {
+                continue;
+            }
+
+            let last_frame = frames.len() - 1;
+
+            if !sequence.looped && frame_animation.frame == last_frame && !frame_animation.changed {
+                continue;
+            }
+
+            frame_animation.elapsed += time.delta_seconds() * frame_animation.speed;
+
+            let delay = sequence.delay.max(f32::EPSILON);
+            let advance = (frame_animation.elapsed / delay) as usize;
+
+            if advance == 0 && !frame_animation.changed {
+                continue;
+            }
+
+            frame_animation.elapsed %= delay;
+            frame_animation.frame = if sequence.looped {
+                (frame_animation.frame + advance) % frames.len()
+            } else {
+                (frame_animation.frame + advance).min(last_frame)
+            };
+            frame_animation.changed = false;
+
+            let frame = &frames[frame_animation.frame];
+
+            for (index, part_entity) in frame_animation.parts.iter().enumerate() {
+                let Ok((mut object, mut object_color, mut part_transform, image_handle)) =
+                    parts.get_mut(*part_entity)
+                else {
+                    continue;
+                };
+
+                let Some(part) = frame.get(index) else {
+                    // This frame has less parts than the others
+                    part_transform.scale = Vec2::ZERO;
+                    continue;
+                };
+
+                object.frame = part.frame;
+                object_color.texture_ids = part.texture_ids;
+                *part_transform = part.transform;
+
+                if let Some(mut image_handle) = image_handle {
+                    *image_handle = Handle::Weak(part.texture_ids.0);
+                }
+            }
+
+            // Parts only get their global transform updated when the root changes
+            transform.set_changed();
+        }
+    }
+}
+
 unsafe fn propagate_opacity_recursive<'w, 's, D: QueryData, F: QueryFilter>(
     children: &Children,
     children_query: &'w Query<'w, 's, D, F>,
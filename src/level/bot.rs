@@ -0,0 +1,370 @@
+use std::path::Path;
+
+use anyhow::{anyhow, bail};
+use bevy::app::{First, Last, PostUpdate, PreUpdate, RunFixedMainLoop, Update};
+use bevy::hierarchy::Parent;
+use bevy::prelude::{With, Without, World};
+use bevy::tasks::{AsyncComputeTaskPool, ComputeTaskPool, TaskPool};
+use futures_lite::future;
+
+use crate::api::robtop::RobtopApi;
+use crate::api::ServerApi;
+use crate::asset::cocos2d_atlas::Cocos2dFrames;
+use crate::level::checkpoint::{Checkpoint, Checkpoints};
+use crate::level::death::{restart_level, PlayerDeath};
+use crate::level::object::Object;
+use crate::level::player::{Player, PlayerInput};
+use crate::level::portal::DualPlayer;
+use crate::level::replay::{Replay, ReplayState, TICK_RATE};
+use crate::level::section::GlobalSections;
+use crate::level::transform::Transform2d;
+use crate::level::trigger::audio::AudioQueue;
+use crate::level::trigger::end::LevelEnd;
+use crate::level::LevelData;
+use crate::utils::section_index_from_x;
+
+/// Sections on each side of the player kept up to date when there is no camera
+const SIMULATED_SECTIONS: usize = 8;
+
+#[derive(Clone, Debug)]
+pub(crate) struct AutoplaySettings {
+    /// Ticks an input is held for before the next decision
+    pub(crate) ticks_per_decision: u32,
+    /// Decisions between world snapshots, the ones in between are simulated again
+    pub(crate) snapshot_interval: usize,
+    /// Gives up once the search reaches this many ticks into the level
+    pub(crate) max_ticks: u64,
+    pub(crate) max_backtracks: usize,
+}
+
+impl Default for AutoplaySettings {
+    fn default() -> Self {
+        Self {
+            ticks_per_decision: 4,
+            snapshot_interval: 15,
+            max_ticks: TICK_RATE as u64 * 600,
+            max_backtracks: 100_000,
+        }
+    }
+}
+
+struct Decision {
+    checkpoint: Option<Checkpoint>,
+    pressed: bool,
+    /// Both holding and releasing have been tried from here
+    exhausted: bool,
+}
+
+/// Depth first search over holding and releasing, backtracking whenever the player dies
+pub(crate) struct Autoplay {
+    settings: AutoplaySettings,
+    decisions: Vec<Decision>,
+    backtracks: usize,
+    end_x: f32,
+    furthest: f32,
+}
+
+impl Autoplay {
+    pub(crate) fn new(world: &mut World, settings: AutoplaySettings) -> Autoplay {
+        world.resource_mut::<ReplayState>().playback = None;
+
+        // Practice checkpoints would be placed and restored in the middle of the search
+        let mut checkpoints = world.resource_mut::<Checkpoints>();
+        checkpoints.enabled = false;
+        checkpoints.list.clear();
+
+        restart_level(world);
+
+        Autoplay {
+            settings,
+            decisions: Vec::new(),
+            backtracks: 0,
+            end_x: level_end_x(world),
+            furthest: 0.,
+        }
+    }
+
+    /// How far into the level the search has got, from 0 to 1
+    pub(crate) fn progress(&self) -> f32 {
+        (self.furthest / self.end_x).clamp(0., 1.)
+    }
+
+    /// Searches for up to `decisions` decisions, returning the replay once the end is reached
+    pub(crate) fn step(
+        &mut self,
+        world: &mut World,
+        decisions: usize,
+    ) -> Result<Option<Replay>, anyhow::Error> {
+        for _ in 0..decisions {
+            if self.finished(world) {
+                let replay = world.resource::<ReplayState>().replay();
+                self.verify(world, &replay)?;
+                return Ok(Some(replay));
+            }
+
+            let tick = world.resource::<ReplayState>().tick;
+            if tick >= self.settings.max_ticks {
+                bail!("Gave up after {} ticks", tick);
+            }
+
+            // Most of a level is spent holding or not holding, so keep the last input first
+            let mut pressed = self
+                .decisions
+                .last()
+                .is_some_and(|decision| decision.pressed);
+
+            let checkpoint = (self.decisions.len() % self.settings.snapshot_interval == 0)
+                .then(|| Checkpoint::capture(world));
+
+            self.decisions.push(Decision {
+                checkpoint,
+                pressed,
+                exhausted: false,
+            });
+
+            while !self.run_decision(world, pressed) {
+                pressed = self.backtrack(world)?;
+            }
+        }
+
+        Ok(None)
+    }
+
+    pub(crate) fn run(mut self, world: &mut World) -> Result<Replay, anyhow::Error> {
+        loop {
+            if let Some(replay) = self.step(world, usize::MAX)? {
+                return Ok(replay);
+            }
+        }
+    }
+
+    /// Plays the replay back from a restart, as the search only ever restored snapshots
+    fn verify(&self, world: &mut World, replay: &Replay) -> Result<(), anyhow::Error> {
+        world
+            .resource_mut::<ReplayState>()
+            .start_playback(replay.clone());
+        restart_level(world);
+
+        let result = loop {
+            if self.finished(world) {
+                break Ok(());
+            }
+
+            let tick = world.resource::<ReplayState>().tick;
+            if tick >= self.settings.max_ticks {
+                break Err(anyhow!("Replay didn't reach the end after {} ticks", tick));
+            }
+
+            run_tick(world, false);
+
+            if world.resource::<PlayerDeath>().died.is_some() {
+                break Err(anyhow!(
+                    "Replay dies at x {:.0} when played back from the start",
+                    primary_player_x(world)
+                ));
+            }
+        };
+
+        world.resource_mut::<ReplayState>().playback = None;
+
+        result
+    }
+
+    fn finished(&self, world: &mut World) -> bool {
+        world.resource::<LevelEnd>().reached || primary_player_x(world) >= self.end_x
+    }
+
+    /// Returns whether the player survived
+    fn run_decision(&mut self, world: &mut World, pressed: bool) -> bool {
+        for _ in 0..self.settings.ticks_per_decision {
+            run_tick(world, pressed);
+
+            if world.resource::<PlayerDeath>().died.is_some() {
+                return false;
+            }
+
+            self.furthest = self.furthest.max(primary_player_x(world));
+
+            if self.finished(world) {
+                break;
+            }
+        }
+
+        true
+    }
+
+    /// Goes back to the last decision with an input left to try and returns that input
+    fn backtrack(&mut self, world: &mut World) -> Result<bool, anyhow::Error> {
+        self.backtracks += 1;
+        if self.backtracks > self.settings.max_backtracks {
+            bail!("Gave up after {} backtracks", self.settings.max_backtracks);
+        }
+
+        loop {
+            let Some(decision) = self.decisions.last_mut() else {
+                bail!("No input gets the player past x {:.0}", self.furthest);
+            };
+
+            if decision.exhausted {
+                self.decisions.pop();
+                continue;
+            }
+
+            decision.exhausted = true;
+            decision.pressed = !decision.pressed;
+            let pressed = decision.pressed;
+
+            self.restore_last(world);
+
+            return Ok(pressed);
+        }
+    }
+
+    /// Puts the world back to right before the last decision
+    fn restore_last(&self, world: &mut World) {
+        let last = self.decisions.len() - 1;
+        let (start, checkpoint) = self.decisions[..=last]
+            .iter()
+            .enumerate()
+            .rev()
+            .find_map(|(index, decision)| Some((index, decision.checkpoint.as_ref()?)))
+            .expect("The first decision always has a snapshot");
+
+        checkpoint.restore(world);
+        world.resource_mut::<PlayerDeath>().died = None;
+
+        for decision in &self.decisions[start..last] {
+            for _ in 0..self.settings.ticks_per_decision {
+                run_tick(world, decision.pressed);
+            }
+        }
+    }
+}
+
+/// Steps the level world by one tick without the main world
+pub(crate) fn run_tick(world: &mut World, pressed: bool) {
+    world
+        .resource_mut::<PlayerInput>()
+        .set_both(pressed, pressed);
+
+    let section = section_index_from_x(primary_player_x(world)) as usize;
+    world.resource_mut::<GlobalSections>().visible =
+        section.saturating_sub(SIMULATED_SECTIONS)..section + SIMULATED_SECTIONS;
+
+    world.run_schedule(First);
+    world.run_schedule(PreUpdate);
+    world.run_schedule(RunFixedMainLoop);
+    world.run_schedule(Update);
+    world.run_schedule(PostUpdate);
+    world.run_schedule(Last);
+
+    // Nothing plays the audio back
+    world.resource_mut::<AudioQueue>().0.clear();
+}
+
+fn primary_player_x(world: &mut World) -> f32 {
+    world
+        .query_filtered::<&Transform2d, (With<Player>, Without<DualPlayer>)>()
+        .single(world)
+        .translation
+        .x
+}
+
+/// Where the last object of the level is, for levels without an end trigger
+fn level_end_x(world: &mut World) -> f32 {
+    let mut objects = world.query_filtered::<&Transform2d, (With<Object>, Without<Parent>)>();
+    let global_sections = world.resource::<GlobalSections>();
+
+    let Some(last_section) = global_sections.sections.last() else {
+        return 0.;
+    };
+
+    objects
+        .iter_many(world, last_section)
+        .map(|transform| transform.translation.x)
+        .fold(0., f32::max)
+}
+
+/// Creates the world of a level and searches it for a replay that completes it
+pub(crate) fn autoplay_level(
+    level_data: &LevelData,
+    cocos2d_frames: &Cocos2dFrames,
+    settings: &AutoplaySettings,
+) -> Result<Replay, anyhow::Error> {
+    let mut world = level_data.create_world(cocos2d_frames)?;
+
+    Autoplay::new(&mut world, settings.clone()).run(&mut world)
+}
+
+/// Downloads and searches every level in turn, for checking in bulk that levels can be completed
+pub(crate) async fn autoplay_levels(
+    level_ids: Vec<u64>,
+    cocos2d_frames: Cocos2dFrames,
+    settings: AutoplaySettings,
+) -> Vec<(u64, Result<Replay, anyhow::Error>)> {
+    let api = RobtopApi::default();
+
+    let mut results = Vec::with_capacity(level_ids.len());
+    for id in level_ids {
+        let result = match api.get_level_data(id).await {
+            Ok(level_data) => autoplay_level(&level_data, &cocos2d_frames, &settings),
+            Err(err) => Err(err),
+        };
+        results.push((id, result));
+    }
+
+    results
+}
+
+/// Autoplays the levels given on the command line and saves a replay of each completed one
+///
+/// Takes the directory to save the replays in followed by the level ids.
+pub(crate) fn autoplay_command(args: &[String]) -> Result<(), anyhow::Error> {
+    const USAGE: &str = "Usage: --autoplay <output directory> <level id>...";
+
+    let Some((output_dir, level_ids)) = args.split_first() else {
+        bail!(USAGE);
+    };
+    if level_ids.is_empty() {
+        bail!(USAGE);
+    }
+
+    let level_ids = level_ids
+        .iter()
+        .map(|id| id.parse())
+        .collect::<Result<Vec<u64>, _>>()?;
+
+    let output_dir = Path::new(output_dir);
+    std::fs::create_dir_all(output_dir)?;
+
+    // The level systems run on the task pools even without the rest of the app
+    ComputeTaskPool::get_or_init(TaskPool::new);
+    AsyncComputeTaskPool::get_or_init(TaskPool::new);
+
+    let results = future::block_on(autoplay_levels(
+        level_ids,
+        Cocos2dFrames::headless(),
+        AutoplaySettings::default(),
+    ));
+
+    let mut failed = 0;
+    for (id, result) in results {
+        match result {
+            Ok(replay) => {
+                let path = output_dir.join(format!("{}.json", id));
+                replay.save(&path)?;
+                println!("{}: Completed, replay saved to {}", id, path.display());
+            }
+            Err(err) => {
+                failed += 1;
+                println!("{}: Not completed. {}", id, err);
+            }
+        }
+    }
+
+    if failed > 0 {
+        bail!("{} level(s) could not be completed", failed);
+    }
+
+    Ok(())
+}
@@ -0,0 +1,307 @@
+use bevy::hierarchy::Parent;
+use bevy::prelude::{Entity, Mut, Resource, With, Without, World};
+use bevy::time::{Time, Virtual};
+
+use crate::level::collision::ActiveCollider;
+use crate::level::color::{GlobalColorChannel, GlobalColorChannelKind};
+use crate::level::death::PlayerDeath;
+use crate::level::group::{GlobalGroup, ObjectGroups};
+use crate::level::mode::wave::WaveTrail;
+use crate::level::mode::{GameModeKind, PlayerMode};
+use crate::level::orb::{Orb, Pad};
+use crate::level::player::{GhostTrail, Player};
+use crate::level::portal::{dual_player_bundle, DualPlayer, PortalContacts};
+use crate::level::replay::ReplayState;
+use crate::level::section::{GlobalSections, Section};
+use crate::level::transform::Transform2d;
+use crate::level::trigger::audio::AudioQueue;
+use crate::level::trigger::end::LevelEnd;
+use crate::level::trigger::pickup::{LevelTime, PickupValues};
+use crate::level::trigger::random::LevelRng;
+use crate::level::trigger::teleport::Teleports;
+use crate::level::trigger::{
+    Activated, GlobalTriggers, TriggerActivator, TriggerData, TriggerProgress, TriggerState,
+};
+use crate::utils::section_index_from_x;
+
+/// State of the players and the objects they can change
+pub(crate) struct LevelSnapshot {
+    /// The primary player first, followed by the dual player if there is one
+    players: Vec<(Player, Transform2d, GameModeKind)>,
+    trigger_activator: TriggerActivator,
+    /// Objects that can be moved by their groups
+    objects: Vec<(Entity, Transform2d)>,
+    groups: Vec<(Entity, f32, bool)>,
+    color_channels: Vec<(Entity, GlobalColorChannelKind)>,
+    /// Orbs and pads that have already been used
+    used: Vec<Entity>,
+    activated: Vec<Entity>,
+}
+
+impl LevelSnapshot {
+    pub(crate) fn capture(world: &mut World) -> LevelSnapshot {
+        let (player, transform, player_mode, trigger_activator) = world
+            .query::<(&Player, &Transform2d, &PlayerMode, &TriggerActivator)>()
+            .single(world);
+        let mut players = vec![(player.clone(), *transform, player_mode.kind)];
+        let trigger_activator = trigger_activator.clone();
+
+        players.extend(
+            world
+                .query_filtered::<(&Player, &Transform2d, &PlayerMode), With<DualPlayer>>()
+                .iter(world)
+                .map(|(player, transform, player_mode)| {
+                    (player.clone(), *transform, player_mode.kind)
+                }),
+        );
+
+        let objects = world
+            .query_filtered::<(Entity, &Transform2d), (With<ObjectGroups>, Without<Parent>)>()
+            .iter(world)
+            .map(|(entity, transform)| (entity, *transform))
+            .collect();
+
+        let groups = world
+            .query::<(Entity, &GlobalGroup)>()
+            .iter(world)
+            .map(|(entity, group)| (entity, group.opacity, group.enabled))
+            .collect();
+
+        let color_channels = world
+            .query::<(Entity, &GlobalColorChannel)>()
+            .iter(world)
+            .map(|(entity, color_channel)| (entity, color_channel.kind.clone()))
+            .collect();
+
+        let mut used: Vec<Entity> = world
+            .query::<(Entity, &Orb)>()
+            .iter(world)
+            .filter(|(_, orb)| orb.used)
+            .map(|(entity, _)| entity)
+            .collect();
+        used.extend(
+            world
+                .query::<(Entity, &Pad)>()
+                .iter(world)
+                .filter(|(_, pad)| pad.used)
+                .map(|(entity, _)| entity),
+        );
+
+        let activated = world
+            .query_filtered::<Entity, With<Activated>>()
+            .iter(world)
+            .collect();
+
+        LevelSnapshot {
+            players,
+            trigger_activator,
+            objects,
+            groups,
+            color_channels,
+            used,
+            activated,
+        }
+    }
+
+    pub(crate) fn restore(&self, world: &mut World) {
+        let dual_players: Vec<(Entity, Section)> = world
+            .query_filtered::<(Entity, &Section), With<DualPlayer>>()
+            .iter(world)
+            .map(|(entity, section)| (entity, *section))
+            .collect();
+
+        for (entity, section) in dual_players {
+            world.resource_mut::<GlobalSections>().sections[section.current as usize]
+                .swap_remove(&entity);
+            world.despawn(entity);
+        }
+
+        let player_entity = world
+            .query_filtered::<Entity, With<TriggerActivator>>()
+            .single(world);
+
+        let (player, transform, kind) = &self.players[0];
+
+        world.entity_mut(player_entity).insert((
+            player.clone(),
+            *transform,
+            PlayerMode::new(*kind),
+            kind.hitbox(),
+            WaveTrail::default(),
+            GhostTrail::default(),
+            PortalContacts::default(),
+            ActiveCollider::default(),
+            self.trigger_activator.clone(),
+        ));
+
+        for (player, transform, kind) in &self.players[1..] {
+            let dual_entity = world
+                .spawn(dual_player_bundle(
+                    player.clone(),
+                    *transform,
+                    *kind,
+                    Vec::new(),
+                ))
+                .id();
+
+            let section_index = section_index_from_x(transform.translation.x);
+            world.resource_mut::<GlobalSections>().sections[section_index as usize]
+                .insert(dual_entity);
+        }
+
+        let mut transforms = world.query::<&mut Transform2d>();
+        for (entity, transform) in &self.objects {
+            if let Ok(mut current) = transforms.get_mut(world, *entity) {
+                *current = *transform;
+            }
+        }
+
+        let mut groups = world.query::<&mut GlobalGroup>();
+        for (entity, opacity, enabled) in &self.groups {
+            if let Ok(mut group) = groups.get_mut(world, *entity) {
+                group.opacity = *opacity;
+                group.enabled = *enabled;
+            }
+        }
+
+        let mut color_channels = world.query::<&mut GlobalColorChannel>();
+        for (entity, kind) in &self.color_channels {
+            if let Ok(mut color_channel) = color_channels.get_mut(world, *entity) {
+                color_channel.kind = kind.clone();
+            }
+        }
+
+        for (entity, mut orb) in world.query::<(Entity, &mut Orb)>().iter_mut(world) {
+            orb.used = self.used.contains(&entity);
+        }
+
+        for (entity, mut pad) in world.query::<(Entity, &mut Pad)>().iter_mut(world) {
+            pad.used = self.used.contains(&entity);
+        }
+
+        let activated: Vec<Entity> = world
+            .query_filtered::<Entity, With<Activated>>()
+            .iter(world)
+            .collect();
+
+        for entity in activated {
+            world.entity_mut(entity).remove::<Activated>();
+        }
+
+        for entity in &self.activated {
+            if let Some(mut entity) = world.get_entity_mut(*entity) {
+                entity.insert(Activated);
+            }
+        }
+    }
+}
+
+/// A point in practice mode the player respawns at instead of the start of the level
+pub(crate) struct Checkpoint {
+    /// Time into the level the checkpoint was placed at, in seconds
+    pub(crate) time: f32,
+    /// Replay tick the checkpoint was placed at
+    tick: u64,
+    snapshot: LevelSnapshot,
+    pickup_values: PickupValues,
+    trigger_progress: TriggerProgress,
+    trigger_state: TriggerState,
+    /// Relative speed of the level set by time warps
+    time_speed: f32,
+    level_time: LevelTime,
+    rng: LevelRng,
+}
+
+impl Checkpoint {
+    pub(crate) fn capture(world: &mut World) -> Checkpoint {
+        let snapshot = LevelSnapshot::capture(world);
+
+        let time = world
+            .resource::<GlobalTriggers>()
+            .speed_changes
+            .time_for_pos(snapshot.players[0].0.distance);
+
+        Checkpoint {
+            time,
+            tick: world.resource::<ReplayState>().tick,
+            snapshot,
+            pickup_values: world.resource::<PickupValues>().clone(),
+            trigger_progress: world.resource::<TriggerData>().progress(),
+            trigger_state: TriggerState::capture(world),
+            time_speed: world.resource::<Time<Virtual>>().relative_speed(),
+            level_time: *world.resource::<LevelTime>(),
+            rng: world.resource::<LevelRng>().clone(),
+        }
+    }
+
+    pub(crate) fn restore(&self, world: &mut World) {
+        self.snapshot.restore(world);
+
+        world
+            .resource_mut::<TriggerData>()
+            .restore_progress(&self.trigger_progress);
+
+        let mut pickup_values = world.resource_mut::<PickupValues>();
+        let attempts = pickup_values.attempts;
+        *pickup_values = self.pickup_values.clone();
+        pickup_values.attempts = attempts;
+
+        self.trigger_state.restore(world);
+        world
+            .resource_mut::<Time<Virtual>>()
+            .set_relative_speed(self.time_speed);
+        world.insert_resource(self.level_time);
+        world.insert_resource(self.rng.clone());
+        world.insert_resource(LevelEnd::default());
+        world.insert_resource(Teleports::default());
+        world.insert_resource(AudioQueue::default());
+
+        world.resource_mut::<ReplayState>().rewind(self.tick);
+    }
+}
+
+#[derive(Default, Resource)]
+pub(crate) struct Checkpoints {
+    /// Whether practice mode is on
+    pub(crate) enabled: bool,
+    pub(crate) list: Vec<Checkpoint>,
+    /// Places a checkpoint automatically every this many seconds
+    pub(crate) interval: Option<f32>,
+    pub(crate) since_last: f32,
+    /// Places a checkpoint at the end of the frame
+    pub(crate) place: bool,
+}
+
+/// Places the checkpoints requested by the player or due from the interval
+pub(crate) fn update_checkpoints(world: &mut World) {
+    if world.resource::<PlayerDeath>().died.is_some() {
+        return;
+    }
+
+    let delta = world.resource::<Time>().delta_seconds();
+
+    let place = world.resource_scope(|_, mut checkpoints: Mut<Checkpoints>| {
+        if !checkpoints.enabled {
+            checkpoints.place = false;
+            return false;
+        }
+
+        checkpoints.since_last += delta;
+
+        let due = checkpoints
+            .interval
+            .is_some_and(|interval| checkpoints.since_last >= interval);
+
+        std::mem::take(&mut checkpoints.place) || due
+    });
+
+    if !place {
+        return;
+    }
+
+    let checkpoint = Checkpoint::capture(world);
+
+    let mut checkpoints = world.resource_mut::<Checkpoints>();
+    checkpoints.list.push(checkpoint);
+    checkpoints.since_last = 0.;
+}
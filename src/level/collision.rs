@@ -35,6 +35,19 @@ impl Default for Hitbox {
     }
 }
 
+/// What touching a hitbox does to the player
+#[derive(Clone, Component, Copy, Debug, Default, PartialEq, Eq)]
+pub(crate) enum HitboxCategory {
+    /// Can be stood on, but kills the player when run into
+    #[default]
+    Solid,
+    Hazard,
+    /// Has no effect on the player
+    Passable,
+    /// Portals, orbs, pads and other objects with their own behaviour
+    Special,
+}
+
 #[derive(Component, Copy, Clone)]
 pub(crate) struct GlobalHitbox {
     pub(crate) aabb: Vec4,
@@ -158,14 +171,110 @@ impl From<(&Hitbox, &Transform2d, &GlobalTransform2d)> for GlobalHitbox {
 }
 
 impl GlobalHitbox {
+    #[inline]
+    pub(crate) fn kind(&self) -> Option<GlobalHitboxKind> {
+        self.specific
+    }
+
     #[inline]
     pub(crate) fn intersect(&self, other: &GlobalHitbox) -> (bool, Option<Vec2>) {
         if !intersect_aabb(self.aabb, other.aabb) {
             return (false, None);
         }
 
-        (true, None)
+        if self.specific.is_none() && other.specific.is_none() {
+            return (true, None);
+        }
+
+        (self.shape().intersect(&other.shape()), None)
     }
+
+    fn shape(&self) -> Shape {
+        match self.specific {
+            Some(GlobalHitboxKind::Obb { vertices }) => Shape::Polygon(vertices.to_vec()),
+            Some(GlobalHitboxKind::Triangle { vertices }) => Shape::Polygon(vertices.to_vec()),
+            Some(GlobalHitboxKind::Circle { center, radius }) => Shape::Circle { center, radius },
+            None => {
+                let min = self.aabb.xy();
+                let max = -self.aabb.zw();
+                Shape::Polygon(vec![
+                    min,
+                    Vec2::new(max.x, min.y),
+                    max,
+                    Vec2::new(min.x, max.y),
+                ])
+            }
+        }
+    }
+}
+
+enum Shape {
+    Polygon(Vec<Vec2>),
+    Circle { center: Vec2, radius: f32 },
+}
+
+impl Shape {
+    /// Separating axis test between two convex shapes
+    fn intersect(&self, other: &Shape) -> bool {
+        match (self, other) {
+            (Shape::Polygon(a), Shape::Polygon(b)) => edge_normals(a)
+                .chain(edge_normals(b))
+                .all(|axis| overlaps(project(a, axis), project(b, axis))),
+            (Shape::Polygon(polygon), Shape::Circle { center, radius })
+            | (Shape::Circle { center, radius }, Shape::Polygon(polygon)) => {
+                let closest = polygon
+                    .iter()
+                    .min_by(|a, b| {
+                        a.distance_squared(*center)
+                            .total_cmp(&b.distance_squared(*center))
+                    })
+                    .copied()
+                    .unwrap_or(*center);
+
+                edge_normals(polygon)
+                    .chain(std::iter::once((*center - closest).normalize_or_zero()))
+                    .filter(|axis| *axis != Vec2::ZERO)
+                    .all(|axis| {
+                        let projected = center.dot(axis);
+                        overlaps(
+                            project(polygon, axis),
+                            (projected - radius, projected + radius),
+                        )
+                    })
+            }
+            (
+                Shape::Circle {
+                    center: center_a,
+                    radius: radius_a,
+                },
+                Shape::Circle {
+                    center: center_b,
+                    radius: radius_b,
+                },
+            ) => center_a.distance_squared(*center_b) <= (radius_a + radius_b).powi(2),
+        }
+    }
+}
+
+fn edge_normals(vertices: &[Vec2]) -> impl Iterator<Item = Vec2> + '_ {
+    vertices
+        .iter()
+        .zip(vertices.iter().cycle().skip(1))
+        .map(|(start, end)| (*end - *start).perp().normalize_or_zero())
+}
+
+fn project(vertices: &[Vec2], axis: Vec2) -> (f32, f32) {
+    vertices
+        .iter()
+        .map(|vertex| vertex.dot(axis))
+        .fold((f32::INFINITY, f32::NEG_INFINITY), |(min, max), value| {
+            (min.min(value), max.max(value))
+        })
+}
+
+#[inline]
+fn overlaps(a: (f32, f32), b: (f32, f32)) -> bool {
+    a.0 <= b.1 && b.0 <= a.1
 }
 
 impl<'w, 's, T: GizmoConfigGroup> GizmoPrimitive2d<GlobalHitbox> for Gizmos<'w, 's, T> {
@@ -27,7 +27,7 @@ pub(crate) struct GlobalColorChannel {
     pub(crate) kind: GlobalColorChannelKind,
 }
 
-#[derive(Debug)]
+#[derive(Clone, Debug)]
 pub(crate) enum GlobalColorChannelKind {
     Base {
         color: Vec4,
@@ -0,0 +1,168 @@
+use bevy::math::{Vec2, Vec3Swizzles};
+use bevy::prelude::{EventWriter, Mut, Query, Res, ResMut, Resource, Without, World};
+use bevy::time::{Time, Virtual};
+
+use crate::level::checkpoint::{Checkpoints, LevelSnapshot};
+use crate::level::collision::{ActiveCollider, GlobalHitbox, Hitbox, HitboxCategory};
+use crate::level::color::ObjectColorCalculated;
+use crate::level::event::{PlayerEvent, PlayerEventKind};
+use crate::level::player::Player;
+use crate::level::portal::DualPlayer;
+use crate::level::replay::ReplayState;
+use crate::level::transform::{GlobalTransform2d, Transform2d};
+use crate::level::trigger::pickup::{LevelTime, PickupValues};
+use crate::level::trigger::random::LevelRng;
+use crate::level::trigger::reset_trigger_state;
+use crate::level::trigger::settings::LevelSettings;
+
+/// How long the death effect plays before the player respawns, unless an options trigger changes it
+pub(crate) const RESPAWN_DELAY: f32 = 1.;
+
+/// Size of the part of the player that dies when inside a solid, relative to its hitbox
+const INNER_HITBOX_SCALE: f32 = 0.3;
+
+#[derive(Default, Resource)]
+pub(crate) struct PlayerDeath {
+    /// Where the player died and how long ago
+    pub(crate) died: Option<(Vec2, f32)>,
+    /// Set on respawn until the main world has restarted the audio
+    pub(crate) respawned: bool,
+}
+
+pub(crate) fn player_alive(player_death: Res<PlayerDeath>) -> bool {
+    player_death.died.is_none()
+}
+
+/// Kills the player when it touches a hazard or ends up inside a solid it couldn't land on
+pub(crate) fn update_player_death(
+    mut player_death: ResMut<PlayerDeath>,
+    players: Query<(&Transform2d, &Hitbox, &ActiveCollider, Option<&DualPlayer>)>,
+    others: Query<(&HitboxCategory, Option<&ObjectColorCalculated>), Without<Player>>,
+    mut player_events: EventWriter<PlayerEvent>,
+) {
+    for (transform, hitbox, active_collider, dual_player) in &players {
+        let Hitbox::Box { half_extents, .. } = *hitbox else {
+            continue;
+        };
+
+        let inner_hitbox = Hitbox::Box {
+            no_rotation: true,
+            offset: None,
+            half_extents: half_extents * INNER_HITBOX_SCALE,
+        };
+        let inner_hitbox = GlobalHitbox::from((
+            &inner_hitbox,
+            transform,
+            &GlobalTransform2d::from(*transform),
+        ));
+
+        let died = active_collider
+            .collided
+            .iter()
+            .any(|(entity, other_hitbox, ..)| {
+                let Ok((category, calculated)) = others.get(*entity) else {
+                    return false;
+                };
+
+                if calculated.is_some_and(|calculated| !calculated.enabled) {
+                    return false;
+                }
+
+                match category {
+                    HitboxCategory::Hazard => true,
+                    HitboxCategory::Solid => inner_hitbox.intersect(other_hitbox).0,
+                    HitboxCategory::Passable | HitboxCategory::Special => false,
+                }
+            });
+
+        if !died {
+            continue;
+        }
+
+        player_death.died = Some((transform.translation.xy(), 0.));
+
+        player_events.send(PlayerEvent {
+            kind: PlayerEventKind::Death,
+            player2: dual_player.is_some(),
+        });
+
+        return;
+    }
+}
+
+/// State of the level world before the first frame, put back every time the player respawns
+#[derive(Resource)]
+pub(crate) struct LevelStart(LevelSnapshot);
+
+impl LevelStart {
+    pub(crate) fn capture(world: &mut World) -> LevelStart {
+        LevelStart(LevelSnapshot::capture(world))
+    }
+
+    fn restore(&self, world: &mut World) {
+        self.0.restore(world);
+
+        reset_trigger_state(world);
+
+        world.insert_resource(LevelTime::default());
+        world.resource_mut::<LevelRng>().reseed();
+        world.resource_mut::<PickupValues>().reset_non_persistent();
+        // Time warps last until the level is restarted
+        world.resource_mut::<Time<Virtual>>().set_relative_speed(1.);
+
+        world.resource_mut::<ReplayState>().rewind(0);
+    }
+}
+
+/// Puts the level back to the start right away, without the death effect
+pub(crate) fn restart_level(world: &mut World) {
+    let mut player_death = world.resource_mut::<PlayerDeath>();
+    player_death.died = None;
+    player_death.respawned = true;
+
+    world.resource_scope(|world, level_start: Mut<LevelStart>| {
+        level_start.restore(world);
+    });
+
+    world.resource_mut::<PickupValues>().attempts += 1;
+}
+
+/// Restarts the level once the death effect has finished
+pub(crate) fn update_respawn(world: &mut World) {
+    let delta = world.resource::<Time>().delta_seconds();
+    let respawn_delay = world.resource::<LevelSettings>().respawn_delay();
+
+    let mut player_death = world.resource_mut::<PlayerDeath>();
+
+    let Some((_, elapsed)) = &mut player_death.died else {
+        return;
+    };
+
+    *elapsed += delta;
+
+    if *elapsed < respawn_delay {
+        return;
+    }
+
+    player_death.died = None;
+    player_death.respawned = true;
+
+    // Practice mode respawns at the last checkpoint
+    let restored = world.resource_scope(|world, mut checkpoints: Mut<Checkpoints>| {
+        checkpoints.since_last = 0.;
+
+        let Some(checkpoint) = checkpoints.list.last().filter(|_| checkpoints.enabled) else {
+            return false;
+        };
+        checkpoint.restore(world);
+        true
+    });
+
+    if !restored {
+        world.resource_scope(|world, level_start: Mut<LevelStart>| {
+            level_start.restore(world);
+        });
+    }
+
+    world.resource_mut::<PickupValues>().attempts += 1;
+}
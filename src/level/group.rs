@@ -48,6 +48,15 @@ impl Default for GlobalGroup {
 pub(crate) struct GlobalGroupDeltas {
     pub(crate) translation_delta: Vec2,
     pub(crate) rotation: RotationKind,
+    pub(crate) scale: Option<ScaleDelta>,
+}
+
+#[derive(Clone, Copy)]
+pub(crate) struct ScaleDelta {
+    /// Objects are scaled in place when there is no center
+    pub(crate) center: Option<Entity>,
+    pub(crate) factor: Vec2,
+    pub(crate) only_move: bool,
 }
 
 pub(crate) enum RotationKind {
@@ -95,6 +104,7 @@ pub(crate) fn clear_group_delta(
         let global_group = global_group.bypass_change_detection();
         global_group.translation_delta = Vec2::ZERO;
         global_group.rotation = RotationKind::Angle(0.);
+        global_group.scale = None;
     }
 }
 
@@ -145,6 +155,30 @@ pub(crate) fn apply_group_delta(
             }
         }
     }
+
+    for (group, group_deltas) in &groups {
+        let Some(scale) = group_deltas.scale else {
+            continue;
+        };
+
+        let center = scale
+            .center
+            .and_then(|center_entity| objects.get(center_entity).ok())
+            .map(|center_transform| center_transform.translation.xy());
+
+        let mut iter = objects.iter_many_mut(&group.root_entities);
+
+        while let Some(mut transform) = iter.fetch_next() {
+            if let Some(center) = center {
+                let translation = center + (transform.translation.xy() - center) * scale.factor;
+                transform.translation.x = translation.x;
+                transform.translation.y = translation.y;
+            }
+            if !scale.only_move {
+                transform.scale *= scale.factor;
+            }
+        }
+    }
 }
 
 pub(crate) fn spawn_groups(
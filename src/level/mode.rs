@@ -0,0 +1,251 @@
+use std::any::Any;
+
+use bevy::math::Vec2;
+use bevy::prelude::{Component, Entity, EventWriter, Local, Query, With, World};
+
+use crate::level::collision::{ActiveCollider, Hitbox};
+use crate::level::event::{GameEvent, PlayerEvent, PlayerEventKind};
+use crate::level::mode::ball::BallMode;
+use crate::level::mode::cube::CubeMode;
+use crate::level::mode::robot::RobotMode;
+use crate::level::mode::ship::ShipMode;
+use crate::level::mode::spider::SpiderMode;
+use crate::level::mode::swing::SwingMode;
+use crate::level::mode::ufo::UfoMode;
+use crate::level::mode::wave::WaveMode;
+use crate::level::player::{Player, PlayerInput};
+use crate::level::portal::DualPlayer;
+use crate::level::transform::Transform2d;
+
+pub(crate) mod ball;
+pub(crate) mod cube;
+pub(crate) mod robot;
+pub(crate) mod ship;
+pub(crate) mod spider;
+pub(crate) mod swing;
+pub(crate) mod ufo;
+pub(crate) mod wave;
+
+/// The game simulates at a fixed rate, so the physics constants are given per tick
+pub(crate) const PHYSICS_TICK_RATE: f32 = 60.;
+
+/// Gravity shared by all of the gamemodes, in units per tick squared
+pub(crate) const GRAVITY: f32 = 0.958199;
+
+pub(crate) trait GameMode: Send + Sync + 'static {
+    fn update(
+        &mut self,
+        world: &mut World,
+        player_entity: Entity,
+        system_state: &mut Box<dyn Any + Send + Sync>,
+    );
+
+    fn create_system_state(&self, world: &mut World) -> Box<dyn Any + Send + Sync>;
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub(crate) enum GameModeKind {
+    #[default]
+    Cube,
+    Ship,
+    Ball,
+    Ufo,
+    Wave,
+    Robot,
+    Spider,
+    Swing,
+}
+
+impl GameModeKind {
+    pub(crate) fn create(self) -> Box<dyn GameMode> {
+        match self {
+            GameModeKind::Cube => Box::<CubeMode>::default(),
+            GameModeKind::Ship => Box::<ShipMode>::default(),
+            GameModeKind::Ball => Box::<BallMode>::default(),
+            GameModeKind::Ufo => Box::<UfoMode>::default(),
+            GameModeKind::Wave => Box::<WaveMode>::default(),
+            GameModeKind::Robot => Box::<RobotMode>::default(),
+            GameModeKind::Spider => Box::<SpiderMode>::default(),
+            GameModeKind::Swing => Box::<SwingMode>::default(),
+        }
+    }
+
+    pub(crate) fn hitbox(self) -> Hitbox {
+        // The wave is a lot smaller than the other gamemodes
+        let half_extents = match self {
+            GameModeKind::Wave => Vec2::splat(5.),
+            _ => Vec2::splat(15.),
+        };
+
+        Hitbox::Box {
+            no_rotation: true,
+            offset: None,
+            half_extents,
+        }
+    }
+
+    /// Height of the corridor the gamemode is limited to, if any
+    fn bounds_height(self) -> Option<f32> {
+        match self {
+            GameModeKind::Cube | GameModeKind::Robot => None,
+            GameModeKind::Ship | GameModeKind::Ufo | GameModeKind::Wave | GameModeKind::Swing => {
+                Some(300.)
+            }
+            GameModeKind::Ball | GameModeKind::Spider => Some(240.),
+        }
+    }
+
+    fn portal_event(self) -> GameEvent {
+        match self {
+            GameModeKind::Cube => GameEvent::PortalNormal,
+            GameModeKind::Ship => GameEvent::PortalShip,
+            GameModeKind::Ball => GameEvent::PortalBall,
+            GameModeKind::Ufo => GameEvent::PortalUfo,
+            GameModeKind::Wave => GameEvent::PortalWave,
+            GameModeKind::Robot => GameEvent::PortalRobot,
+            GameModeKind::Spider => GameEvent::PortalSpider,
+            GameModeKind::Swing => GameEvent::PortalSwing,
+        }
+    }
+}
+
+#[derive(Component)]
+pub(crate) struct PlayerMode {
+    pub(crate) kind: GameModeKind,
+    mode: Option<Box<dyn GameMode>>,
+    system_state: Option<Box<dyn Any + Send + Sync>>,
+    /// Portal the player is still touching, so it is only entered once
+    last_portal: Option<Entity>,
+}
+
+impl Default for PlayerMode {
+    fn default() -> Self {
+        Self::new(GameModeKind::default())
+    }
+}
+
+impl PlayerMode {
+    pub(crate) fn new(kind: GameModeKind) -> Self {
+        Self {
+            kind,
+            mode: Some(kind.create()),
+            system_state: None,
+            last_portal: None,
+        }
+    }
+
+    pub(crate) fn switch(&mut self, kind: GameModeKind) {
+        *self = Self {
+            last_portal: self.last_portal,
+            ..Self::new(kind)
+        };
+    }
+}
+
+#[derive(Clone, Copy, Component)]
+pub(crate) struct GameModePortal(pub(crate) GameModeKind);
+
+pub(crate) fn update_game_modes(world: &mut World, mut player_entities: Local<Vec<Entity>>) {
+    player_entities.clear();
+    player_entities.extend(
+        world
+            .query_filtered::<Entity, With<PlayerMode>>()
+            .iter(world),
+    );
+
+    for player_entity in player_entities.drain(..) {
+        let player2 = world.get::<DualPlayer>(player_entity).is_some();
+        let pressed = world.resource::<PlayerInput>().pressed(player2);
+
+        // Dashing overrides the gamemode until the button is let go or the player lands
+        if let Some(mut player) = world.get_mut::<Player>(player_entity) {
+            if let Some(dash_velocity) = player.dash {
+                if pressed && !player.on_ground {
+                    player.velocity.y = dash_velocity;
+                    continue;
+                }
+
+                player.dash = None;
+
+                world.send_event(PlayerEvent {
+                    kind: PlayerEventKind::Game(GameEvent::DashStop),
+                    player2,
+                });
+            }
+        }
+
+        let Some(mut player_mode) = world.get_mut::<PlayerMode>(player_entity) else {
+            continue;
+        };
+
+        let Some(mut mode) = player_mode.mode.take() else {
+            continue;
+        };
+
+        let mut system_state = match player_mode.system_state.take() {
+            Some(system_state) => system_state,
+            None => mode.create_system_state(world),
+        };
+
+        mode.update(world, player_entity, &mut system_state);
+
+        if let Some(mut player_mode) = world.get_mut::<PlayerMode>(player_entity) {
+            player_mode.mode = Some(mode);
+            player_mode.system_state = Some(system_state);
+        }
+    }
+}
+
+pub(crate) fn apply_game_mode_portals(
+    mut players: Query<(
+        &mut Player,
+        &mut PlayerMode,
+        &mut Hitbox,
+        &ActiveCollider,
+        Option<&DualPlayer>,
+    )>,
+    portals: Query<(&GameModePortal, &Transform2d)>,
+    mut player_events: EventWriter<PlayerEvent>,
+) {
+    for (mut player, mut player_mode, mut hitbox, active_collider, dual_player) in &mut players {
+        let touched_portal = active_collider
+            .collided
+            .iter()
+            .find_map(|(entity, ..)| Some((*entity, portals.get(*entity).ok()?)));
+
+        let Some((portal_entity, (portal, portal_transform))) = touched_portal else {
+            player_mode.last_portal = None;
+            continue;
+        };
+
+        if player_mode.last_portal == Some(portal_entity) {
+            continue;
+        }
+
+        let kind = portal.0;
+
+        if player_mode.kind != kind {
+            player_mode.switch(kind);
+            *hitbox = kind.hitbox();
+        }
+        player_mode.last_portal = Some(portal_entity);
+
+        match kind.bounds_height() {
+            Some(height) => {
+                // The corridor is snapped to the grid and can't go below the ground
+                let center = (portal_transform.translation.y / 30.).round() * 30.;
+                player.floor = (center - height / 2.).max(0.);
+                player.ceiling = player.floor + height;
+            }
+            None => {
+                player.floor = 0.;
+                player.ceiling = f32::INFINITY;
+            }
+        }
+
+        player_events.send(PlayerEvent {
+            kind: PlayerEventKind::Game(kind.portal_event()),
+            player2: dual_player.is_some(),
+        });
+    }
+}
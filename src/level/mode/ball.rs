@@ -0,0 +1,98 @@
+use std::any::Any;
+use std::f32::consts::PI;
+
+use bevy::ecs::system::SystemState;
+use bevy::prelude::{Entity, EventWriter, Query, Res, World};
+use bevy::time::Time;
+
+use crate::level::event::{GameEvent, PlayerEvent, PlayerEventKind};
+use crate::level::mode::{GameMode, GRAVITY, PHYSICS_TICK_RATE};
+use crate::level::player::Player;
+use crate::level::portal::DualPlayer;
+use crate::level::transform::Transform2d;
+
+type BallSystemParam = (
+    Res<'static, Time>,
+    Query<
+        'static,
+        'static,
+        (
+            &'static mut Player,
+            &'static mut Transform2d,
+            Option<&'static DualPlayer>,
+        ),
+    >,
+    EventWriter<'static, PlayerEvent>,
+);
+
+#[derive(Default)]
+pub(crate) struct BallMode;
+
+/// Acceleration while falling, relative to the gravity
+const FALL_FACTOR: f32 = 0.6;
+/// Velocity the ball leaves the ground with after switching gravity
+const SWITCH_VELOCITY: f32 = 3.;
+const VELOCITY_LIMIT: f32 = 15.;
+/// Half a turn per block travelled
+const ROLL_SPEED: f32 = PI / 30.;
+
+impl GameMode for BallMode {
+    fn update(
+        &mut self,
+        world: &mut World,
+        player_entity: Entity,
+        system_state: &mut Box<dyn Any + Send + Sync>,
+    ) {
+        let system_state: &mut SystemState<BallSystemParam> =
+            &mut *system_state.downcast_mut().unwrap();
+
+        let (time, mut player_query, mut player_events) = system_state.get_mut(world);
+
+        let Ok((mut player, mut transform, dual_player)) = player_query.get_mut(player_entity)
+        else {
+            return;
+        };
+
+        let slowed_delta = time.delta_seconds() * 0.9;
+
+        if player.on_ground && player.click_buffered {
+            player.click_buffered = false;
+            player.upside_down = !player.upside_down;
+            player.on_ground = false;
+
+            let switch_velocity = if player.mini {
+                SWITCH_VELOCITY * 0.8
+            } else {
+                SWITCH_VELOCITY
+            };
+            player.velocity.y = player.gravity_direction() * switch_velocity * PHYSICS_TICK_RATE;
+
+            player_events.send(PlayerEvent {
+                kind: PlayerEventKind::Game(GameEvent::BallSwitch),
+                player2: dual_player.is_some(),
+            });
+        }
+
+        let direction = player.gravity_direction();
+
+        player.velocity.y += direction
+            * FALL_FACTOR
+            * GRAVITY
+            * PHYSICS_TICK_RATE
+            * PHYSICS_TICK_RATE
+            * player.gravity_multiplier
+            * slowed_delta;
+        player.velocity.y = player.velocity.y.clamp(
+            -VELOCITY_LIMIT * PHYSICS_TICK_RATE,
+            VELOCITY_LIMIT * PHYSICS_TICK_RATE,
+        );
+
+        // Roll along the surface the ball is pulled towards
+        let forward = player.velocity.x * player.speed * time.delta_seconds();
+        transform.angle += direction * forward * ROLL_SPEED;
+    }
+
+    fn create_system_state(&self, world: &mut World) -> Box<dyn Any + Send + Sync> {
+        Box::new(SystemState::<BallSystemParam>::new(world))
+    }
+}
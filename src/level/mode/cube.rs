@@ -2,29 +2,35 @@ use std::any::Any;
 use std::f32::consts::{FRAC_2_PI, FRAC_PI_2, PI, TAU};
 
 use bevy::ecs::system::SystemState;
-use bevy::input::ButtonInput;
-use bevy::log::info;
-use bevy::prelude::{Entity, MouseButton, Query, Res, World};
+use bevy::prelude::{Entity, EventWriter, Query, Res, World};
 use bevy::time::Time;
-use instant::Instant;
 
-use crate::level::mode::GameMode;
-use crate::level::player::Player;
+use crate::level::event::{GameEvent, PlayerEvent, PlayerEventKind};
+use crate::level::mode::{GameMode, GRAVITY, PHYSICS_TICK_RATE};
+use crate::level::player::{Player, PlayerInput};
+use crate::level::portal::DualPlayer;
 use crate::level::transform::Transform2d;
 
 type CubeSystemParam = (
     Res<'static, Time>,
-    Res<'static, ButtonInput<MouseButton>>,
-    Query<'static, 'static, (&'static mut Player, &'static mut Transform2d)>,
+    Res<'static, PlayerInput>,
+    Query<
+        'static,
+        'static,
+        (
+            &'static mut Player,
+            &'static mut Transform2d,
+            Option<&'static DualPlayer>,
+        ),
+    >,
+    EventWriter<'static, PlayerEvent>,
 );
 
 #[derive(Default)]
-pub(crate) struct CubeMode {
-    a: Option<Instant>,
-}
+pub(crate) struct CubeMode;
 
-const GRAVITY: f32 = 0.958199;
 const JUMP_HEIGHT: f32 = 11.180032;
+const MINI_JUMP_HEIGHT: f32 = 9.4;
 const VELOCITY_LIMIT: f32 = 15.;
 
 impl GameMode for CubeMode {
@@ -37,16 +43,20 @@ impl GameMode for CubeMode {
         let system_state: &mut SystemState<CubeSystemParam> =
             &mut *system_state.downcast_mut().unwrap();
 
-        let (time, mouse_input, mut player_query) = system_state.get_mut(world);
+        let (time, input, mut player_query, mut player_events) = system_state.get_mut(world);
 
-        let (mut player, mut transform) = player_query.get_mut(player_entity).unwrap();
+        let Ok((mut player, mut transform, dual_player)) = player_query.get_mut(player_entity)
+        else {
+            return;
+        };
 
-        if player.on_ground {
-            if let Some(a) = self.a {
-                info!("{:?}", a.elapsed());
-                self.a = None;
-            }
+        let player2 = dual_player.is_some();
 
+        let direction = player.gravity_direction();
+        let slowed_delta = time.delta_seconds() * 0.9;
+
+        if player.on_ground {
+            // Snap the rotation back to the closest side
             let rotation = transform.angle % TAU;
             let mut target = (rotation * FRAC_2_PI).fract();
 
@@ -56,22 +66,37 @@ impl GameMode for CubeMode {
 
             transform.angle -= (FRAC_PI_2 * target / 0.075) * time.delta_seconds();
 
-            if mouse_input.pressed(MouseButton::Left) {
-                self.a = Some(Instant::now());
-                player.velocity.y = JUMP_HEIGHT;
+            if input.pressed(player2) {
+                let jump_height = if player.mini {
+                    MINI_JUMP_HEIGHT
+                } else {
+                    JUMP_HEIGHT
+                };
+
+                player.velocity.y = -direction * jump_height * PHYSICS_TICK_RATE;
                 player.on_ground = false;
+                player.click_buffered = false;
+
+                player_events.send(PlayerEvent {
+                    kind: PlayerEventKind::Game(GameEvent::NormalJump),
+                    player2,
+                });
                 return;
             }
         } else {
-            transform.angle -= (PI / (1.3 / 3.)) * time.delta_seconds();
-        }
-
-        if player.velocity.y < -GRAVITY * 2. {
-            player.on_ground = false;
+            transform.angle += direction * (PI / (1.3 / 3.)) * time.delta_seconds();
         }
 
-        player.velocity.y -= GRAVITY * 60. * 0.9 * time.delta_seconds();
-        player.velocity.y = player.velocity.y.max(-VELOCITY_LIMIT);
+        player.velocity.y += direction
+            * GRAVITY
+            * PHYSICS_TICK_RATE
+            * PHYSICS_TICK_RATE
+            * player.gravity_multiplier
+            * slowed_delta;
+        player.velocity.y = player.velocity.y.clamp(
+            -VELOCITY_LIMIT * PHYSICS_TICK_RATE,
+            VELOCITY_LIMIT * PHYSICS_TICK_RATE,
+        );
     }
 
     fn create_system_state(&self, world: &mut World) -> Box<dyn Any + Send + Sync> {
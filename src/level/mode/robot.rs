@@ -0,0 +1,113 @@
+use std::any::Any;
+
+use bevy::ecs::system::SystemState;
+use bevy::prelude::{Entity, EventWriter, Query, Res, World};
+use bevy::time::Time;
+
+use crate::level::event::{GameEvent, PlayerEvent, PlayerEventKind};
+use crate::level::mode::{GameMode, GRAVITY, PHYSICS_TICK_RATE};
+use crate::level::player::{Player, PlayerInput};
+use crate::level::portal::DualPlayer;
+use crate::level::transform::Transform2d;
+
+type RobotSystemParam = (
+    Res<'static, Time>,
+    Res<'static, PlayerInput>,
+    Query<
+        'static,
+        'static,
+        (
+            &'static mut Player,
+            &'static mut Transform2d,
+            Option<&'static DualPlayer>,
+        ),
+    >,
+    EventWriter<'static, PlayerEvent>,
+);
+
+#[derive(Default)]
+pub(crate) struct RobotMode {
+    /// Time spent boosting, `None` when the jump button was let go
+    boost: Option<f32>,
+}
+
+const JUMP_VELOCITY: f32 = 10.;
+const MINI_JUMP_VELOCITY: f32 = 8.;
+/// How long holding the button keeps the robot rising, in seconds
+const MAX_BOOST_TIME: f32 = 0.25;
+const VELOCITY_LIMIT: f32 = 15.;
+
+impl GameMode for RobotMode {
+    fn update(
+        &mut self,
+        world: &mut World,
+        player_entity: Entity,
+        system_state: &mut Box<dyn Any + Send + Sync>,
+    ) {
+        let system_state: &mut SystemState<RobotSystemParam> =
+            &mut *system_state.downcast_mut().unwrap();
+
+        let (time, input, mut player_query, mut player_events) = system_state.get_mut(world);
+
+        let Ok((mut player, mut transform, dual_player)) = player_query.get_mut(player_entity)
+        else {
+            return;
+        };
+
+        let player2 = dual_player.is_some();
+
+        let direction = player.gravity_direction();
+        let slowed_delta = time.delta_seconds() * 0.9;
+
+        // The robot doesn't tumble like the cube
+        transform.angle = 0.;
+
+        let jump_velocity = if player.mini {
+            MINI_JUMP_VELOCITY
+        } else {
+            JUMP_VELOCITY
+        };
+
+        if self.boost.is_none() && player.on_ground && input.pressed(player2) {
+            self.boost = Some(0.);
+            player.on_ground = false;
+            player.click_buffered = false;
+
+            player_events.send(PlayerEvent {
+                kind: PlayerEventKind::Game(GameEvent::RobotBoostStart),
+                player2,
+            });
+        }
+
+        if let Some(boost) = &mut self.boost {
+            *boost += time.delta_seconds();
+
+            if input.pressed(player2) && *boost <= MAX_BOOST_TIME {
+                player.velocity.y = -direction * jump_velocity * PHYSICS_TICK_RATE;
+                return;
+            }
+
+            self.boost = None;
+
+            player_events.send(PlayerEvent {
+                kind: PlayerEventKind::Game(GameEvent::RobotBoostStop),
+                player2,
+            });
+        }
+
+        player.velocity.y += direction
+            * GRAVITY
+            * PHYSICS_TICK_RATE
+            * PHYSICS_TICK_RATE
+            * player.gravity_multiplier
+            * slowed_delta;
+        player.velocity.y = player.velocity.y.clamp(
+            -VELOCITY_LIMIT * PHYSICS_TICK_RATE,
+            VELOCITY_LIMIT * PHYSICS_TICK_RATE,
+        );
+    }
+
+    fn create_system_state(&self, world: &mut World) -> Box<dyn Any + Send + Sync> {
+        Box::new(SystemState::<RobotSystemParam>::new(world))
+    }
+}
@@ -0,0 +1,129 @@
+use std::any::Any;
+use std::f32::consts::{PI, TAU};
+
+use bevy::ecs::system::SystemState;
+use bevy::prelude::{Entity, EventWriter, Query, Res, World};
+use bevy::time::Time;
+
+use crate::level::event::{GameEvent, PlayerEvent, PlayerEventKind};
+use crate::level::mode::{GameMode, GRAVITY, PHYSICS_TICK_RATE};
+use crate::level::player::{Player, PlayerInput};
+use crate::level::portal::DualPlayer;
+use crate::level::transform::Transform2d;
+
+type ShipSystemParam = (
+    Res<'static, Time>,
+    Res<'static, PlayerInput>,
+    Query<
+        'static,
+        'static,
+        (
+            &'static mut Player,
+            &'static mut Transform2d,
+            Option<&'static DualPlayer>,
+        ),
+    >,
+    EventWriter<'static, PlayerEvent>,
+);
+
+#[derive(Default)]
+pub(crate) struct ShipMode {
+    boosting: bool,
+}
+
+/// Acceleration while falling, relative to the gravity
+const FALL_FACTOR: f32 = 0.4;
+/// Acceleration while holding, relative to the gravity
+const BOOST_FACTOR: f32 = 0.5;
+/// The mini ship reacts faster in both directions
+const MINI_FACTOR: f32 = 1.176;
+const UP_VELOCITY_LIMIT: f32 = 8.;
+const DOWN_VELOCITY_LIMIT: f32 = 6.4;
+/// How quickly the rotation catches up with the direction of travel
+const ROTATION_SPEED: f32 = 15.;
+
+impl GameMode for ShipMode {
+    fn update(
+        &mut self,
+        world: &mut World,
+        player_entity: Entity,
+        system_state: &mut Box<dyn Any + Send + Sync>,
+    ) {
+        let system_state: &mut SystemState<ShipSystemParam> =
+            &mut *system_state.downcast_mut().unwrap();
+
+        let (time, input, mut player_query, mut player_events) = system_state.get_mut(world);
+
+        let Ok((mut player, mut transform, dual_player)) = player_query.get_mut(player_entity)
+        else {
+            return;
+        };
+
+        let player2 = dual_player.is_some();
+
+        let direction = player.gravity_direction();
+        let slowed_delta = time.delta_seconds() * 0.9;
+
+        let boosting = input.pressed(player2);
+        if boosting != self.boosting {
+            self.boosting = boosting;
+
+            let game_event = if boosting {
+                GameEvent::ShipBoostStart
+            } else {
+                GameEvent::ShipBoostEnd
+            };
+
+            player_events.send(PlayerEvent {
+                kind: PlayerEventKind::Game(game_event),
+                player2,
+            });
+        }
+
+        let mut acceleration = if boosting {
+            -direction * BOOST_FACTOR
+        } else {
+            direction * FALL_FACTOR
+        };
+
+        if player.mini {
+            acceleration *= MINI_FACTOR;
+        }
+
+        player.velocity.y += acceleration
+            * GRAVITY
+            * PHYSICS_TICK_RATE
+            * PHYSICS_TICK_RATE
+            * player.gravity_multiplier
+            * slowed_delta;
+
+        // The limits are flipped along with gravity
+        let (up_limit, down_limit) = if player.upside_down {
+            (DOWN_VELOCITY_LIMIT, UP_VELOCITY_LIMIT)
+        } else {
+            (UP_VELOCITY_LIMIT, DOWN_VELOCITY_LIMIT)
+        };
+
+        player.velocity.y = player.velocity.y.clamp(
+            -down_limit * PHYSICS_TICK_RATE,
+            up_limit * PHYSICS_TICK_RATE,
+        );
+
+        // Point the ship in the direction it is travelling
+        let forward = player.velocity.x * player.speed;
+        let vertical = player.velocity.y * 0.9;
+        let mut target = vertical.atan2(forward);
+
+        if player.reverse {
+            target = -target;
+        }
+
+        let difference = (target - transform.angle + PI).rem_euclid(TAU) - PI;
+        let blend = (ROTATION_SPEED * time.delta_seconds()).min(1.);
+        transform.angle += difference * blend;
+    }
+
+    fn create_system_state(&self, world: &mut World) -> Box<dyn Any + Send + Sync> {
+        Box::new(SystemState::<ShipSystemParam>::new(world))
+    }
+}
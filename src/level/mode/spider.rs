@@ -0,0 +1,108 @@
+use std::any::Any;
+
+use bevy::ecs::system::SystemState;
+use bevy::prelude::{Entity, EventWriter, Query, Res, With, World};
+use bevy::time::Time;
+
+use crate::level::collision::{GlobalHitbox, HitboxCategory};
+use crate::level::color::ObjectColorCalculated;
+use crate::level::event::{GameEvent, PlayerEvent, PlayerEventKind};
+use crate::level::mode::{GameMode, GRAVITY, PHYSICS_TICK_RATE};
+use crate::level::player::{teleport_to_opposite_surface, Player, SolidFilter};
+use crate::level::portal::DualPlayer;
+use crate::level::section::GlobalSections;
+use crate::level::transform::Transform2d;
+
+type SpiderSystemParam = (
+    Res<'static, Time>,
+    Res<'static, GlobalSections>,
+    Query<
+        'static,
+        'static,
+        (
+            &'static mut Player,
+            &'static mut Transform2d,
+            Option<&'static DualPlayer>,
+        ),
+    >,
+    Query<'static, 'static, &'static GlobalHitbox, With<Player>>,
+    Query<
+        'static,
+        'static,
+        (
+            &'static HitboxCategory,
+            &'static GlobalHitbox,
+            Option<&'static ObjectColorCalculated>,
+        ),
+        SolidFilter,
+    >,
+    EventWriter<'static, PlayerEvent>,
+);
+
+#[derive(Default)]
+pub(crate) struct SpiderMode;
+
+/// Acceleration while falling, relative to the gravity
+const FALL_FACTOR: f32 = 0.6;
+const VELOCITY_LIMIT: f32 = 15.;
+
+impl GameMode for SpiderMode {
+    fn update(
+        &mut self,
+        world: &mut World,
+        player_entity: Entity,
+        system_state: &mut Box<dyn Any + Send + Sync>,
+    ) {
+        let system_state: &mut SystemState<SpiderSystemParam> =
+            &mut *system_state.downcast_mut().unwrap();
+
+        let (time, global_sections, mut player_query, player_hitboxes, solids, mut player_events) =
+            system_state.get_mut(world);
+
+        let Ok((mut player, mut transform, dual_player)) = player_query.get_mut(player_entity)
+        else {
+            return;
+        };
+
+        let slowed_delta = time.delta_seconds() * 0.9;
+
+        transform.angle = 0.;
+
+        if player.on_ground && player.click_buffered {
+            player.click_buffered = false;
+
+            if let Ok(player_hitbox) = player_hitboxes.get(player_entity) {
+                teleport_to_opposite_surface(
+                    &mut player,
+                    &mut transform,
+                    player_hitbox,
+                    &global_sections,
+                    &solids,
+                );
+            }
+
+            player_events.send(PlayerEvent {
+                kind: PlayerEventKind::Game(GameEvent::SpiderTeleport),
+                player2: dual_player.is_some(),
+            });
+        }
+
+        let direction = player.gravity_direction();
+
+        player.velocity.y += direction
+            * FALL_FACTOR
+            * GRAVITY
+            * PHYSICS_TICK_RATE
+            * PHYSICS_TICK_RATE
+            * player.gravity_multiplier
+            * slowed_delta;
+        player.velocity.y = player.velocity.y.clamp(
+            -VELOCITY_LIMIT * PHYSICS_TICK_RATE,
+            VELOCITY_LIMIT * PHYSICS_TICK_RATE,
+        );
+    }
+
+    fn create_system_state(&self, world: &mut World) -> Box<dyn Any + Send + Sync> {
+        Box::new(SystemState::<SpiderSystemParam>::new(world))
+    }
+}
@@ -0,0 +1,108 @@
+use std::any::Any;
+use std::f32::consts::{PI, TAU};
+
+use bevy::ecs::system::SystemState;
+use bevy::prelude::{Entity, EventWriter, Query, Res, World};
+use bevy::time::Time;
+
+use crate::level::event::{GameEvent, PlayerEvent, PlayerEventKind};
+use crate::level::mode::{GameMode, GRAVITY, PHYSICS_TICK_RATE};
+use crate::level::player::{Player, PlayerInput};
+use crate::level::portal::DualPlayer;
+use crate::level::transform::Transform2d;
+
+type UfoSystemParam = (
+    Res<'static, Time>,
+    Res<'static, PlayerInput>,
+    Query<
+        'static,
+        'static,
+        (
+            &'static mut Player,
+            &'static mut Transform2d,
+            Option<&'static DualPlayer>,
+        ),
+    >,
+    EventWriter<'static, PlayerEvent>,
+);
+
+#[derive(Default)]
+pub(crate) struct UfoMode;
+
+/// Acceleration while falling, relative to the gravity
+const FALL_FACTOR: f32 = 0.67;
+const MINI_FALL_FACTOR: f32 = 0.63;
+const JUMP_HEIGHT: f32 = 7.;
+const MINI_JUMP_HEIGHT: f32 = 5.6;
+const VELOCITY_LIMIT: f32 = 10.;
+/// How quickly the tilt settles back to level
+const ROTATION_SPEED: f32 = 10.;
+
+impl GameMode for UfoMode {
+    fn update(
+        &mut self,
+        world: &mut World,
+        player_entity: Entity,
+        system_state: &mut Box<dyn Any + Send + Sync>,
+    ) {
+        let system_state: &mut SystemState<UfoSystemParam> =
+            &mut *system_state.downcast_mut().unwrap();
+
+        let (time, input, mut player_query, mut player_events) = system_state.get_mut(world);
+
+        let Ok((mut player, mut transform, dual_player)) = player_query.get_mut(player_entity)
+        else {
+            return;
+        };
+
+        let player2 = dual_player.is_some();
+
+        let direction = player.gravity_direction();
+        let slowed_delta = time.delta_seconds() * 0.9;
+
+        // Every click flaps, no matter if the ufo is on the ground or not
+        if input.just_pressed(player2) && player.click_buffered {
+            let jump_height = if player.mini {
+                MINI_JUMP_HEIGHT
+            } else {
+                JUMP_HEIGHT
+            };
+
+            player.velocity.y = -direction * jump_height * PHYSICS_TICK_RATE;
+            player.on_ground = false;
+            player.click_buffered = false;
+
+            player_events.send(PlayerEvent {
+                kind: PlayerEventKind::Game(GameEvent::UfoJump),
+                player2,
+            });
+        } else {
+            let fall_factor = if player.mini {
+                MINI_FALL_FACTOR
+            } else {
+                FALL_FACTOR
+            };
+
+            player.velocity.y += direction
+                * fall_factor
+                * GRAVITY
+                * PHYSICS_TICK_RATE
+                * PHYSICS_TICK_RATE
+                * player.gravity_multiplier
+                * slowed_delta;
+        }
+
+        player.velocity.y = player.velocity.y.clamp(
+            -VELOCITY_LIMIT * PHYSICS_TICK_RATE,
+            VELOCITY_LIMIT * PHYSICS_TICK_RATE,
+        );
+
+        let difference = (PI - transform.angle).rem_euclid(TAU) - PI;
+        let blend = (ROTATION_SPEED * time.delta_seconds()).min(1.);
+        transform.angle += difference * blend;
+    }
+
+    fn create_system_state(&self, world: &mut World) -> Box<dyn Any + Send + Sync> {
+        Box::new(SystemState::<UfoSystemParam>::new(world))
+    }
+}
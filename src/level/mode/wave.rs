@@ -0,0 +1,140 @@
+use std::any::Any;
+
+use bevy::ecs::system::SystemState;
+use bevy::math::{Vec2, Vec3Swizzles};
+use bevy::prelude::{Component, Entity, EventWriter, Query, Res, World};
+
+use crate::level::event::{GameEvent, PlayerEvent, PlayerEventKind};
+use crate::level::mode::GameMode;
+use crate::level::player::{Player, PlayerInput};
+use crate::level::portal::DualPlayer;
+use crate::level::transform::Transform2d;
+
+type WaveSystemParam = (
+    Res<'static, PlayerInput>,
+    Query<
+        'static,
+        'static,
+        (
+            &'static mut Player,
+            &'static mut Transform2d,
+            &'static mut WaveTrail,
+            Option<&'static DualPlayer>,
+        ),
+    >,
+    EventWriter<'static, PlayerEvent>,
+);
+
+/// Corners of the trail left behind by the wave
+#[derive(Component, Default)]
+pub(crate) struct WaveTrail {
+    pub(crate) points: Vec<Vec2>,
+}
+
+/// How far behind the player the trail is kept
+const TRAIL_LENGTH: f32 = 1000.;
+
+impl WaveTrail {
+    fn push(&mut self, point: Vec2) {
+        // Only keep the corners, so the trail stays sharp
+        if let [.., before, last] = &self.points[..] {
+            let previous_direction = (*last - *before).normalize_or_zero();
+            let direction = (point - *last).normalize_or_zero();
+
+            if previous_direction.perp_dot(direction).abs() < 0.001
+                && previous_direction.dot(direction) >= 0.
+            {
+                *self.points.last_mut().unwrap() = point;
+                return;
+            }
+        }
+
+        self.points.push(point);
+
+        let cutoff = self
+            .points
+            .iter()
+            .position(|corner| (point.x - corner.x).abs() < TRAIL_LENGTH)
+            .unwrap_or_default()
+            .saturating_sub(1);
+        self.points.drain(..cutoff);
+    }
+}
+
+#[derive(Default)]
+pub(crate) struct WaveMode {
+    started: bool,
+    rising: bool,
+}
+
+/// The mini wave travels at twice the angle
+const MINI_SLOPE: f32 = 2.;
+
+impl GameMode for WaveMode {
+    fn update(
+        &mut self,
+        world: &mut World,
+        player_entity: Entity,
+        system_state: &mut Box<dyn Any + Send + Sync>,
+    ) {
+        let system_state: &mut SystemState<WaveSystemParam> =
+            &mut *system_state.downcast_mut().unwrap();
+
+        let (input, mut player_query, mut player_events) = system_state.get_mut(world);
+
+        let Ok((mut player, mut transform, mut wave_trail, dual_player)) =
+            player_query.get_mut(player_entity)
+        else {
+            return;
+        };
+
+        let player2 = dual_player.is_some();
+
+        if !self.started {
+            self.started = true;
+            wave_trail.points.clear();
+        }
+
+        let rising = input.pressed(player2);
+        if rising != self.rising {
+            self.rising = rising;
+
+            let game_event = if rising {
+                GameEvent::WavePush
+            } else {
+                GameEvent::WaveRelease
+            };
+
+            player_events.send(PlayerEvent {
+                kind: PlayerEventKind::Game(game_event),
+                player2,
+            });
+        }
+
+        let slope = if player.mini { MINI_SLOPE } else { 1. };
+        let direction = if rising {
+            -player.gravity_direction()
+        } else {
+            player.gravity_direction()
+        };
+
+        // Vertical movement is slowed the same way as the rest of the physics
+        player.velocity.y = direction * slope * player.velocity.x * player.speed / 0.9;
+
+        transform.angle = if player.on_ground {
+            0.
+        } else {
+            (direction * slope).atan()
+        };
+
+        if player.reverse {
+            transform.angle = -transform.angle;
+        }
+
+        wave_trail.push(transform.translation.xy());
+    }
+
+    fn create_system_state(&self, world: &mut World) -> Box<dyn Any + Send + Sync> {
+        Box::new(SystemState::<WaveSystemParam>::new(world))
+    }
+}
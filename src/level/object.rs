@@ -2,13 +2,13 @@ use bevy::asset::Handle;
 use bevy::hierarchy::BuildWorldChildren;
 use bevy::log::debug;
 use bevy::math::{Vec2, Vec3, Vec3Swizzles};
-use bevy::prelude::{Component, Entity, World};
+use bevy::prelude::{Component, Entity, Image, World};
 use bevy::utils::default;
 use indexmap::{IndexMap, IndexSet};
 
 use crate::asset::cocos2d_atlas::{Cocos2dFrame, Cocos2dFrames};
-use crate::level::animation::insert_animation_data;
-use crate::level::collision::{GlobalHitbox, Hitbox};
+use crate::level::animation::{insert_animation_data, FrameAnimation};
+use crate::level::collision::{GlobalHitbox, Hitbox, HitboxCategory};
 use crate::level::color::{GlobalColorChannels, HsvMod, ObjectColorCalculated};
 use crate::level::color::{ObjectColor, ObjectColorKind};
 use crate::level::de;
@@ -27,6 +27,7 @@ struct ObjectDefaultData {
     swap_base_detail: bool,
     opacity: f32,
     hitbox: Option<HitboxData>,
+    hitbox_category: HitboxCategory,
     children: &'static [ObjectChild],
 }
 
@@ -41,6 +42,7 @@ impl ObjectDefaultData {
         swap_base_detail: false,
         opacity: 1.,
         hitbox: None,
+        hitbox_category: HitboxCategory::Solid,
         children: &[],
     };
 }
@@ -57,6 +59,7 @@ impl Default for ObjectDefaultData {
             swap_base_detail: false,
             opacity: 1.,
             hitbox: None,
+            hitbox_category: HitboxCategory::Solid,
             children: &[],
         }
     }
@@ -293,12 +296,18 @@ pub(crate) fn spawn_object(
                 entity.insert(Hitbox::Circle { radius });
             }
         }
-        entity.insert(GlobalHitbox::default());
+        entity.insert((object_default_data.hitbox_category, GlobalHitbox::default()));
     }
 
     insert_trigger_data(&mut entity, object_id, object_data)?;
 
-    insert_animation_data(&mut entity, object_id, object_data)?;
+    insert_animation_data(
+        &mut entity,
+        object_id,
+        object_data,
+        object_default_data.texture,
+        cocos2d_frames,
+    )?;
 
     let entity = entity.id();
 
@@ -342,6 +351,8 @@ pub(crate) fn spawn_object(
         &mut spawned,
     )?;
 
+    spawn_animation_parts(world, entity, global_section, &mut spawned);
+
     for group in groups {
         let (root_entities, entities) = global_groups.entry(group).or_default();
         root_entities.push(entity);
@@ -353,6 +364,79 @@ pub(crate) fn spawn_object(
     Ok(entity)
 }
 
+/// Spawns the entities the frames of a [`FrameAnimation`] are drawn with
+fn spawn_animation_parts(
+    world: &mut World,
+    entity: Entity,
+    global_section: &mut IndexSet<Entity, U64Hash>,
+    spawned: &mut Vec<Entity>,
+) {
+    let mut entity_world_mut = world.entity_mut(entity);
+
+    let Some(part_count) = entity_world_mut
+        .get::<FrameAnimation>()
+        .map(|frame_animation| frame_animation.part_count())
+    else {
+        return;
+    };
+
+    // The object's own texture is only a preview of the animation
+    let visible = entity_world_mut.take::<Handle<Image>>().is_some();
+
+    let object = entity_world_mut.get::<Object>().unwrap().clone();
+    let object_color = entity_world_mut.get::<ObjectColor>().unwrap();
+    let channel_id = object_color.channel_id;
+    let channel_entity = object_color.channel_entity;
+    let hsv = object_color.hsv;
+    let object_opacity = object_color.object_opacity;
+    let object_color_kind = object_color.object_color_kind;
+
+    let mut parts = Vec::with_capacity(part_count);
+
+    for _ in 0..part_count {
+        let mut part_entity = world.spawn((
+            Object {
+                anchor: Vec2::ZERO,
+                ..object.clone()
+            },
+            ObjectColor {
+                channel_id,
+                channel_entity,
+                hsv,
+                object_opacity,
+                object_color_kind,
+                ..default()
+            },
+            ObjectColorCalculated::default(),
+            Section::default(),
+            Transform2d {
+                scale: Vec2::ZERO,
+                ..default()
+            },
+            GlobalTransform2d::default(),
+        ));
+
+        if visible {
+            part_entity.insert(Handle::<Image>::default());
+        }
+
+        let part_entity = part_entity.id();
+
+        world.entity_mut(entity).add_child(part_entity);
+
+        global_section.insert(part_entity);
+
+        spawned.push(part_entity);
+        parts.push(part_entity);
+    }
+
+    world
+        .entity_mut(entity)
+        .get_mut::<FrameAnimation>()
+        .unwrap()
+        .parts = parts;
+}
+
 fn recursive_spawn_children(
     world: &mut World,
     object_id: u64,
@@ -0,0 +1,290 @@
+use std::f32::consts::FRAC_PI_2;
+
+use bevy::prelude::{Component, EventWriter, Query, Res, ResMut, Without};
+
+use crate::level::collision::{ActiveCollider, GlobalHitbox, HitboxCategory};
+use crate::level::color::ObjectColorCalculated;
+use crate::level::event::{GameEvent, PlayerEvent, PlayerEventKind};
+use crate::level::group::{GlobalGroup, GlobalGroups};
+use crate::level::mode::{GameModeKind, PlayerMode, PHYSICS_TICK_RATE};
+use crate::level::player::{teleport_to_opposite_surface, Player, SolidFilter};
+use crate::level::portal::DualPlayer;
+use crate::level::section::GlobalSections;
+use crate::level::transform::Transform2d;
+use crate::level::trigger::teleport::{group_position, Teleports};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum OrbKind {
+    Yellow,
+    Pink,
+    Red,
+    Gravity,
+    Green,
+    Black,
+    Spider,
+    Teleport { target_group: u64 },
+    Toggle { target_group: u64, activate: bool },
+    Dash,
+    GravityDash,
+}
+
+#[derive(Component)]
+pub(crate) struct Orb {
+    pub(crate) kind: OrbKind,
+    pub(crate) multi_activate: bool,
+    pub(crate) used: bool,
+}
+
+impl Orb {
+    pub(crate) fn new(kind: OrbKind) -> Self {
+        Self {
+            kind,
+            multi_activate: false,
+            used: false,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum PadKind {
+    Yellow,
+    Pink,
+    Red,
+    Gravity,
+    Spider,
+}
+
+#[derive(Component)]
+pub(crate) struct Pad {
+    pub(crate) kind: PadKind,
+    pub(crate) used: bool,
+}
+
+impl Pad {
+    pub(crate) fn new(kind: PadKind) -> Self {
+        Self { kind, used: false }
+    }
+}
+
+/// Velocity of a yellow orb for each gamemode, the other orbs and pads are relative to it
+fn base_velocity(kind: GameModeKind) -> f32 {
+    match kind {
+        GameModeKind::Cube => 11.2,
+        GameModeKind::Ship | GameModeKind::Ufo => 8.,
+        GameModeKind::Robot => 10.,
+        GameModeKind::Ball | GameModeKind::Spider | GameModeKind::Swing => 7.8,
+        // The wave ignores everything but gravity changes
+        GameModeKind::Wave => 0.,
+    }
+}
+
+impl OrbKind {
+    /// Velocity relative to the yellow orb, negative values push along gravity
+    fn factor(self) -> f32 {
+        match self {
+            OrbKind::Yellow | OrbKind::Green => 1.,
+            OrbKind::Pink => 0.72,
+            OrbKind::Red => 1.38,
+            OrbKind::Gravity => -0.4,
+            OrbKind::Black => -1.34,
+            _ => 0.,
+        }
+    }
+
+    fn game_event(self) -> GameEvent {
+        match self {
+            OrbKind::Yellow => GameEvent::YellowOrb,
+            OrbKind::Pink => GameEvent::PinkOrb,
+            OrbKind::Red => GameEvent::RedOrb,
+            OrbKind::Gravity => GameEvent::GravityOrb,
+            OrbKind::Green => GameEvent::GreenOrb,
+            OrbKind::Black => GameEvent::DropOrb,
+            OrbKind::Spider => GameEvent::SpiderOrb,
+            OrbKind::Teleport { .. } => GameEvent::TeleportOrb,
+            OrbKind::Toggle { .. } => GameEvent::CustomOrb,
+            OrbKind::Dash => GameEvent::DashOrb,
+            OrbKind::GravityDash => GameEvent::GravityDashOrb,
+        }
+    }
+}
+
+impl PadKind {
+    fn factor(self) -> f32 {
+        match self {
+            PadKind::Yellow => 1.43,
+            PadKind::Pink => 0.93,
+            PadKind::Red => 1.79,
+            PadKind::Gravity => -0.4,
+            PadKind::Spider => 0.,
+        }
+    }
+
+    fn game_event(self) -> GameEvent {
+        match self {
+            PadKind::Yellow => GameEvent::YellowPad,
+            PadKind::Pink => GameEvent::PinkPad,
+            PadKind::Red => GameEvent::RedPad,
+            PadKind::Gravity => GameEvent::GravityPad,
+            PadKind::Spider => GameEvent::SpiderPad,
+        }
+    }
+}
+
+/// Steepest angle a dash orb can send the player in
+const MAX_DASH_ANGLE: f32 = FRAC_PI_2 * 7. / 9.;
+
+fn send_event(player_events: &mut EventWriter<PlayerEvent>, game_event: GameEvent, player2: bool) {
+    player_events.send(PlayerEvent {
+        kind: PlayerEventKind::Game(game_event),
+        player2,
+    });
+}
+
+/// Sets the vertical velocity against gravity, scaled down for mini players
+fn launch(player: &mut Player, kind: GameModeKind, factor: f32) {
+    let mut velocity = base_velocity(kind) * factor;
+
+    if player.mini {
+        velocity *= 0.8;
+    }
+
+    player.velocity.y = -player.gravity_direction() * velocity * PHYSICS_TICK_RATE;
+    player.on_ground = false;
+}
+
+/// Activates the orbs and pads the player overlapped during the last collision check
+///
+/// This runs before the gamemodes, so a click on an orb takes priority over a jump
+pub(crate) fn update_orbs(
+    mut players: Query<(
+        &mut Player,
+        &mut Transform2d,
+        &PlayerMode,
+        &GlobalHitbox,
+        &ActiveCollider,
+        Option<&DualPlayer>,
+    )>,
+    mut orbs: Query<(&mut Orb, &Transform2d, Option<&ObjectColorCalculated>), Without<Player>>,
+    mut pads: Query<(&mut Pad, Option<&ObjectColorCalculated>)>,
+    solids: Query<
+        (
+            &HitboxCategory,
+            &GlobalHitbox,
+            Option<&ObjectColorCalculated>,
+        ),
+        SolidFilter,
+    >,
+    global_sections: Res<GlobalSections>,
+    global_groups: Res<GlobalGroups>,
+    mut groups: Query<&mut GlobalGroup>,
+    objects: Query<&Transform2d, Without<Player>>,
+    mut teleports: ResMut<Teleports>,
+    mut player_events: EventWriter<PlayerEvent>,
+) {
+    for (mut player, mut transform, player_mode, player_hitbox, active_collider, dual_player) in
+        &mut players
+    {
+        let kind = player_mode.kind;
+        let player2 = dual_player.is_some();
+
+        for (entity, ..) in &active_collider.collided {
+            if let Ok((mut pad, calculated)) = pads.get_mut(*entity) {
+                // Orbs and pads in disabled groups can't be used
+                if pad.used || calculated.is_some_and(|calculated| !calculated.enabled) {
+                    continue;
+                }
+                pad.used = true;
+
+                match pad.kind {
+                    PadKind::Spider => teleport_to_opposite_surface(
+                        &mut player,
+                        &mut transform,
+                        player_hitbox,
+                        &global_sections,
+                        &solids,
+                    ),
+                    PadKind::Gravity => {
+                        player.flip_gravity(player2, &mut player_events);
+                        launch(&mut player, kind, pad.kind.factor());
+                    }
+                    pad_kind => launch(&mut player, kind, pad_kind.factor()),
+                }
+
+                send_event(&mut player_events, GameEvent::PadActivated, player2);
+                send_event(&mut player_events, pad.kind.game_event(), player2);
+                continue;
+            }
+
+            let Ok((mut orb, orb_transform, calculated)) = orbs.get_mut(*entity) else {
+                continue;
+            };
+
+            if orb.used || calculated.is_some_and(|calculated| !calculated.enabled) {
+                continue;
+            }
+
+            // The click has to happen while overlapping, or be held from before reaching the orb
+            if !player.click_buffered {
+                continue;
+            }
+
+            player.click_buffered = false;
+            orb.used = !orb.multi_activate;
+
+            match orb.kind {
+                OrbKind::Gravity | OrbKind::Green => {
+                    player.flip_gravity(player2, &mut player_events);
+                    launch(&mut player, kind, orb.kind.factor());
+                }
+                OrbKind::Spider => teleport_to_opposite_surface(
+                    &mut player,
+                    &mut transform,
+                    player_hitbox,
+                    &global_sections,
+                    &solids,
+                ),
+                OrbKind::Teleport { target_group } => {
+                    if let Some(destination) = group_position(
+                        &global_groups,
+                        &groups.to_readonly(),
+                        &objects,
+                        target_group,
+                    ) {
+                        teleports.teleport(destination);
+                    }
+                }
+                OrbKind::Toggle {
+                    target_group,
+                    activate,
+                } => {
+                    if let Some(mut group) = global_groups
+                        .0
+                        .get(target_group as usize)
+                        .and_then(|group_entity| groups.get_mut(*group_entity).ok())
+                    {
+                        group.enabled = activate;
+                    }
+                }
+                OrbKind::Dash | OrbKind::GravityDash => {
+                    if orb.kind == OrbKind::GravityDash {
+                        player.flip_gravity(player2, &mut player_events);
+                    }
+
+                    // The orb points in the direction of the dash
+                    let angle = orb_transform.angle.clamp(-MAX_DASH_ANGLE, MAX_DASH_ANGLE);
+                    let forward = player.velocity.x * player.speed;
+
+                    player.dash = Some(forward * angle.tan() / 0.9);
+                    player.on_ground = false;
+                    transform.translation.y = orb_transform.translation.y;
+
+                    send_event(&mut player_events, GameEvent::DashStart, player2);
+                }
+                orb_kind => launch(&mut player, kind, orb_kind.factor()),
+            }
+
+            send_event(&mut player_events, GameEvent::OrbActivated, player2);
+            send_event(&mut player_events, orb.kind.game_event(), player2);
+        }
+    }
+}
@@ -1,49 +1,258 @@
 use bevy::math::{Vec2, Vec3Swizzles};
-use bevy::prelude::{Component, Query, Res};
+use bevy::prelude::{Component, Entity, EventWriter, Query, Res, ResMut, Resource, Without};
 use bevy::time::Time;
 
-use crate::level::transform::Transform2d;
-use crate::level::trigger::{GlobalTriggers, SpeedChange};
+use crate::level::collision::{
+    ActiveCollider, GlobalHitbox, GlobalHitboxKind, Hitbox, HitboxCategory,
+};
+use crate::level::color::ObjectColorCalculated;
+use crate::level::event::{GameEvent, PlayerEvent, PlayerEventKind};
+use crate::level::mode::PHYSICS_TICK_RATE;
+use crate::level::portal::DualPlayer;
+use crate::level::section::GlobalSections;
+use crate::level::transform::{GlobalTransform2d, Transform2d};
+use crate::level::trigger::collision::CollisionBlock;
+use crate::level::trigger::end::LevelEnd;
+use crate::level::trigger::settings::LevelSettings;
+use crate::level::trigger::{GlobalTriggers, SpeedChange, TouchActivate};
+use crate::utils::section_index_from_x;
 
-#[derive(Component)]
+#[derive(Clone, Component)]
 pub(crate) struct Player {
     pub(crate) last_translation: Vec2,
+    /// Distance travelled along the level, used as the clock for triggers and the song
+    pub(crate) distance: f32,
+    pub(crate) last_distance: f32,
     pub(crate) velocity: Vec2,
     pub(crate) vertical_is_x: bool,
     pub(crate) reverse: bool,
     pub(crate) speed: f32,
-    pub(crate) gravity: f32,
+    pub(crate) gravity_multiplier: f32,
+    pub(crate) upside_down: bool,
+    pub(crate) hidden: bool,
+    pub(crate) ghost_trail: bool,
+    /// Touching the surface gravity is pulling towards
+    pub(crate) on_ground: bool,
+    pub(crate) mini: bool,
+    /// Vertical bounds of the current gamemode, flying modes are limited to a corridor
+    pub(crate) floor: f32,
+    pub(crate) ceiling: f32,
+    /// A click that can still be used by an orb or a jump
+    pub(crate) click_buffered: bool,
+    /// Vertical velocity held while dashing
+    pub(crate) dash: Option<f32>,
+    /// Speed portal touched off the path, along with the speed change it overrides
+    pub(crate) speed_override: Option<(Entity, Entity)>,
 }
 
 impl Default for Player {
     fn default() -> Self {
         Self {
             last_translation: Vec2::ZERO,
+            distance: 0.,
+            last_distance: 0.,
             // velocity: Vec2::new(0., 0.),
             velocity: Vec2::new(5.77 * 60., 0.),
             vertical_is_x: false,
             reverse: false,
             speed: 0.9,
-            gravity: 0.,
+            gravity_multiplier: 1.,
+            upside_down: false,
+            hidden: false,
+            ghost_trail: false,
+            on_ground: false,
+            mini: false,
+            floor: 0.,
+            ceiling: f32::INFINITY,
+            click_buffered: false,
+            dash: None,
+            speed_override: None,
         }
     }
 }
 
-// pub(crate) fn update_player_velocity(mut players: Query<&mut Player>) {
-//     for mut player in &mut players {}
-// }
+impl Player {
+    /// Direction gravity is pulling the player in
+    #[inline]
+    pub(crate) fn gravity_direction(&self) -> f32 {
+        if self.upside_down {
+            1.
+        } else {
+            -1.
+        }
+    }
+
+    pub(crate) fn flip_gravity(
+        &mut self,
+        player2: bool,
+        player_events: &mut EventWriter<PlayerEvent>,
+    ) {
+        self.upside_down = !self.upside_down;
+        self.on_ground = false;
+
+        let game_event = if self.upside_down {
+            GameEvent::GravityInverted
+        } else {
+            GameEvent::GravityRestored
+        };
+
+        player_events.send(PlayerEvent {
+            kind: PlayerEventKind::Game(game_event),
+            player2,
+        });
+    }
+}
+
+#[derive(Clone, Copy, Default)]
+struct Button {
+    pressed: bool,
+    previous: bool,
+}
+
+/// State of the jump button of each player, forwarded from the main world every frame
+///
+/// The dual player is controlled by the second button, which follows the first one
+/// unless the level is in two player mode
+#[derive(Default, Resource)]
+pub(crate) struct PlayerInput {
+    buttons: [Button; 2],
+    pub(crate) two_player: bool,
+}
+
+impl PlayerInput {
+    pub(crate) fn set(&mut self, player2: bool, pressed: bool) {
+        let button = &mut self.buttons[player2 as usize];
+        button.previous = button.pressed;
+        button.pressed = pressed;
+    }
+
+    /// Sets both buttons from the input of the main world
+    pub(crate) fn set_both(&mut self, pressed: bool, pressed2: bool) {
+        self.set(false, pressed);
+        self.set(true, if self.two_player { pressed2 } else { pressed });
+    }
+
+    #[inline]
+    pub(crate) fn pressed(&self, player2: bool) -> bool {
+        self.buttons[player2 as usize].pressed
+    }
+
+    #[inline]
+    pub(crate) fn just_pressed(&self, player2: bool) -> bool {
+        let button = &self.buttons[player2 as usize];
+        button.pressed && !button.previous
+    }
+
+    #[inline]
+    pub(crate) fn just_released(&self, player2: bool) -> bool {
+        let button = &self.buttons[player2 as usize];
+        !button.pressed && button.previous
+    }
+
+    /// Lets go of the buttons without it counting as a press once they are held again
+    pub(crate) fn release(&mut self) {
+        for button in &mut self.buttons {
+            button.pressed = false;
+        }
+    }
+}
+
+/// Copies of the player left behind while the ghost trail is on
+#[derive(Component, Default)]
+pub(crate) struct GhostTrail {
+    /// Where each copy was left and how long ago
+    pub(crate) images: Vec<(Transform2d, f32)>,
+    since_last: f32,
+}
+
+/// How often the ghost trail leaves a copy behind, in seconds
+const GHOST_TRAIL_INTERVAL: f32 = 0.05;
+
+/// How long a copy of the ghost trail takes to fade out, in seconds
+pub(crate) const GHOST_TRAIL_FADE: f32 = 0.4;
+
+pub(crate) fn update_ghost_trail(
+    mut players: Query<(&Player, &Transform2d, &mut GhostTrail)>,
+    time: Res<Time>,
+) {
+    let delta = time.delta_seconds();
+
+    for (player, transform, mut ghost_trail) in &mut players {
+        for (_, age) in &mut ghost_trail.images {
+            *age += delta;
+        }
+        ghost_trail
+            .images
+            .retain(|(_, age)| *age < GHOST_TRAIL_FADE);
+
+        if !player.ghost_trail {
+            ghost_trail.since_last = GHOST_TRAIL_INTERVAL;
+            continue;
+        }
+
+        ghost_trail.since_last += delta;
+
+        if ghost_trail.since_last >= GHOST_TRAIL_INTERVAL {
+            ghost_trail.since_last = 0.;
+            ghost_trail.images.push((*transform, 0.));
+        }
+    }
+}
+
+pub(crate) fn update_player_input(
+    mut input: ResMut<PlayerInput>,
+    level_settings: Res<LevelSettings>,
+    mut players: Query<(&mut Player, Option<&DualPlayer>)>,
+    mut player_events: EventWriter<PlayerEvent>,
+) {
+    // Options triggers can take the controls away from the player
+    if level_settings.disable_controls {
+        input.release();
+    }
+
+    for (mut player, dual_player) in &mut players {
+        let player2 = dual_player.is_some();
+
+        let game_event = if input.just_pressed(player2) {
+            GameEvent::JumpPush
+        } else if input.just_released(player2) {
+            GameEvent::JumpRelease
+        } else {
+            continue;
+        };
+
+        player.click_buffered = input.pressed(player2);
+
+        player_events.send(PlayerEvent {
+            kind: PlayerEventKind::Game(game_event),
+            player2,
+        });
+    }
+}
 
 pub(crate) fn update_player_pos(
-    mut players: Query<(&mut Player, &mut Transform2d)>,
+    mut players: Query<(&mut Player, &mut Transform2d, &Hitbox, &mut GlobalHitbox)>,
     speed_changes: Query<&SpeedChange>,
     time: Res<Time>,
     triggers: Res<GlobalTriggers>,
+    level_end: Res<LevelEnd>,
 ) {
-    for (mut player, mut transform) in &mut players {
-        let (_, speed_data) = triggers
-            .speed_changes
-            .speed_data_at_pos(transform.translation.x);
-        let speed_change = speed_changes.get(speed_data.entity).unwrap();
+    if level_end.reached {
+        return;
+    }
+
+    for (mut player, mut transform, hitbox, mut global_hitbox) in &mut players {
+        let (_, speed_data) = triggers.speed_changes.speed_data_at_pos(player.distance);
+
+        // A touched portal is used until the player reaches the next speed change on the path
+        let speed_entity = match player.speed_override {
+            Some((portal, overridden)) if overridden == speed_data.entity => portal,
+            _ => {
+                player.speed_override = None;
+                speed_data.entity
+            }
+        };
+        let speed_change = speed_changes.get(speed_entity).unwrap();
         player.velocity.x = speed_change.forward_velocity;
         player.speed = speed_change.speed;
 
@@ -55,7 +264,310 @@ pub(crate) fn update_player_pos(
 
         let slowed_delta = time.delta_seconds() * 0.9;
 
-        transform.translation.x += player.velocity.x * time.delta_seconds() * player.speed;
-        transform.translation.y += player.velocity.y * slowed_delta;
+        let forward = player.velocity.x * time.delta_seconds() * player.speed;
+        let vertical = player.velocity.y * slowed_delta;
+        let direction = if player.reverse { -1. } else { 1. };
+
+        if player.vertical_is_x {
+            transform.translation.y += forward * direction;
+            transform.translation.x -= vertical * direction;
+        } else {
+            transform.translation.x += forward * direction;
+            transform.translation.y += vertical;
+        }
+
+        player.last_distance = player.distance;
+        player.distance += forward;
+
+        // Collision runs before the transform propagation, so the hitbox is updated right away
+        let global_transform = GlobalTransform2d::from(*transform);
+        *global_hitbox = GlobalHitbox::from((hitbox, &*transform, &global_transform));
+    }
+}
+
+/// Hitboxes the player can stand on, as long as their groups are enabled
+#[inline]
+pub(crate) fn is_solid(
+    category: &HitboxCategory,
+    calculated: Option<&ObjectColorCalculated>,
+) -> bool {
+    *category == HitboxCategory::Solid && calculated.map_or(true, |calculated| calculated.enabled)
+}
+
+pub(crate) type SolidFilter = (
+    Without<Player>,
+    Without<TouchActivate>,
+    Without<CollisionBlock>,
+);
+
+/// Moves the player onto the closest surface on the other side and flips its gravity,
+/// the way the spider does
+pub(crate) fn teleport_to_opposite_surface(
+    player: &mut Player,
+    transform: &mut Transform2d,
+    player_hitbox: &GlobalHitbox,
+    global_sections: &GlobalSections,
+    solids: &Query<
+        (
+            &HitboxCategory,
+            &GlobalHitbox,
+            Option<&ObjectColorCalculated>,
+        ),
+        SolidFilter,
+    >,
+) {
+    let min_x = player_hitbox.aabb.x;
+    let max_x = -player_hitbox.aabb.z;
+    let bottom = player_hitbox.aabb.y;
+    let top = -player_hitbox.aabb.w;
+    let half_height = (top - bottom) / 2.;
+
+    let mut target = if player.upside_down {
+        player.floor
+    } else {
+        player.ceiling
+    };
+
+    let section_index = section_index_from_x(transform.translation.x) as usize;
+    let end = section_index
+        .saturating_add(2)
+        .min(global_sections.sections.len());
+    let start = section_index.saturating_sub(1).min(end);
+
+    for section in &global_sections.sections[start..end] {
+        for (category, global_hitbox, calculated) in solids.iter_many(section) {
+            if !is_solid(category, calculated)
+                || global_hitbox.aabb.x > max_x
+                || -global_hitbox.aabb.z < min_x
+            {
+                continue;
+            }
+
+            if player.upside_down {
+                let surface = surface_height(global_hitbox, min_x, max_x, true);
+                if surface <= bottom && surface > target {
+                    target = surface;
+                }
+            } else {
+                let surface = surface_height(global_hitbox, min_x, max_x, false);
+                if surface >= top && surface < target {
+                    target = surface;
+                }
+            }
+        }
+    }
+
+    // Without anything to land on the player just falls the other way
+    if target.is_finite() {
+        transform.translation.y = if player.upside_down {
+            target + half_height
+        } else {
+            target - half_height
+        };
+    }
+
+    player.upside_down = !player.upside_down;
+    player.on_ground = false;
+    player.velocity.y = 0.;
+}
+
+/// How far below a surface the player can be and still be put on top of it,
+/// as slopes rise while the player moves into them
+const SNAP_DISTANCE: f32 = 10.;
+
+/// Height of the top or bottom of a hitbox within the given horizontal range
+pub(crate) fn surface_height(hitbox: &GlobalHitbox, min_x: f32, max_x: f32, top: bool) -> f32 {
+    let bottom = hitbox.aabb.y;
+    let top_y = -hitbox.aabb.w;
+
+    let Some(GlobalHitboxKind::Triangle { vertices }) = hitbox.kind() else {
+        return if top { top_y } else { bottom };
+    };
+
+    // The first and last vertex form the slope, the one in between is the right angle
+    let (start, end) = if vertices[0].x <= vertices[2].x {
+        (vertices[0], vertices[2])
+    } else {
+        (vertices[2], vertices[0])
+    };
+
+    let width = end.x - start.x;
+    let slope_at = |x: f32| {
+        if width <= f32::EPSILON {
+            return start.y.max(end.y);
+        }
+        let t = ((x - start.x) / width).clamp(0., 1.);
+        start.y + (end.y - start.y) * t
+    };
+
+    let corner_below = vertices[1].y < slope_at(vertices[1].x);
+
+    match (top, corner_below) {
+        (true, true) => slope_at(min_x).max(slope_at(max_x)),
+        (false, false) => slope_at(min_x).min(slope_at(max_x)),
+        (true, false) => top_y,
+        (false, true) => bottom,
+    }
+}
+
+/// Falling speeds, in units per tick, below which a landing counts as each kind of landing
+const LANDING_SPEEDS: [(f32, GameEvent); 4] = [
+    (2., GameEvent::TinyLanding),
+    (5., GameEvent::FeatherLanding),
+    (9., GameEvent::SoftLanding),
+    (13., GameEvent::NormalLanding),
+];
+
+fn landing_event(falling_speed: f32) -> GameEvent {
+    LANDING_SPEEDS
+        .iter()
+        .find(|(speed, _)| falling_speed < *speed)
+        .map_or(GameEvent::HardLanding, |(_, game_event)| *game_event)
+}
+
+/// Position of the player against normal gravity, which is along the x axis in rotated gameplay
+fn height(player: &Player, translation: Vec2) -> f32 {
+    match (player.vertical_is_x, player.reverse) {
+        (false, _) => translation.y,
+        (true, false) => -translation.x,
+        (true, true) => translation.x,
+    }
+}
+
+/// Moves the player against normal gravity
+fn raise(player: &Player, transform: &mut Transform2d, amount: f32) {
+    match (player.vertical_is_x, player.reverse) {
+        (false, _) => transform.translation.y += amount,
+        (true, false) => transform.translation.x -= amount,
+        (true, true) => transform.translation.x += amount,
+    }
+}
+
+/// Puts the player on top of solids and keeps it inside the bounds of the current gamemode
+pub(crate) fn update_player_collision(
+    mut players: Query<(
+        &mut Player,
+        &mut Transform2d,
+        &Hitbox,
+        &mut GlobalHitbox,
+        &ActiveCollider,
+        Option<&DualPlayer>,
+    )>,
+    solids: Query<(&HitboxCategory, Option<&ObjectColorCalculated>), SolidFilter>,
+    mut player_events: EventWriter<PlayerEvent>,
+) {
+    for (mut player, mut transform, hitbox, mut global_hitbox, active_collider, dual_player) in
+        &mut players
+    {
+        let rotated = player.vertical_is_x;
+
+        let half_height = if rotated {
+            (-global_hitbox.aabb.z - global_hitbox.aabb.x) / 2.
+        } else {
+            (-global_hitbox.aabb.w - global_hitbox.aabb.y) / 2.
+        };
+        let previous_height = height(&player, player.last_translation);
+        let previous_bottom = previous_height - half_height;
+        let previous_top = previous_height + half_height;
+
+        let mut touched_floor = false;
+        let mut touched_ceiling = false;
+
+        // Speed in the direction of gravity before anything stops the player
+        let falling_speed = player.velocity.y * player.gravity_direction();
+        let was_on_ground = player.on_ground;
+
+        for (entity, other_hitbox, ..) in &active_collider.collided {
+            let Ok((category, calculated)) = solids.get(*entity) else {
+                continue;
+            };
+
+            if !is_solid(category, calculated) {
+                continue;
+            }
+
+            let current_height = height(&player, transform.translation.xy());
+            let bottom = current_height - half_height;
+            let top = current_height + half_height;
+
+            // Slopes are only followed in regular gameplay
+            let (surface_bottom, surface_top) = match (rotated, player.reverse) {
+                (false, _) => {
+                    let min_x = global_hitbox.aabb.x;
+                    let max_x = -global_hitbox.aabb.z;
+                    (
+                        surface_height(other_hitbox, min_x, max_x, false),
+                        surface_height(other_hitbox, min_x, max_x, true),
+                    )
+                }
+                (true, false) => (other_hitbox.aabb.z, -other_hitbox.aabb.x),
+                (true, true) => (other_hitbox.aabb.x, -other_hitbox.aabb.z),
+            };
+
+            if bottom < surface_top
+                && previous_bottom >= surface_top - SNAP_DISTANCE
+                && player.velocity.y <= 0.
+            {
+                raise(&player, &mut transform, surface_top - bottom);
+                player.velocity.y = 0.;
+                touched_floor = true;
+            } else if top > surface_bottom
+                && previous_top <= surface_bottom + SNAP_DISTANCE
+                && player.velocity.y >= 0.
+            {
+                raise(&player, &mut transform, surface_bottom - top);
+                player.velocity.y = 0.;
+                touched_ceiling = true;
+            }
+        }
+
+        // The floor and ceiling are horizontal, so they don't apply to rotated gameplay
+        if !rotated {
+            let bottom = transform.translation.y - half_height;
+            let top = transform.translation.y + half_height;
+
+            if bottom <= player.floor {
+                transform.translation.y += player.floor - bottom;
+                player.velocity.y = player.velocity.y.max(0.);
+                touched_floor = true;
+            } else if top >= player.ceiling {
+                transform.translation.y -= top - player.ceiling;
+                player.velocity.y = player.velocity.y.min(0.);
+                touched_ceiling = true;
+            }
+        }
+
+        let touched_head = if player.upside_down {
+            touched_floor
+        } else {
+            touched_ceiling
+        };
+
+        player.on_ground = if player.upside_down {
+            touched_ceiling
+        } else {
+            touched_floor
+        };
+
+        let player2 = dual_player.is_some();
+
+        if player.on_ground && !was_on_ground && falling_speed > 0. {
+            player_events.send(PlayerEvent {
+                kind: PlayerEventKind::Game(landing_event(falling_speed / PHYSICS_TICK_RATE)),
+                player2,
+            });
+        }
+
+        if touched_head && falling_speed < 0. {
+            player_events.send(PlayerEvent {
+                kind: PlayerEventKind::Game(GameEvent::HitHead),
+                player2,
+            });
+        }
+
+        if touched_floor || touched_ceiling {
+            let global_transform = GlobalTransform2d::from(*transform);
+            *global_hitbox = GlobalHitbox::from((hitbox, &*transform, &global_transform));
+        }
     }
 }
@@ -0,0 +1,218 @@
+use bevy::math::Vec2;
+use bevy::prelude::{
+    Bundle, Commands, Component, Entity, EventWriter, Query, Res, ResMut, With, Without,
+};
+
+use crate::level::collision::{ActiveCollider, GlobalHitbox};
+use crate::level::event::{GameEvent, PlayerEvent, PlayerEventKind};
+use crate::level::mode::wave::WaveTrail;
+use crate::level::mode::{GameModeKind, PlayerMode};
+use crate::level::player::{GhostTrail, Player};
+use crate::level::section::{GlobalSections, Section};
+use crate::level::transform::{GlobalTransform2d, Transform2d};
+use crate::level::trigger::camera::CameraSettings;
+use crate::level::trigger::{GlobalTriggers, SpeedChange};
+use crate::utils::section_index_from_x;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum GravityPortal {
+    Normal,
+    Flipped,
+    Toggle,
+}
+
+/// Portals that change the state of the player when touched
+#[derive(Clone, Copy, Component, Debug, PartialEq, Eq)]
+pub(crate) enum Portal {
+    Gravity(GravityPortal),
+    Mirror(bool),
+    Mini(bool),
+    Dual(bool),
+}
+
+/// Marks the second player spawned by a dual portal
+#[derive(Component)]
+pub(crate) struct DualPlayer;
+
+/// Portals the player is overlapping, so each one is only entered once
+#[derive(Component, Default)]
+pub(crate) struct PortalContacts(Vec<Entity>);
+
+/// Scale of the player and its hitbox in mini mode
+pub(crate) const MINI_SCALE: f32 = 0.6;
+
+/// Height of the corridor both players are kept in during dual mode
+const DUAL_HEIGHT: f32 = 300.;
+
+/// Components of the second player, spawned next to the first one
+pub(crate) fn dual_player_bundle(
+    player: Player,
+    transform: Transform2d,
+    kind: GameModeKind,
+    portal_contacts: Vec<Entity>,
+) -> impl Bundle {
+    (
+        player,
+        DualPlayer,
+        PlayerMode::new(kind),
+        WaveTrail::default(),
+        GhostTrail::default(),
+        PortalContacts(portal_contacts),
+        transform,
+        GlobalTransform2d::from(transform),
+        kind.hitbox(),
+        GlobalHitbox::default(),
+        ActiveCollider::default(),
+        Section::from_section_index(section_index_from_x(transform.translation.x)),
+    )
+}
+
+fn send_event(player_events: &mut EventWriter<PlayerEvent>, game_event: GameEvent, player2: bool) {
+    player_events.send(PlayerEvent {
+        kind: PlayerEventKind::Game(game_event),
+        player2,
+    });
+}
+
+pub(crate) fn apply_portals(
+    mut commands: Commands,
+    mut players: Query<(
+        &mut Player,
+        &mut Transform2d,
+        &mut PortalContacts,
+        &PlayerMode,
+        &ActiveCollider,
+        Option<&DualPlayer>,
+    )>,
+    portals: Query<(&Portal, &Transform2d), Without<Player>>,
+    speed_portals: Query<(), (With<SpeedChange>, Without<Player>)>,
+    dual_players: Query<(Entity, &Section), With<DualPlayer>>,
+    mut global_sections: ResMut<GlobalSections>,
+    mut camera_settings: ResMut<CameraSettings>,
+    triggers: Res<GlobalTriggers>,
+    mut player_events: EventWriter<PlayerEvent>,
+) {
+    for (
+        mut player,
+        mut transform,
+        mut portal_contacts,
+        player_mode,
+        active_collider,
+        dual_player,
+    ) in &mut players
+    {
+        let player2 = dual_player.is_some();
+
+        let touched: Vec<Entity> = active_collider
+            .collided
+            .iter()
+            .map(|(entity, ..)| *entity)
+            .filter(|entity| portals.contains(*entity) || speed_portals.contains(*entity))
+            .collect();
+
+        for entity in &touched {
+            if portal_contacts.0.contains(entity) {
+                continue;
+            }
+
+            if speed_portals.contains(*entity) {
+                let (_, speed_data) = triggers.speed_changes.speed_data_at_pos(player.distance);
+                player.speed_override = Some((*entity, speed_data.entity));
+                continue;
+            }
+
+            let Ok((portal, portal_transform)) = portals.get(*entity) else {
+                continue;
+            };
+
+            match *portal {
+                Portal::Gravity(gravity_portal) => {
+                    let upside_down = match gravity_portal {
+                        GravityPortal::Normal => false,
+                        GravityPortal::Flipped => true,
+                        GravityPortal::Toggle => !player.upside_down,
+                    };
+
+                    if upside_down != player.upside_down {
+                        player.flip_gravity(player2, &mut player_events);
+                        // Gravity portals give a small push in the new direction
+                        player.velocity.y *= 0.5;
+                    }
+
+                    let game_event = match gravity_portal {
+                        GravityPortal::Normal => GameEvent::PortalGravityNormal,
+                        GravityPortal::Flipped => GameEvent::PortalGravityInvert,
+                        GravityPortal::Toggle => GameEvent::PortalGravityFlip,
+                    };
+                    send_event(&mut player_events, game_event, player2);
+                }
+                Portal::Mirror(mirrored) => {
+                    camera_settings.mirrored = mirrored;
+
+                    let game_event = if mirrored {
+                        GameEvent::PortalFlip
+                    } else {
+                        GameEvent::PortalUnFlip
+                    };
+                    send_event(&mut player_events, game_event, player2);
+                }
+                Portal::Mini(mini) => {
+                    player.mini = mini;
+                    // The hitbox follows the scale of the player
+                    transform.scale = Vec2::splat(if mini { MINI_SCALE } else { 1. });
+
+                    let game_event = if mini {
+                        GameEvent::PortalMiniScale
+                    } else {
+                        GameEvent::PortalNormalScale
+                    };
+                    send_event(&mut player_events, game_event, player2);
+                }
+                Portal::Dual(enabled) => {
+                    if enabled && dual_players.is_empty() && !player2 {
+                        if player.ceiling.is_infinite() {
+                            let center = (portal_transform.translation.y / 30.).round() * 30.;
+                            player.floor = (center - DUAL_HEIGHT / 2.).max(0.);
+                            player.ceiling = player.floor + DUAL_HEIGHT;
+                        }
+
+                        let mut dual = Player {
+                            upside_down: !player.upside_down,
+                            on_ground: false,
+                            dash: None,
+                            ..player.clone()
+                        };
+                        dual.velocity.y = 0.;
+
+                        let dual_entity = commands
+                            .spawn(dual_player_bundle(
+                                dual,
+                                *transform,
+                                player_mode.kind,
+                                touched.clone(),
+                            ))
+                            .id();
+
+                        let section_index = section_index_from_x(transform.translation.x);
+                        global_sections.sections[section_index as usize].insert(dual_entity);
+                    } else if !enabled {
+                        for (dual_entity, section) in &dual_players {
+                            global_sections.sections[section.current as usize]
+                                .swap_remove(&dual_entity);
+                            commands.entity(dual_entity).despawn();
+                        }
+                    }
+
+                    let game_event = if enabled {
+                        GameEvent::PortalDualOn
+                    } else {
+                        GameEvent::PortalDualOff
+                    };
+                    send_event(&mut player_events, game_event, player2);
+                }
+            }
+        }
+
+        portal_contacts.0 = touched;
+    }
+}
@@ -0,0 +1,181 @@
+use std::fs::File;
+use std::io::{BufReader, BufWriter};
+use std::path::Path;
+
+use bevy::prelude::{ResMut, Resource, World};
+use serde::{Deserialize, Serialize};
+
+use crate::level::player::PlayerInput;
+use crate::level::trigger::random::LevelRng;
+
+/// Ticks the level world is stepped at per second
+pub(crate) const TICK_RATE: f64 = 240.;
+
+/// Most ticks run in a single frame, so a stall doesn't freeze the game catching up
+const MAX_TICKS_PER_FRAME: u32 = 60;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub(crate) struct ReplayInput {
+    pub(crate) tick: u64,
+    /// Whether this is the button of the dual player
+    pub(crate) player2: bool,
+    pub(crate) pressed: bool,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub(crate) struct Replay {
+    pub(crate) version: String,
+    pub(crate) level_id: Option<u64>,
+    pub(crate) level_hash: u64,
+    pub(crate) tick_rate: f64,
+    /// Whether the level was played in two player mode, missing in older replays
+    #[serde(default)]
+    pub(crate) two_player: bool,
+    pub(crate) inputs: Vec<ReplayInput>,
+}
+
+impl Replay {
+    pub(crate) fn save(&self, path: &Path) -> Result<(), anyhow::Error> {
+        serde_json::to_writer(BufWriter::new(File::create(path)?), self)?;
+        Ok(())
+    }
+
+    pub(crate) fn load(path: &Path) -> Result<Replay, anyhow::Error> {
+        Ok(serde_json::from_reader(BufReader::new(File::open(path)?))?)
+    }
+
+    /// Whether the replay was recorded on the same level data and mode at the same tick rate
+    pub(crate) fn matches(&self, replay_state: &ReplayState) -> bool {
+        self.level_hash == replay_state.level_hash
+            && self.tick_rate == TICK_RATE
+            && self.two_player == replay_state.two_player
+    }
+}
+
+/// Identifies the level the world plays, which also seeds the random triggers
+pub(crate) fn set_level(world: &mut World, level_id: Option<u64>, level_hash: u64) {
+    let mut replay_state = world.resource_mut::<ReplayState>();
+    replay_state.level_id = level_id;
+    replay_state.level_hash = level_hash;
+
+    world.insert_resource(LevelRng::new(level_hash));
+}
+
+/// FNV-1a, stable between builds unlike the std hasher
+pub(crate) fn level_hash(data: &str) -> u64 {
+    data.bytes().fold(0xcbf29ce484222325, |hash, byte| {
+        (hash ^ byte as u64).wrapping_mul(0x100000001b3)
+    })
+}
+
+/// Keeps the level world on fixed ticks and records the input of every tick
+#[derive(Default, Resource)]
+pub(crate) struct ReplayState {
+    /// Ticks run since the start of the current attempt
+    pub(crate) tick: u64,
+    accumulated: f64,
+    level_id: Option<u64>,
+    level_hash: u64,
+    /// Whether the level is played in two player mode
+    pub(crate) two_player: bool,
+    /// Presses and releases of the current attempt
+    recorded: Vec<ReplayInput>,
+    /// Replay whose inputs are used instead of the live input
+    pub(crate) playback: Option<Replay>,
+    playback_index: usize,
+    /// State of the button of each player in the replay being played back
+    playback_pressed: [bool; 2],
+}
+
+impl ReplayState {
+    /// Number of ticks to run for a frame that took `delta` seconds
+    pub(crate) fn ticks_for_frame(&mut self, delta: f64) -> u32 {
+        self.accumulated += delta * TICK_RATE;
+        let ticks = (self.accumulated as u32).min(MAX_TICKS_PER_FRAME);
+        self.accumulated = self.accumulated.fract();
+        ticks
+    }
+
+    /// Goes back to `tick`, dropping everything recorded after it
+    pub(crate) fn rewind(&mut self, tick: u64) {
+        self.tick = tick;
+        self.recorded.retain(|input| input.tick < tick);
+
+        if let Some(playback) = &self.playback {
+            self.playback_index = playback.inputs.partition_point(|input| input.tick < tick);
+            for (player2, pressed) in self.playback_pressed.iter_mut().enumerate() {
+                *pressed = playback.inputs[..self.playback_index]
+                    .iter()
+                    .rev()
+                    .find(|input| input.player2 == (player2 == 1))
+                    .is_some_and(|input| input.pressed);
+            }
+        }
+    }
+
+    pub(crate) fn start_playback(&mut self, replay: Replay) {
+        self.playback = Some(replay);
+        self.rewind(0);
+    }
+
+    pub(crate) fn replay(&self) -> Replay {
+        Replay {
+            version: env!("VERSION").to_string(),
+            level_id: self.level_id,
+            level_hash: self.level_hash,
+            tick_rate: TICK_RATE,
+            two_player: self.two_player,
+            inputs: self.recorded.clone(),
+        }
+    }
+
+    fn last_recorded(&self, player2: bool) -> bool {
+        self.recorded
+            .iter()
+            .rev()
+            .find(|input| input.player2 == player2)
+            .is_some_and(|input| input.pressed)
+    }
+}
+
+/// Feeds the replay being played back into the input and records any change to it
+pub(crate) fn update_replay(mut replay_state: ResMut<ReplayState>, mut input: ResMut<PlayerInput>) {
+    let replay_state = &mut *replay_state;
+    let tick = replay_state.tick;
+
+    if let Some(playback) = &replay_state.playback {
+        while let Some(replay_input) = playback.inputs.get(replay_state.playback_index) {
+            if replay_input.tick > tick {
+                break;
+            }
+            replay_state.playback_pressed[replay_input.player2 as usize] = replay_input.pressed;
+            replay_state.playback_index += 1;
+        }
+        let [pressed, mut pressed2] = replay_state.playback_pressed;
+        // A single player replay drives both players of a two player level, the second button
+        // of a two player replay is already ignored by a single player level
+        if !playback.two_player {
+            pressed2 = pressed;
+        }
+        input.set_both(pressed, pressed2);
+    }
+
+    // The second button only has its own state in two player mode
+    let players: &[bool] = if input.two_player {
+        &[false, true]
+    } else {
+        &[false]
+    };
+
+    for &player2 in players {
+        if input.pressed(player2) != replay_state.last_recorded(player2) {
+            replay_state.recorded.push(ReplayInput {
+                tick,
+                player2,
+                pressed: input.pressed(player2),
+            });
+        }
+    }
+
+    replay_state.tick += 1;
+}
@@ -63,6 +63,11 @@ impl From<Transform2d> for GlobalTransform2d {
 }
 
 impl GlobalTransform2d {
+    #[inline]
+    pub(crate) fn from_affine(affine: Affine2, z: f32) -> Self {
+        Self { affine, z }
+    }
+
     #[inline]
     pub(crate) fn mul_transform(&self, transform: Transform2d) -> Self {
         let rhs = GlobalTransform2d::from(transform);
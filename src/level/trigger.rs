@@ -3,8 +3,10 @@ use std::cell::UnsafeCell;
 use std::ops::Range;
 
 use bevy::ecs::system::SystemState;
-use bevy::math::Vec3A;
-use bevy::prelude::{Component, Entity, EntityWorldMut, Query, ResMut, Resource, With, World};
+use bevy::math::{Vec2, Vec3A, Vec4};
+use bevy::prelude::{
+    Component, Entity, EntityWorldMut, Events, Query, ResMut, Resource, With, Without, World,
+};
 use bevy::utils::syncunsafecell::SyncUnsafeCell;
 use bevy::utils::{default, hashbrown, HashMap as AHashMap};
 use dyn_clone::DynClone;
@@ -16,41 +18,102 @@ use ordered_float::OrderedFloat;
 use crate::level::collision::{ActiveCollider, GlobalHitbox, Hitbox};
 // use bevy::log::info_span;
 use crate::level::color::{ColorMod, HsvMod, ObjectColorCalculated};
+use crate::level::de;
 use crate::level::easing::Easing;
-use crate::level::group::ObjectGroups;
+use crate::level::event::PlayerEvent;
+use crate::level::group::{GlobalGroup, ObjectGroups};
+use crate::level::mode::{GameModeKind, GameModePortal};
+use crate::level::orb::{Orb, OrbKind, Pad, PadKind};
 use crate::level::player::Player;
+use crate::level::portal::{GravityPortal, Portal};
 use crate::level::transform::{GlobalTransform2d, Transform2d};
+use crate::level::trigger::advanced_follow::{
+    AdvancedFollowTrigger, AdvancedFollows, EditAdvancedFollowTrigger,
+};
 use crate::level::trigger::alpha::AlphaTrigger;
+use crate::level::trigger::animate::AnimateTrigger;
+use crate::level::trigger::area::{
+    AreaEditTrigger, AreaEffect, AreaEffectKind, AreaEffects, AreaStopTrigger, AreaTrigger,
+};
+use crate::level::trigger::audio::{
+    AudioQueue, SfxTrigger, SongTrigger, SoundAction, SoundSettings, SpatialSettings,
+};
+use crate::level::trigger::camera::{
+    CameraEdge, CameraEdgeTrigger, CameraOffsetTrigger, CameraRotateTrigger, CameraSettings,
+    CameraZoomTrigger, StaticCameraTrigger, UiTrigger,
+};
 use crate::level::trigger::collision::{CollisionBlock, CollisionTrigger};
 use crate::level::trigger::color::ColorTrigger;
-use crate::level::trigger::count::CountTrigger;
+use crate::level::trigger::count::{CountListeners, CountTrigger};
 use crate::level::trigger::empty::EmptyTrigger;
-use crate::level::trigger::follow::FollowTrigger;
+use crate::level::trigger::end::{EndTrigger, LevelEnd};
+use crate::level::trigger::enter_effect::{EnterEffect, EnterEffectTrigger, GlobalEnterEffect};
+use crate::level::trigger::event::{
+    EventTrigger, OnDeathTrigger, PlayerEventListeners, TouchToggleMode, TouchTrigger,
+};
+use crate::level::trigger::follow::{FollowPlayerYTrigger, FollowTrigger};
+use crate::level::trigger::gameplay::{
+    GameplayDirection, GhostTrailTrigger, GravityTrigger, PlayerControlTrigger,
+    PlayerVisibilityTrigger, ReverseTrigger, RotateGameplayTrigger, TimeWarpTrigger,
+};
+use crate::level::trigger::gradient::{GlobalGradients, GradientTrigger};
 use crate::level::trigger::instant_count::{InstantCountMode, InstantCountTrigger};
-use crate::level::trigger::pickup::{PickupTrigger, PickupValues};
+use crate::level::trigger::item_compare::{CompareOperator, ItemCompareTrigger, ItemOperand};
+use crate::level::trigger::item_edit::ItemEditTrigger;
+use crate::level::trigger::item_persist::ItemPersistTrigger;
+use crate::level::trigger::pickup::{
+    ItemKind, ItemOperator, PickupTrigger, PickupValues, RoundMode, SignMode,
+};
 use crate::level::trigger::pulse::PulseTrigger;
 use crate::level::trigger::r#move::MoveTrigger;
+use crate::level::trigger::random::{AdvancedRandomTrigger, RandomTrigger};
 use crate::level::trigger::rotate::RotateTrigger;
+use crate::level::trigger::scale::ScaleTrigger;
+use crate::level::trigger::sequence::{SequenceMode, SequenceStates, SequenceTrigger};
+use crate::level::trigger::settings::{BgEffectTrigger, LevelSettings, OptionsTrigger};
+use crate::level::trigger::shader::{ShaderCenter, ShaderEffect, ShaderState, ShaderTrigger};
 use crate::level::trigger::shake::{ShakeData, ShakeTrigger};
 use crate::level::trigger::spawn::SpawnTrigger;
-use crate::level::trigger::stop::StopTrigger;
+use crate::level::trigger::stop::{StopOption, StopTarget, StopTrigger};
+use crate::level::trigger::teleport::{TeleportTarget, TeleportTrigger, Teleports};
+use crate::level::trigger::time::{TimeControlTrigger, TimeEventTrigger, TimeTrigger, TimerStates};
 use crate::level::trigger::toggle::ToggleTrigger;
 use crate::utils::{str_to_bool, ObjectStorage, U64Hash};
 
+pub(crate) mod advanced_follow;
 mod alpha;
-mod collision;
+mod animate;
+pub(crate) mod area;
+pub(crate) mod audio;
+pub(crate) mod camera;
+pub(crate) mod collision;
 mod color;
-mod count;
+pub(crate) mod count;
 mod empty;
+pub(crate) mod end;
+pub(crate) mod enter_effect;
+pub(crate) mod event;
 mod follow;
+mod gameplay;
+pub(crate) mod gradient;
 mod instant_count;
+mod item_compare;
+mod item_edit;
+mod item_persist;
 mod r#move;
-mod pickup;
+pub(crate) mod pickup;
 mod pulse;
+pub(crate) mod random;
 mod rotate;
+mod scale;
+mod sequence;
+pub(crate) mod settings;
+pub(crate) mod shader;
 pub(crate) mod shake;
 mod spawn;
 mod stop;
+pub(crate) mod teleport;
+pub(crate) mod time;
 mod toggle;
 
 #[derive(Default, Resource)]
@@ -73,6 +136,8 @@ pub(crate) struct SpeedChangeData {
 pub(crate) struct SpeedChange {
     pub(crate) forward_velocity: f32,
     pub(crate) speed: f32,
+    /// Part of the speed timeline, otherwise it only applies when touched
+    pub(crate) on_path: bool,
 }
 
 impl SpeedChanges {
@@ -146,12 +211,13 @@ impl SpeedChanges {
 #[derive(Debug)]
 struct GlobalTriggerChannel {
     x: (IntervalSetGeneric<OrderedFloat<f32>>, Vec<Entity>),
-    // y: (IntervalSetGeneric<OrderedFloat<f32>>, Vec<Entity>),
+    y: (IntervalSetGeneric<OrderedFloat<f32>>, Vec<Entity>),
 }
 
-#[derive(Default, Component)]
+#[derive(Clone, Default, Component)]
 pub(crate) struct TriggerActivator {
     channel: u64,
+    started: bool,
 }
 
 #[derive(Component)]
@@ -169,6 +235,14 @@ pub(crate) struct MultiActivate;
 #[derive(Component)]
 pub(crate) struct Activated;
 
+/// Marks triggers that don't do anything yet, including ones that aren't known at all
+#[derive(Component)]
+pub(crate) struct UnsupportedTrigger(u64);
+
+/// How many of each unsupported trigger the level has, by object id
+#[derive(Default, Resource)]
+pub(crate) struct UnsupportedTriggers(pub(crate) IndexMap<u64, usize, U64Hash>);
+
 #[derive(Clone, Component)]
 pub(crate) struct Trigger(Box<dyn TriggerFunction>);
 
@@ -193,6 +267,9 @@ pub(crate) trait TriggerFunction: DynClone + Send + Sync + 'static {
 
     fn post(&self) -> bool;
 
+    /// Rewrites the group ids this trigger targets, used by spawn triggers with remapping
+    fn remap(&mut self, _: &GroupRemap) {}
+
     fn concrete_type_id(&self) -> TypeId {
         TypeId::of::<Self>()
     }
@@ -200,9 +277,80 @@ pub(crate) trait TriggerFunction: DynClone + Send + Sync + 'static {
 
 dyn_clone::clone_trait_object!(TriggerFunction);
 
+#[derive(Clone, Debug, Default)]
+pub(crate) struct GroupRemap(Vec<(u64, u64)>);
+
+impl GroupRemap {
+    pub(crate) fn parse(remap: &str) -> Result<GroupRemap, anyhow::Error> {
+        let ids = remap
+            .split('.')
+            .filter(|id| !id.is_empty())
+            .map(|id| id.parse())
+            .collect::<Result<Vec<u64>, _>>()?;
+
+        Ok(GroupRemap(
+            ids.chunks_exact(2).map(|pair| (pair[0], pair[1])).collect(),
+        ))
+    }
+
+    pub(crate) fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    pub(crate) fn get(&self, id: u64) -> u64 {
+        self.0
+            .iter()
+            .find(|(from, _)| *from == id)
+            .map(|(_, to)| *to)
+            .unwrap_or(id)
+    }
+
+    /// Remaps the targets of this remap by `parent` and carries over the pairs it doesn't override
+    pub(crate) fn inherit(&mut self, parent: &GroupRemap) {
+        for (_, to) in &mut self.0 {
+            *to = parent.get(*to);
+        }
+        for (from, to) in &parent.0 {
+            if self.0.iter().all(|(existing, _)| existing != from) {
+                self.0.push((*from, *to));
+            }
+        }
+    }
+}
+
+/// Lets stop triggers target triggers by a control id instead of their groups
+#[derive(Component)]
+pub(crate) struct ControlId(u64);
+
+/// A trigger that is currently running
+#[derive(Clone)]
+struct SpawnedTrigger {
+    entity: Entity,
+    trigger: Trigger,
+    groups: Vec<u64>,
+    control_id: Option<u64>,
+    range: Range<f32>,
+    /// Progress the trigger was last run with
+    progress: f32,
+    /// Position the trigger was paused at
+    paused_at: Option<f32>,
+}
+
+impl SpawnedTrigger {
+    fn is_targeted(&self, target: StopTarget) -> bool {
+        match target {
+            StopTarget::Group(group_id) => self.groups.contains(&group_id),
+            StopTarget::ControlId(control_id) => self.control_id == Some(control_id),
+        }
+    }
+}
+
 #[derive(Default, Resource)]
 pub(crate) struct TriggerData {
     stopped: IndexMap<u64, f32, U64Hash>,
+    stopped_control_ids: IndexMap<u64, f32, U64Hash>,
+    /// Pauses and resumes waiting to be applied to the running triggers
+    pause_changes: Vec<(StopTarget, f32, bool)>,
     data: AHashMap<
         TypeId,
         (
@@ -211,7 +359,51 @@ pub(crate) struct TriggerData {
         ),
     >,
     to_spawn: Vec<(Entity, Trigger, Vec<u64>, Range<f32>)>,
-    spawned: Vec<(Entity, Trigger, Vec<u64>, Range<f32>)>,
+    spawned: Vec<SpawnedTrigger>,
+}
+
+/// Progress of the running triggers, without the system states cached in [`TriggerData`]
+#[derive(Clone)]
+pub(crate) struct TriggerProgress {
+    stopped: IndexMap<u64, f32, U64Hash>,
+    stopped_control_ids: IndexMap<u64, f32, U64Hash>,
+    pause_changes: Vec<(StopTarget, f32, bool)>,
+    exclusive: Vec<(TypeId, hashbrown::HashMap<u64, f32, U64Hash>)>,
+    to_spawn: Vec<(Entity, Trigger, Vec<u64>, Range<f32>)>,
+    spawned: Vec<SpawnedTrigger>,
+}
+
+impl TriggerData {
+    pub(crate) fn progress(&self) -> TriggerProgress {
+        TriggerProgress {
+            stopped: self.stopped.clone(),
+            stopped_control_ids: self.stopped_control_ids.clone(),
+            pause_changes: self.pause_changes.clone(),
+            exclusive: self
+                .data
+                .iter()
+                .map(|(type_id, (exclusive_data, _))| (*type_id, exclusive_data.clone()))
+                .collect(),
+            to_spawn: self.to_spawn.clone(),
+            spawned: self.spawned.clone(),
+        }
+    }
+
+    pub(crate) fn restore_progress(&mut self, progress: &TriggerProgress) {
+        self.stopped.clone_from(&progress.stopped);
+        self.stopped_control_ids
+            .clone_from(&progress.stopped_control_ids);
+        self.pause_changes.clone_from(&progress.pause_changes);
+        self.to_spawn.clone_from(&progress.to_spawn);
+        self.spawned.clone_from(&progress.spawned);
+
+        for (type_id, (exclusive_data, _)) in &mut self.data {
+            match progress.exclusive.iter().find(|(id, _)| id == type_id) {
+                Some((_, saved)) => exclusive_data.clone_from(saved),
+                None => exclusive_data.clear(),
+            }
+        }
+    }
 }
 
 pub(crate) fn process_triggers(world: &mut World) {
@@ -221,15 +413,17 @@ pub(crate) fn process_triggers(world: &mut World) {
 
     let system_state: &mut SystemState<(
         ResMut<GlobalTriggers>,
-        Query<(&Player, &Transform2d, &TriggerActivator)>,
+        Query<(&Player, &Transform2d, &mut TriggerActivator)>,
         Query<(&Trigger, &ObjectGroups, &ObjectColorCalculated)>,
+        Query<&ControlId>,
     )> = if let Some((_, cell)) = trigger_data.data.get(&TypeId::of::<World>()) {
         unsafe { &mut *cell.get() }
     } else {
         let system_state: SystemState<(
             ResMut<GlobalTriggers>,
-            Query<(&Player, &Transform2d, &TriggerActivator)>,
+            Query<(&Player, &Transform2d, &mut TriggerActivator)>,
             Query<(&Trigger, &ObjectGroups, &ObjectColorCalculated)>,
+            Query<&ControlId>,
         )> = SystemState::new(unsafe { world_cell.world_mut() });
 
         trigger_data.data.insert(
@@ -247,53 +441,70 @@ pub(crate) fn process_triggers(world: &mut World) {
     .downcast_mut()
     .unwrap();
 
-    let (mut global_triggers, players, triggers) =
+    let (mut global_triggers, mut players, triggers, control_ids) =
         system_state.get_mut(unsafe { world_cell.world_mut() });
 
-    for (player, transform, trigger_activator) in &players {
-        let Some(global_trigger_channel) = global_triggers
-            .pos_triggers
-            .get_mut(&trigger_activator.channel)
+    for (player, transform, mut trigger_activator) in &mut players {
+        let Some(global_trigger_channel) =
+            global_triggers.pos_triggers.get(&trigger_activator.channel)
         else {
             continue;
         };
 
-        let mut last_translation = player.last_translation;
+        let (axis, (intervals, entities)) = if player.vertical_is_x {
+            (1, &global_trigger_channel.y)
+        } else {
+            (0, &global_trigger_channel.x)
+        };
 
-        if transform.translation.x == 0. {
-            last_translation.x = f32::NEG_INFINITY;
-        }
+        let direction = if player.reverse { -1. } else { 1. };
 
-        let activate_range =
-            OrderedFloat(last_translation.x)..OrderedFloat(transform.translation.x);
+        let position = transform.translation[axis];
+        let mut last_position = player.last_translation[axis];
 
-        // let span_a = info_span!("interval lookup");
-        // let span_b = info_span!("trigger");
-        //
-        // let query = span_a.in_scope(|| {
-        //     global_trigger_channel
-        //         .x
-        //         .0
-        //         .query_overlapping(&activate_range)
-        // });
+        if !trigger_activator.started {
+            // Everything placed before the start is activated right away
+            last_position = f32::NEG_INFINITY * direction;
+            trigger_activator.started = true;
+        }
 
-        let mut post_triggers = Vec::new();
+        // Offset from the position along the axis to the distance travelled, zero until the
+        // player turns around, changes axis or teleports
+        let distance_offset = player.distance - position * direction;
 
-        let query = global_trigger_channel
-            .x
-            .0
-            .query_overlapping(&activate_range);
+        let (low, high) = if direction > 0. {
+            (last_position, position)
+        } else {
+            (position, last_position)
+        };
 
+        let query = intervals
+            .query_overlapping(&(OrderedFloat(low)..OrderedFloat(high.next_after(f32::INFINITY))));
+
+        // Triggers are activated when the player crosses their start and then run on the
+        // distance travelled, like spawned triggers
         for (trigger_range, entity_indices) in query.iter() {
-            let trigger_range_length = trigger_range.end.0 - trigger_range.start.0;
-            let previous_progress =
-                ((last_translation.x - trigger_range.start.0) / trigger_range_length).clamp(0., 1.);
-            let current_progress = ((transform.translation.x - trigger_range.start.0)
-                / trigger_range_length)
-                .clamp(0., 1.);
+            let start = trigger_range.start.0;
+
+            let crossed = if direction > 0. {
+                last_position <= start && start < position
+            } else {
+                position < start && start <= last_position
+            };
+
+            if !crossed {
+                continue;
+            }
+
+            let range = if distance_offset == 0. && direction > 0. {
+                start..trigger_range.end.0
+            } else {
+                let distance_start = distance_offset + start * direction;
+                distance_start..distance_start + (trigger_range.end.0 - start)
+            };
 
-            'trigger_loop: for entity_index in entity_indices {
-                let trigger_entity = global_trigger_channel.x.1[*entity_index as usize];
+            for entity_index in entity_indices {
+                let trigger_entity = entities[*entity_index as usize];
 
                 let Ok((trigger, object_groups, object_color_calculated)) =
                     triggers.get(trigger_entity)
@@ -305,51 +516,57 @@ pub(crate) fn process_triggers(world: &mut World) {
                     continue;
                 }
 
-                for (stopped_group, stop_pos) in &trigger_data.stopped {
-                    if object_groups
-                        .groups
-                        .iter()
-                        .any(|group_id| group_id == stopped_group)
-                        && trigger_range.start.0 < *stop_pos
-                    {
-                        continue 'trigger_loop;
-                    }
-                }
-
-                let range = trigger_range.start.0..trigger_range.end.0;
+                trigger_data.to_spawn.push((
+                    trigger_entity,
+                    trigger.clone(),
+                    object_groups.groups.clone(),
+                    range.clone(),
+                ));
+            }
+        }
 
-                if trigger.0.post() {
-                    post_triggers.push((
-                        trigger.clone(),
-                        trigger_entity,
-                        previous_progress,
-                        current_progress,
-                        range,
-                    ));
-                    continue;
-                }
+        let mut post_triggers = Vec::new();
 
-                // Very unsafe but works for now
-                let world_mut = unsafe { world_cell.world_mut() };
+        let spawned: Vec<_> = trigger_data
+            .to_spawn
+            .drain(..)
+            .map(|(entity, trigger, groups, range)| {
+                let progress = ((player.last_distance - range.start) / (range.end - range.start))
+                    .clamp(0., 1.);
 
-                run_trigger(
+                SpawnedTrigger {
+                    entity,
                     trigger,
-                    world_mut,
-                    trigger_entity,
-                    previous_progress,
-                    current_progress,
+                    groups,
+                    control_id: control_ids.get(entity).ok().map(|control_id| control_id.0),
                     range,
-                    &mut trigger_data,
-                );
+                    progress,
+                    paused_at: None,
+                }
+            })
+            .collect();
+
+        trigger_data.spawned.extend(spawned);
+
+        for (target, position, pause) in std::mem::take(&mut trigger_data.pause_changes) {
+            for spawned in &mut trigger_data.spawned {
+                if !spawned.is_targeted(target) {
+                    continue;
+                }
 
-                // span_b.in_scope(|| {
-                //     trigger.0.execute(
-                //         world_mut,
-                //         trigger_system_state.get_mut(),
-                //         previous_progress,
-                //         current_progress,
-                //     );
-                // });
+                if pause {
+                    if spawned.range.start < position && spawned.paused_at.is_none() {
+                        // It may have already run past the pause this frame
+                        let range = &spawned.range;
+                        let progress_position =
+                            range.start + spawned.progress * (range.end - range.start);
+                        spawned.paused_at = Some(position.max(progress_position));
+                    }
+                } else if let Some(paused_at) = spawned.paused_at.take() {
+                    // Continue from the same progress with what is left shifted to the resume
+                    let shift = position - paused_at;
+                    spawned.range = spawned.range.start + shift..spawned.range.end + shift;
+                }
             }
         }
 
@@ -357,34 +574,50 @@ pub(crate) fn process_triggers(world: &mut World) {
 
         unsafe { &mut **trigger_data_cell.get() }
             .spawned
-            .append(&mut unsafe { &mut **trigger_data_cell.get() }.to_spawn);
-
-        unsafe { &mut **trigger_data_cell.get() }.spawned.retain(
-            |(entity, trigger, groups, range)| {
+            .retain_mut(|spawned| {
                 let trigger_data = unsafe { &mut **trigger_data_cell.get() };
 
-                let trigger_range_length = range.end - range.start;
-                let mut previous_progress =
-                    ((last_translation.x - range.start) / trigger_range_length).clamp(0., 1.);
-                let current_progress =
-                    ((transform.translation.x - range.start) / trigger_range_length).clamp(0., 1.);
-
-                if previous_progress == 1. && current_progress == 1. {
-                    previous_progress = 0.;
-                }
+                let range = &spawned.range;
 
                 for (stopped_group, stop_pos) in &trigger_data.stopped {
-                    if groups.iter().any(|group_id| group_id == stopped_group)
+                    if spawned
+                        .groups
+                        .iter()
+                        .any(|group_id| group_id == stopped_group)
                         && range.start < *stop_pos
                     {
                         return false;
                     }
                 }
 
-                if trigger.0.post() {
+                if let Some(stop_pos) = spawned
+                    .control_id
+                    .and_then(|control_id| trigger_data.stopped_control_ids.get(&control_id))
+                {
+                    if range.start < *stop_pos {
+                        return false;
+                    }
+                }
+
+                if spawned.paused_at.is_some() {
+                    return true;
+                }
+
+                let trigger_range_length = range.end - range.start;
+                let mut previous_progress = spawned.progress;
+                let current_progress =
+                    ((player.distance - range.start) / trigger_range_length).clamp(0., 1.);
+
+                if previous_progress == 1. && current_progress == 1. {
+                    previous_progress = 0.;
+                }
+
+                spawned.progress = current_progress;
+
+                if spawned.trigger.0.post() {
                     post_triggers.push((
-                        trigger.clone(),
-                        *entity,
+                        spawned.trigger.clone(),
+                        spawned.entity,
                         previous_progress,
                         current_progress,
                         range.clone(),
@@ -396,9 +629,9 @@ pub(crate) fn process_triggers(world: &mut World) {
                 let world_mut = unsafe { world_cell.world_mut() };
 
                 run_trigger(
-                    trigger,
+                    &spawned.trigger,
                     world_mut,
-                    *entity,
+                    spawned.entity,
                     previous_progress,
                     current_progress,
                     range.clone(),
@@ -406,8 +639,7 @@ pub(crate) fn process_triggers(world: &mut World) {
                 );
 
                 current_progress != 1.
-            },
-        );
+            });
 
         for (trigger, entity, previous_progress, current_progress, range) in post_triggers {
             // Very unsafe but works for now
@@ -479,6 +711,61 @@ fn run_trigger(
     );
 }
 
+/// Queues every spawn activated trigger of the group to start at `start_time`
+///
+/// Returns the triggers that should be marked as [`Activated`] afterwards
+pub(crate) fn queue_group_spawn(
+    group: &GlobalGroup,
+    start_time: f32,
+    global_triggers: &GlobalTriggers,
+    trigger_data: &mut TriggerData,
+    trigger_query: &Query<
+        (
+            Entity,
+            &Trigger,
+            &ObjectGroups,
+            &ObjectColorCalculated,
+            Option<&MultiActivate>,
+        ),
+        (With<SpawnActivate>, Without<Activated>),
+    >,
+) -> Vec<Entity> {
+    let start_pos = global_triggers.speed_changes.pos_for_time(start_time);
+
+    let mut activated = Vec::new();
+
+    for (entity, trigger, object_groups, calculated, multi_activate) in
+        trigger_query.iter_many(&group.root_entities)
+    {
+        if !calculated.enabled {
+            continue;
+        }
+
+        let mut end_pos = global_triggers
+            .speed_changes
+            .pos_for_time(start_time + trigger.0.duration());
+
+        if end_pos == start_pos {
+            end_pos = end_pos.next_after(f32::INFINITY);
+        }
+
+        trigger_data.to_spawn.push((
+            entity,
+            trigger.clone(),
+            object_groups.groups.clone(),
+            start_pos..end_pos,
+        ));
+
+        if multi_activate.is_some() {
+            continue;
+        }
+
+        activated.push(entity);
+    }
+
+    activated
+}
+
 pub(crate) fn insert_trigger_data(
     entity_world_mut: &mut EntityWorldMut,
     object_id: u64,
@@ -486,63 +773,119 @@ pub(crate) fn insert_trigger_data(
 ) -> Result<(), anyhow::Error> {
     match object_id {
         200 | 201 | 202 | 203 | 1334 => {
+            let (forward_velocity, speed) = match object_id {
+                // Known as "0.5x"
+                200 => (5.98 * 60., 0.7),
+                // Known as "2x"
+                202 => (5.87 * 60., 1.1),
+                // Known as "3x"
+                203 => (6. * 60., 1.3),
+                // Known as "4x"
+                1334 => (6. * 60., 1.6),
+                // Known as "1x"
+                _ => (5.77 * 60., 0.9),
+            };
+            let mut on_path = false;
             if let Some(editor_preview) = object_data.get("13") {
-                if !str_to_bool(editor_preview) {
-                    return Ok(());
-                }
-            } else {
-                return Ok(());
-            }
+                on_path = str_to_bool(editor_preview);
+            }
+            entity_world_mut.insert((
+                SpeedChange {
+                    forward_velocity,
+                    speed,
+                    on_path,
+                },
+                TouchActivate,
+            ));
+            return Ok(());
         }
-        _ => (),
-    }
-    match object_id {
-        200 => {
-            // Known as "0.5x"
-            entity_world_mut.insert(SpeedChange {
-                forward_velocity: 5.98 * 60.,
-                speed: 0.7,
-            });
-            entity_world_mut.insert(TouchActivate);
+        12 | 13 | 47 | 111 | 660 | 745 | 1331 | 1933 => {
+            let kind = match object_id {
+                13 => GameModeKind::Ship,
+                47 => GameModeKind::Ball,
+                111 => GameModeKind::Ufo,
+                660 => GameModeKind::Wave,
+                745 => GameModeKind::Robot,
+                1331 => GameModeKind::Spider,
+                1933 => GameModeKind::Swing,
+                _ => GameModeKind::Cube,
+            };
+            entity_world_mut.insert((GameModePortal(kind), TouchActivate));
             return Ok(());
         }
-        201 => {
-            // Known as "1x"
-            entity_world_mut.insert(SpeedChange {
-                forward_velocity: 5.77 * 60.,
-                speed: 0.9,
-            });
-            entity_world_mut.insert(TouchActivate);
+        10 | 11 | 2926 => {
+            let gravity_portal = match object_id {
+                11 => GravityPortal::Flipped,
+                2926 => GravityPortal::Toggle,
+                _ => GravityPortal::Normal,
+            };
+            entity_world_mut.insert((Portal::Gravity(gravity_portal), TouchActivate));
             return Ok(());
         }
-        202 => {
-            // Known as "2x"
-            entity_world_mut.insert(SpeedChange {
-                forward_velocity: 5.87 * 60.,
-                speed: 1.1,
-            });
-            entity_world_mut.insert(TouchActivate);
+        45 | 46 => {
+            entity_world_mut.insert((Portal::Mirror(object_id == 45), TouchActivate));
             return Ok(());
         }
-        203 => {
-            // Known as "3x"
-            entity_world_mut.insert(SpeedChange {
-                forward_velocity: 6. * 60.,
-                speed: 1.3,
-            });
-            entity_world_mut.insert(TouchActivate);
+        99 | 101 => {
+            entity_world_mut.insert((Portal::Mini(object_id == 101), TouchActivate));
             return Ok(());
         }
-        1334 => {
-            // Known as "4x"
-            entity_world_mut.insert(SpeedChange {
-                forward_velocity: 6. * 60.,
-                speed: 1.6,
-            });
-            entity_world_mut.insert(TouchActivate);
+        286 | 287 => {
+            entity_world_mut.insert((Portal::Dual(object_id == 286), TouchActivate));
+            return Ok(());
+        }
+        36 | 84 | 141 | 1022 | 1330 | 1333 | 1594 | 1704 | 1751 | 3004 | 3027 => {
+            let kind = match object_id {
+                36 => OrbKind::Yellow,
+                84 => OrbKind::Gravity,
+                141 => OrbKind::Pink,
+                1022 => OrbKind::Green,
+                1330 => OrbKind::Black,
+                1333 => OrbKind::Red,
+                1594 => {
+                    let mut target_group = 0;
+                    let mut activate = false;
+                    if let Some(group) = object_data.get("51") {
+                        target_group = group.parse()?;
+                    }
+                    if let Some(activate_group) = object_data.get("56") {
+                        activate = str_to_bool(activate_group);
+                    }
+                    OrbKind::Toggle {
+                        target_group,
+                        activate,
+                    }
+                }
+                1704 => OrbKind::Dash,
+                1751 => OrbKind::GravityDash,
+                3004 => OrbKind::Spider,
+                _ => {
+                    let mut target_group = 0;
+                    if let Some(group) = object_data.get("51") {
+                        target_group = group.parse()?;
+                    }
+                    OrbKind::Teleport { target_group }
+                }
+            };
+            let mut orb = Orb::new(kind);
+            if let Some(multi_activate) = object_data.get("99") {
+                orb.multi_activate = str_to_bool(multi_activate);
+            }
+            entity_world_mut.insert((orb, TouchActivate));
+            return Ok(());
+        }
+        35 | 67 | 140 | 1332 | 3005 => {
+            let kind = match object_id {
+                35 => PadKind::Yellow,
+                67 => PadKind::Gravity,
+                140 => PadKind::Pink,
+                1332 => PadKind::Red,
+                _ => PadKind::Spider,
+            };
+            entity_world_mut.insert((Pad::new(kind), TouchActivate));
             return Ok(());
         }
-        29 | 30 | 105 | 221 | 717 | 718 | 743 | 744 | 899 => {
+        29 | 30 | 104 | 105 | 221 | 717 | 718 | 743 | 744 | 899 | 900 | 915 => {
             let mut trigger = ColorTrigger::default();
             if let Some(duration) = object_data.get("10") {
                 trigger.duration = duration.parse()?;
@@ -568,8 +911,10 @@ pub(crate) fn insert_trigger_data(
                 743 => 4,
                 29 => 1000,
                 30 => 1001,
+                104 | 915 => 1002,
                 744 => 1003,
                 105 => 1004,
+                900 => 1009,
                 _ => trigger.target_channel,
             };
             if let Some(r) = object_data.get("7") {
@@ -735,6 +1080,15 @@ pub(crate) fn insert_trigger_data(
             if let Some(target_group) = object_data.get("51") {
                 trigger.target_group = target_group.parse()?;
             }
+            if let Some(ordered) = object_data.get("441") {
+                trigger.ordered = str_to_bool(ordered);
+            }
+            if let Some(remap) = object_data.get("442") {
+                trigger.remap = GroupRemap::parse(remap)?;
+            }
+            if let Some(reset_remap) = object_data.get("581") {
+                trigger.reset_remap = str_to_bool(reset_remap);
+            }
             entity_world_mut.insert(Trigger(Box::new(trigger)));
         }
         1346 => {
@@ -819,6 +1173,9 @@ pub(crate) fn insert_trigger_data(
             if let Some(item_id) = object_data.get("80") {
                 trigger.item_id = item_id.parse()?;
             }
+            if let Some(multi_activate) = object_data.get("104") {
+                trigger.multi_activate = str_to_bool(multi_activate);
+            }
             entity_world_mut.insert(Trigger(Box::new(trigger)));
         }
         1616 => {
@@ -826,6 +1183,12 @@ pub(crate) fn insert_trigger_data(
             if let Some(target_group) = object_data.get("51") {
                 trigger.target_group = target_group.parse()?;
             }
+            if let Some(use_control_id) = object_data.get("535") {
+                trigger.use_control_id = str_to_bool(use_control_id);
+            }
+            if let Some(option) = object_data.get("580") {
+                trigger.option = StopOption::from_id(option.parse()?);
+            }
             entity_world_mut.insert(Trigger(Box::new(trigger)));
         }
         1811 => {
@@ -889,65 +1252,1091 @@ pub(crate) fn insert_trigger_data(
             }
             entity_world_mut.insert(Trigger(Box::new(trigger)));
         }
-        31 | 32 | 33 | 34 | 104 | 900 | 915 | 1585 | 1595 | 1612 | 1613 | 1812 | 1814 | 1818
-        | 1819 | 22 | 24 | 23 | 25 | 26 | 27 | 28 | 55 | 56 | 57 | 58 | 59 | 1912 | 1913 | 1914
-        | 1916 | 1917 | 1931 | 1932 | 1934 | 1935 | 2015 | 2016 | 2062 | 2067 | 2068 | 2701
-        | 2702 | 1586 | 1700 | 1755 | 1813 | 1829 | 1859 => {
-            entity_world_mut.insert(Trigger(Box::new(EmptyTrigger::default())));
+        3619 => {
+            let mut trigger = ItemEditTrigger {
+                first: parse_item(object_data, "80", "476")?,
+                second: parse_item(object_data, "95", "477")?,
+                ..default()
+            };
+            if let Some(target_id) = object_data.get("51") {
+                trigger.target.1 = target_id.parse()?;
+            }
+            if let Some(target_kind) = object_data.get("478") {
+                trigger.target.0 = ItemKind::from_id(target_kind.parse()?).unwrap_or_default();
+            }
+            if let Some(modifier) = object_data.get("479") {
+                trigger.modifier = modifier.parse()?;
+            }
+            if let Some(assign_operator) = object_data.get("480") {
+                trigger.assign_operator = ItemOperator::from_id(assign_operator.parse()?);
+            }
+            if let Some(operator) = object_data.get("481") {
+                trigger.operator = ItemOperator::from_id(operator.parse()?);
+            }
+            if let Some(modifier_operator) = object_data.get("482") {
+                trigger.modifier_operator = ItemOperator::from_id(modifier_operator.parse()?);
+            }
+            if let Some(round_mode) = object_data.get("485") {
+                trigger.round_mode = RoundMode::from_id(round_mode.parse()?);
+            }
+            if let Some(target_round_mode) = object_data.get("486") {
+                trigger.target_round_mode = RoundMode::from_id(target_round_mode.parse()?);
+            }
+            if let Some(sign_mode) = object_data.get("578") {
+                trigger.sign_mode = SignMode::from_id(sign_mode.parse()?);
+            }
+            if let Some(target_sign_mode) = object_data.get("579") {
+                trigger.target_sign_mode = SignMode::from_id(target_sign_mode.parse()?);
+            }
+            entity_world_mut.insert(Trigger(Box::new(trigger)));
         }
-        _ => return Ok(()),
-    }
-
-    let touch_triggered = object_data
-        .get("11")
-        .map(|b| str_to_bool(b))
-        .unwrap_or_default();
-    let spawn_triggered = object_data
-        .get("62")
-        .map(|b| str_to_bool(b))
-        .unwrap_or_default();
-    if touch_triggered {
-        entity_world_mut.insert(TouchActivate);
-    } else if spawn_triggered {
-        entity_world_mut.insert(SpawnActivate);
-    } else {
-        entity_world_mut.insert(PosActivate);
-    }
-
-    if let Some(multi_activate) = object_data.get("87") {
-        if str_to_bool(multi_activate) {
-            entity_world_mut.insert(MultiActivate);
+        3620 => {
+            let mut trigger = ItemCompareTrigger {
+                first: ItemOperand {
+                    item: parse_item(object_data, "80", "476")?,
+                    ..default()
+                },
+                second: ItemOperand {
+                    item: parse_item(object_data, "95", "477")?,
+                    ..default()
+                },
+                ..default()
+            };
+            if let Some(true_group) = object_data.get("51") {
+                trigger.true_group = true_group.parse()?;
+            }
+            if let Some(false_group) = object_data.get("71") {
+                trigger.false_group = false_group.parse()?;
+            }
+            if let Some(modifier) = object_data.get("479") {
+                trigger.first.modifier = modifier.parse()?;
+            }
+            if let Some(modifier) = object_data.get("483") {
+                trigger.second.modifier = modifier.parse()?;
+            }
+            if let Some(operator) = object_data.get("480") {
+                trigger.first.operator = ItemOperator::from_id(operator.parse()?);
+            }
+            if let Some(operator) = object_data.get("481") {
+                trigger.second.operator = ItemOperator::from_id(operator.parse()?);
+            }
+            if let Some(compare_operator) = object_data.get("482") {
+                trigger.compare_operator = CompareOperator::from_id(compare_operator.parse()?);
+            }
+            if let Some(tolerance) = object_data.get("484") {
+                trigger.tolerance = tolerance.parse()?;
+            }
+            if let Some(round_mode) = object_data.get("485") {
+                trigger.first.round_mode = RoundMode::from_id(round_mode.parse()?);
+            }
+            if let Some(round_mode) = object_data.get("486") {
+                trigger.second.round_mode = RoundMode::from_id(round_mode.parse()?);
+            }
+            if let Some(sign_mode) = object_data.get("578") {
+                trigger.first.sign_mode = SignMode::from_id(sign_mode.parse()?);
+            }
+            if let Some(sign_mode) = object_data.get("579") {
+                trigger.second.sign_mode = SignMode::from_id(sign_mode.parse()?);
+            }
+            entity_world_mut.insert(Trigger(Box::new(trigger)));
         }
-    }
-
-    Ok(())
-}
-
-pub(crate) fn construct_trigger_index(world: &mut World) {
-    let mut speed_changes = SpeedChanges::default();
-
-    // Start by indexing speed changes
-    let mut speed_change_query = world.query::<(Entity, &SpeedChange, &Transform2d, &Hitbox)>();
-
-    for (entity, speed_change, transform, hitbox) in speed_change_query.iter(world) {
-        let global_transform = GlobalTransform2d::from(*transform);
-        let global_hitbox = GlobalHitbox::from((hitbox, transform, &global_transform));
-        speed_changes.0.push((
-            OrderedFloat(global_hitbox.aabb.x),
-            SpeedChangeData {
-                speed_per_sec: speed_change.speed * speed_change.forward_velocity,
-                // Calculate time at pos in SpeedChanges::initialize()
-                time_at_pos: 0.,
-                entity,
-            },
-        ));
-    }
-
-    speed_changes.initialize();
-
-    let mut global_triggers = GlobalTriggers {
-        speed_changes,
-        ..default()
+        3641 => {
+            let mut trigger = ItemPersistTrigger::default();
+            if let Some(item_id) = object_data.get("80") {
+                trigger.item_id = item_id.parse()?;
+            }
+            if let Some(persistent) = object_data.get("491") {
+                trigger.persistent = str_to_bool(persistent);
+            }
+            if let Some(target_all) = object_data.get("492") {
+                trigger.target_all = str_to_bool(target_all);
+            }
+            if let Some(reset) = object_data.get("493") {
+                trigger.reset = str_to_bool(reset);
+            }
+            if let Some(timer) = object_data.get("494") {
+                if str_to_bool(timer) {
+                    trigger.kind = ItemKind::Timer;
+                }
+            }
+            entity_world_mut.insert(Trigger(Box::new(trigger)));
+        }
+        3016 => {
+            let mut trigger = AdvancedFollowTrigger::default();
+            if let Some(target_group) = object_data.get("51") {
+                trigger.target_group = target_group.parse()?;
+            }
+            let follow_player = object_data
+                .get("138")
+                .map(|b| str_to_bool(b))
+                .unwrap_or_default();
+            if !follow_player {
+                if let Some(follow_group) = object_data.get("71") {
+                    trigger.follow_group = Some(follow_group.parse()?);
+                }
+            }
+            if let Some(speed) = object_data.get("300") {
+                trigger.speed = speed.parse()?;
+            }
+            if let Some(delay) = object_data.get("302") {
+                trigger.delay = delay.parse::<f32>()?.max(0.);
+            }
+            if let Some(max_range) = object_data.get("303") {
+                trigger.max_range = max_range.parse()?;
+            }
+            if let Some(friction) = object_data.get("304") {
+                trigger.friction = friction.parse()?;
+            }
+            if let Some(rotate_direction) = object_data.get("305") {
+                trigger.rotate_direction = str_to_bool(rotate_direction);
+            }
+            if let Some(rotation_offset) = object_data.get("306") {
+                trigger.rotation_offset = -rotation_offset.parse::<f32>()?.to_radians();
+            }
+            if let Some(rotation_easing) = object_data.get("307") {
+                trigger.rotation_easing = rotation_easing.parse()?;
+            }
+            if let Some(max_speed) = object_data.get("308") {
+                trigger.max_speed = max_speed.parse()?;
+            }
+            if let Some(acceleration) = object_data.get("334") {
+                trigger.acceleration = acceleration.parse()?;
+            }
+            entity_world_mut.insert(Trigger(Box::new(trigger)));
+        }
+        3660 => {
+            let mut trigger = EditAdvancedFollowTrigger::default();
+            if let Some(target_group) = object_data.get("51") {
+                trigger.target_group = target_group.parse()?;
+            }
+            if let Some(speed) = object_data.get("300") {
+                trigger.speed = Some(speed.parse()?);
+            }
+            if let Some(max_range) = object_data.get("303") {
+                trigger.max_range = Some(max_range.parse()?);
+            }
+            if let Some(friction) = object_data.get("304") {
+                trigger.friction = Some(friction.parse()?);
+            }
+            if let Some(acceleration) = object_data.get("334") {
+                trigger.acceleration = Some(acceleration.parse()?);
+            }
+            let velocity_x = object_data.get("309").map(|x| x.parse()).transpose()?;
+            let velocity_y = object_data.get("310").map(|y| y.parse()).transpose()?;
+            if velocity_x.is_some() || velocity_y.is_some() {
+                trigger.velocity = Some(Vec2::new(
+                    velocity_x.unwrap_or_default(),
+                    velocity_y.unwrap_or_default(),
+                ));
+            }
+            if let Some(add_velocity) = object_data.get("311") {
+                trigger.add_velocity = str_to_bool(add_velocity);
+            }
+            entity_world_mut.insert(Trigger(Box::new(trigger)));
+        }
+        2903 => {
+            let mut trigger = GradientTrigger::default();
+            for (group, key) in trigger.groups.iter_mut().zip(["203", "204", "205", "206"]) {
+                if let Some(group_id) = object_data.get(key) {
+                    *group = group_id.parse()?;
+                }
+            }
+            if let Some(vertex_mode) = object_data.get("207") {
+                trigger.vertex_mode = str_to_bool(vertex_mode);
+            }
+            if let Some(disable) = object_data.get("208") {
+                trigger.disable = str_to_bool(disable);
+            }
+            if let Some(gradient_id) = object_data.get("209") {
+                trigger.gradient_id = gradient_id.parse()?;
+            }
+            if let Some(blending) = object_data.get("174") {
+                trigger.additive = blending.parse::<u8>()? == 1;
+            }
+            entity_world_mut.insert(Trigger(Box::new(trigger)));
+        }
+        2905 | 2907 | 2909..=2917 | 2919..=2924 => {
+            let effect = ShaderEffect::from_object_id(object_id).unwrap();
+            let mut trigger = ShaderTrigger {
+                effect,
+                ..default()
+            };
+            if let Some(duration) = object_data.get("10") {
+                trigger.duration = duration.parse::<f32>()?.max(0.);
+            }
+            if let Some(easing) = object_data.get("30") {
+                let id = easing.parse()?;
+                let rate = object_data.get("85").map(|b| b.parse()).transpose()?;
+                trigger.easing = Easing::from_id(id, rate)
+            }
+            if object_data
+                .get("138")
+                .map(|b| str_to_bool(b))
+                .unwrap_or_default()
+            {
+                trigger.center = ShaderCenter::Player;
+            } else if let Some(center_group) = object_data.get("51") {
+                trigger.center = ShaderCenter::Group(center_group.parse()?);
+            }
+            trigger.params = parse_shader_params(effect, trigger.duration, object_data)?;
+            entity_world_mut.insert(Trigger(Box::new(trigger)));
+        }
+        1934 => {
+            let mut trigger = SongTrigger {
+                settings: parse_sound_settings(object_data)?,
+                action: parse_sound_action(object_data)?,
+                ..default()
+            };
+            if let Some(song_id) = object_data.get("392") {
+                trigger.song_id = song_id.parse()?;
+            }
+            if let Some(channel) = object_data.get("432") {
+                trigger.channel = channel.parse()?;
+            }
+            entity_world_mut.insert(Trigger(Box::new(trigger)));
+        }
+        3602 => {
+            let mut trigger = SfxTrigger {
+                settings: parse_sound_settings(object_data)?,
+                action: parse_sound_action(object_data)?,
+                ..default()
+            };
+            if let Some(sfx_id) = object_data.get("392") {
+                trigger.sfx_id = sfx_id.parse()?;
+            }
+            if let Some(unique_id) = object_data.get("415") {
+                trigger.unique_id = unique_id.parse()?;
+            }
+            if let Some(pitch) = object_data.get("405") {
+                // Pitch is given in semitones
+                trigger.settings.playback_rate *= 2f32.powf(pitch.parse::<f32>()? / 12.);
+            }
+            if let Some(center_group) = object_data.get("51") {
+                let center_group = center_group.parse()?;
+                if center_group != 0 {
+                    let mut spatial = SpatialSettings {
+                        center_group,
+                        min_distance: 0.,
+                        max_distance: 300.,
+                    };
+                    if let Some(min_distance) = object_data.get("419") {
+                        spatial.min_distance = min_distance.parse()?;
+                    }
+                    if let Some(max_distance) = object_data.get("420") {
+                        spatial.max_distance = max_distance.parse()?;
+                    }
+                    trigger.spatial = Some(spatial);
+                }
+            }
+            entity_world_mut.insert(Trigger(Box::new(trigger)));
+        }
+        3006..=3010 => {
+            let mut trigger = AreaTrigger {
+                effect_id: 0,
+                effect: AreaEffect {
+                    kind: parse_area_effect_kind(object_id - 3006, object_data)?,
+                    target_group: 0,
+                    center_group: 0,
+                    length: 0.,
+                    deadzone: 0.,
+                    inward: false,
+                    falloff: Easing::None,
+                    duration: 0.,
+                    easing: Easing::None,
+                },
+            };
+            if let Some(effect_id) = object_data.get("225") {
+                trigger.effect_id = effect_id.parse()?;
+            }
+            if let Some(target_group) = object_data.get("51") {
+                trigger.effect.target_group = target_group.parse()?;
+            }
+            if let Some(center_group) = object_data.get("71") {
+                trigger.effect.center_group = center_group.parse()?;
+            }
+            if let Some(length) = object_data.get("218") {
+                trigger.effect.length = length.parse()?;
+            }
+            if let Some(deadzone) = object_data.get("219") {
+                trigger.effect.deadzone = deadzone.parse::<f32>()?.clamp(0., 1.);
+            }
+            if let Some(falloff) = object_data.get("220") {
+                let id = falloff.parse()?;
+                let rate = object_data.get("221").map(|b| b.parse()).transpose()?;
+                trigger.effect.falloff = Easing::from_id(id, rate)
+            }
+            if let Some(inward) = object_data.get("222") {
+                trigger.effect.inward = str_to_bool(inward);
+            }
+            if let Some(duration) = object_data.get("10") {
+                trigger.effect.duration = duration.parse::<f32>()?.max(0.);
+            }
+            if let Some(easing) = object_data.get("30") {
+                let id = easing.parse()?;
+                let rate = object_data.get("85").map(|b| b.parse()).transpose()?;
+                trigger.effect.easing = Easing::from_id(id, rate)
+            }
+            entity_world_mut.insert(Trigger(Box::new(trigger)));
+        }
+        3011..=3015 => {
+            let mut trigger = AreaEditTrigger {
+                effect_id: 0,
+                duration: 0.,
+                kind: parse_area_effect_kind(object_id - 3011, object_data)?,
+                length: None,
+                deadzone: None,
+            };
+            if let Some(effect_id) = object_data.get("51") {
+                trigger.effect_id = effect_id.parse()?;
+            }
+            if let Some(duration) = object_data.get("10") {
+                trigger.duration = duration.parse::<f32>()?.max(0.);
+            }
+            if let Some(length) = object_data.get("218") {
+                trigger.length = Some(length.parse()?);
+            }
+            if let Some(deadzone) = object_data.get("219") {
+                trigger.deadzone = Some(deadzone.parse::<f32>()?.clamp(0., 1.));
+            }
+            entity_world_mut.insert(Trigger(Box::new(trigger)));
+        }
+        747 => {
+            // The linked orange portal is only stored as a vertical offset
+            let mut offset = Vec2::ZERO;
+            if let Some(y_offset) = object_data.get("54") {
+                offset.y = y_offset.parse()?;
+            }
+            entity_world_mut.insert(Trigger(Box::new(TeleportTrigger {
+                target: TeleportTarget::Offset(offset),
+            })));
+        }
+        3022 => {
+            let mut trigger = TeleportTrigger::default();
+            if let Some(target_group) = object_data.get("51") {
+                trigger.target = TeleportTarget::Group(target_group.parse()?);
+            }
+            entity_world_mut.insert(Trigger(Box::new(trigger)));
+        }
+        3024 => {
+            let mut trigger = AreaStopTrigger::default();
+            if let Some(effect_id) = object_data.get("51") {
+                trigger.effect_id = effect_id.parse()?;
+            }
+            entity_world_mut.insert(Trigger(Box::new(trigger)));
+        }
+        3607 => {
+            let mut trigger = SequenceTrigger::default();
+            if let Some(sequence) = object_data.get("435") {
+                let ids = sequence
+                    .split('.')
+                    .filter(|id| !id.is_empty())
+                    .map(|id| id.parse())
+                    .collect::<Result<Vec<u64>, _>>()?;
+                trigger.sequence = ids
+                    .chunks_exact(2)
+                    .map(|pair| (pair[0], pair[1].max(1)))
+                    .collect();
+            }
+            if let Some(mode) = object_data.get("436") {
+                trigger.mode = SequenceMode::from_id(mode.parse()?);
+            }
+            entity_world_mut.insert(Trigger(Box::new(trigger)));
+        }
+        3614 => {
+            let mut trigger = TimeTrigger::default();
+            if let Some(item_id) = object_data.get("80") {
+                trigger.item_id = item_id.parse()?;
+            }
+            if let Some(target_group) = object_data.get("51") {
+                trigger.target_group = target_group.parse()?;
+            }
+            if let Some(start_time) = object_data.get("467") {
+                trigger.start_time = start_time.parse()?;
+            }
+            if let Some(dont_override) = object_data.get("468") {
+                trigger.dont_override = str_to_bool(dont_override);
+            }
+            if let Some(ignore_timewarp) = object_data.get("469") {
+                trigger.ignore_timewarp = str_to_bool(ignore_timewarp);
+            }
+            if let Some(time_mod) = object_data.get("470") {
+                trigger.time_mod = time_mod.parse()?;
+            }
+            if let Some(start_paused) = object_data.get("471") {
+                trigger.start_paused = str_to_bool(start_paused);
+            }
+            if let Some(stop_checked) = object_data.get("474") {
+                if str_to_bool(stop_checked) {
+                    trigger.stop_time = Some(match object_data.get("473") {
+                        Some(stop_time) => stop_time.parse()?,
+                        None => 0.,
+                    });
+                }
+            }
+            entity_world_mut.insert(Trigger(Box::new(trigger)));
+        }
+        3615 => {
+            let mut trigger = TimeEventTrigger::default();
+            if let Some(item_id) = object_data.get("80") {
+                trigger.item_id = item_id.parse()?;
+            }
+            if let Some(target_group) = object_data.get("51") {
+                trigger.target_group = target_group.parse()?;
+            }
+            if let Some(target_time) = object_data.get("473") {
+                trigger.target_time = target_time.parse()?;
+            }
+            if let Some(multi_activate) = object_data.get("475") {
+                trigger.multi_activate = str_to_bool(multi_activate);
+            }
+            entity_world_mut.insert(Trigger(Box::new(trigger)));
+        }
+        3617 => {
+            let mut trigger = TimeControlTrigger::default();
+            if let Some(item_id) = object_data.get("80") {
+                trigger.item_id = item_id.parse()?;
+            }
+            if let Some(stop) = object_data.get("472") {
+                trigger.stop = str_to_bool(stop);
+            }
+            entity_world_mut.insert(Trigger(Box::new(trigger)));
+        }
+        1917 => {
+            entity_world_mut.insert(Trigger(Box::new(ReverseTrigger)));
+        }
+        2066 => {
+            let mut trigger = GravityTrigger::default();
+            if let Some(gravity) = object_data.get("148") {
+                trigger.gravity = gravity.parse()?;
+            }
+            entity_world_mut.insert(Trigger(Box::new(trigger)));
+        }
+        2900 => {
+            let mut trigger = RotateGameplayTrigger::default();
+            if let Some(direction) = object_data.get("169") {
+                trigger.direction = GameplayDirection::from_id(direction.parse()?);
+            }
+            entity_world_mut.insert(Trigger(Box::new(trigger)));
+        }
+        22..=28 | 55..=59 => {
+            entity_world_mut.insert(Trigger(Box::new(EnterEffectTrigger {
+                effect: EnterEffect::from_object_id(object_id),
+            })));
+        }
+        32 | 33 => {
+            entity_world_mut.insert(Trigger(Box::new(GhostTrailTrigger {
+                enabled: object_id == 32,
+            })));
+        }
+        1612 | 1613 => {
+            entity_world_mut.insert(Trigger(Box::new(PlayerVisibilityTrigger {
+                visible: object_id == 1613,
+            })));
+        }
+        1818 | 1819 => {
+            entity_world_mut.insert(Trigger(Box::new(BgEffectTrigger {
+                enabled: object_id == 1818,
+            })));
+        }
+        1932 => {
+            let mut trigger = PlayerControlTrigger::default();
+            if let Some(stop_jump) = object_data.get("540") {
+                trigger.stop_jump = str_to_bool(stop_jump);
+            }
+            if let Some(stop_move) = object_data.get("541") {
+                trigger.stop_move = str_to_bool(stop_move);
+            }
+            entity_world_mut.insert(Trigger(Box::new(trigger)));
+        }
+        1935 => {
+            let mut trigger = TimeWarpTrigger::default();
+            if let Some(time_mod) = object_data.get("120") {
+                trigger.time_mod = time_mod.parse::<f32>()?.clamp(0.1, 2.);
+            }
+            entity_world_mut.insert(Trigger(Box::new(trigger)));
+        }
+        2899 => {
+            let mut trigger = OptionsTrigger::default();
+            for (key, option) in [
+                ("159", &mut trigger.streak_additive),
+                ("160", &mut trigger.unlink_dual_gravity),
+                ("161", &mut trigger.hide_ground),
+                ("162", &mut trigger.hide_player),
+                ("165", &mut trigger.disable_controls),
+                ("195", &mut trigger.hide_middleground),
+                ("198", &mut trigger.hide_attempts),
+            ] {
+                if let Some(value) = object_data.get(key) {
+                    *option = parse_option_toggle(value)?;
+                }
+            }
+            if let Some(edit_respawn_time) = object_data.get("573") {
+                if str_to_bool(edit_respawn_time) {
+                    trigger.respawn_time = Some(match object_data.get("574") {
+                        Some(respawn_time) => respawn_time.parse::<f32>()?.max(0.),
+                        None => 0.,
+                    });
+                }
+            }
+            entity_world_mut.insert(Trigger(Box::new(trigger)));
+        }
+        1931 | 3600 => {
+            let mut trigger = EndTrigger::default();
+            if let Some(target_group) = object_data.get("51") {
+                trigger.target_group = target_group.parse()?;
+            }
+            if let Some(instant) = object_data.get("461") {
+                trigger.instant = str_to_bool(instant);
+            }
+            entity_world_mut.insert(Trigger(Box::new(trigger)));
+        }
+        1913 => {
+            let mut trigger = CameraZoomTrigger {
+                zoom: 1.,
+                ..default()
+            };
+            if let Some(zoom) = object_data.get("371") {
+                trigger.zoom = zoom.parse::<f32>()?.clamp(0.4, 3.);
+            }
+            let (duration, easing) = parse_duration_easing(object_data)?;
+            trigger.duration = duration;
+            trigger.easing = easing;
+            entity_world_mut.insert(Trigger(Box::new(trigger)));
+        }
+        1916 => {
+            let mut trigger = CameraOffsetTrigger::default();
+            if let Some(x_offset) = object_data.get("28") {
+                trigger.offset.x = x_offset.parse()?;
+            }
+            if let Some(y_offset) = object_data.get("29") {
+                trigger.offset.y = y_offset.parse()?;
+            }
+            let (duration, easing) = parse_duration_easing(object_data)?;
+            trigger.duration = duration;
+            trigger.easing = easing;
+            entity_world_mut.insert(Trigger(Box::new(trigger)));
+        }
+        2015 => {
+            let mut trigger = CameraRotateTrigger::default();
+            if let Some(degrees) = object_data.get("68") {
+                trigger.degrees = degrees.parse()?;
+            }
+            let (duration, easing) = parse_duration_easing(object_data)?;
+            trigger.duration = duration;
+            trigger.easing = easing;
+            entity_world_mut.insert(Trigger(Box::new(trigger)));
+        }
+        1914 => {
+            let mut trigger = StaticCameraTrigger::default();
+            if let Some(target_group) = object_data.get("71") {
+                trigger.target_group = target_group.parse()?;
+            }
+            if let Some(exit) = object_data.get("110") {
+                trigger.exit = str_to_bool(exit);
+            }
+            let (duration, easing) = parse_duration_easing(object_data)?;
+            trigger.duration = duration;
+            trigger.easing = easing;
+            entity_world_mut.insert(Trigger(Box::new(trigger)));
+        }
+        2062 => {
+            let mut trigger = CameraEdgeTrigger::default();
+            if let Some(target_group) = object_data.get("51") {
+                trigger.target_group = target_group.parse()?;
+            }
+            if let Some(edge) = object_data.get("164") {
+                trigger.edge = CameraEdge::from_id(edge.parse()?);
+            }
+            entity_world_mut.insert(Trigger(Box::new(trigger)));
+        }
+        3613 => {
+            let mut trigger = UiTrigger::default();
+            if let Some(target_group) = object_data.get("51") {
+                trigger.target_group = target_group.parse()?;
+            }
+            entity_world_mut.insert(Trigger(Box::new(trigger)));
+        }
+        1912 => {
+            let mut trigger = RandomTrigger {
+                chance: 50.,
+                ..default()
+            };
+            if let Some(group_a) = object_data.get("51") {
+                trigger.group_a = group_a.parse()?;
+            }
+            if let Some(group_b) = object_data.get("71") {
+                trigger.group_b = group_b.parse()?;
+            }
+            if let Some(chance) = object_data.get("10") {
+                trigger.chance = chance.parse()?;
+            }
+            entity_world_mut.insert(Trigger(Box::new(trigger)));
+        }
+        2068 => {
+            let mut trigger = AdvancedRandomTrigger::default();
+            if let Some(groups) = object_data.get("152") {
+                let ids = groups
+                    .split('.')
+                    .filter(|id| !id.is_empty())
+                    .map(|id| id.parse())
+                    .collect::<Result<Vec<u64>, _>>()?;
+                trigger.groups = ids.chunks_exact(2).map(|pair| (pair[0], pair[1])).collect();
+            }
+            entity_world_mut.insert(Trigger(Box::new(trigger)));
+        }
+        2067 => {
+            let mut trigger = ScaleTrigger::default();
+            if let Some(target_group) = object_data.get("51") {
+                trigger.target_group = target_group.parse()?;
+            }
+            if let Some(center_group) = object_data.get("71") {
+                trigger.center_group = center_group.parse()?;
+            }
+            if let Some(x_scale) = object_data.get("150") {
+                trigger.scale.x = x_scale.parse::<f32>()?.max(0.001);
+            }
+            if let Some(y_scale) = object_data.get("151") {
+                trigger.scale.y = y_scale.parse::<f32>()?.max(0.001);
+            }
+            if let Some(divide_x) = object_data.get("153") {
+                if str_to_bool(divide_x) {
+                    trigger.scale.x = trigger.scale.x.recip();
+                }
+            }
+            if let Some(divide_y) = object_data.get("154") {
+                if str_to_bool(divide_y) {
+                    trigger.scale.y = trigger.scale.y.recip();
+                }
+            }
+            if let Some(only_move) = object_data.get("133") {
+                trigger.only_move = str_to_bool(only_move);
+            }
+            let (duration, easing) = parse_duration_easing(object_data)?;
+            trigger.duration = duration;
+            trigger.easing = easing;
+            entity_world_mut.insert(Trigger(Box::new(trigger)));
+        }
+        1814 => {
+            let mut trigger = FollowPlayerYTrigger {
+                speed: 1.,
+                ..default()
+            };
+            if let Some(duration) = object_data.get("10") {
+                trigger.duration = duration.parse::<f32>()?.max(0.);
+            }
+            if let Some(target_group) = object_data.get("51") {
+                trigger.target_group = target_group.parse()?;
+            }
+            if let Some(speed) = object_data.get("90") {
+                trigger.speed = speed.parse()?;
+            }
+            if let Some(offset) = object_data.get("92") {
+                trigger.offset = offset.parse()?;
+            }
+            if let Some(max_speed) = object_data.get("93") {
+                trigger.max_speed = max_speed.parse::<f32>()?.max(0.);
+            }
+            entity_world_mut.insert(Trigger(Box::new(trigger)));
+        }
+        1585 => {
+            let mut trigger = AnimateTrigger::default();
+            if let Some(target_group) = object_data.get("51") {
+                trigger.target_group = target_group.parse()?;
+            }
+            if let Some(animation) = object_data.get("76") {
+                trigger.animation = animation.parse()?;
+            }
+            entity_world_mut.insert(Trigger(Box::new(trigger)));
+        }
+        1812 => {
+            let mut trigger = OnDeathTrigger::default();
+            if let Some(target_group) = object_data.get("51") {
+                trigger.target_group = target_group.parse()?;
+            }
+            if let Some(activate) = object_data.get("56") {
+                trigger.activate = str_to_bool(activate);
+            }
+            entity_world_mut.insert(Trigger(Box::new(trigger)));
+        }
+        1595 => {
+            let mut trigger = TouchTrigger::default();
+            if let Some(target_group) = object_data.get("51") {
+                trigger.target_group = target_group.parse()?;
+            }
+            if let Some(hold_mode) = object_data.get("81") {
+                trigger.hold_mode = str_to_bool(hold_mode);
+            }
+            if let Some(toggle_mode) = object_data.get("82") {
+                trigger.toggle_mode = TouchToggleMode::from_id(toggle_mode.parse()?);
+            }
+            if let Some(dual_mode) = object_data.get("89") {
+                trigger.dual_mode = str_to_bool(dual_mode);
+            }
+            entity_world_mut.insert(Trigger(Box::new(trigger)));
+        }
+        3604 => {
+            let mut trigger = EventTrigger::default();
+            if let Some(target_group) = object_data.get("51") {
+                trigger.target_group = target_group.parse()?;
+            }
+            if let Some(events) = object_data.get("430") {
+                trigger.events = de::from_str(events, '.')?;
+            }
+            entity_world_mut.insert(Trigger(Box::new(trigger)));
+        }
+        // Start positions and guides only matter in the editor
+        31 | 34 | 2016 => {
+            entity_world_mut.insert(Trigger(Box::new(EmptyTrigger)));
+        }
+        // Particle objects, the D, J, S and H blocks, 2702 and link visible are split out into a
+        // follow-up, until then they are only counted and don't take a place on the timeline
+        1586 | 1700 | 1755 | 1813 | 1829 | 1859 | 2702 | 3662 => {
+            entity_world_mut.insert(UnsupportedTrigger(object_id));
+            return Ok(());
+        }
+        2701
+        | 2901
+        | 2904
+        | 2925
+        | 2999
+        | 3017..=3021
+        | 3023
+        | 3029..=3033
+        | 3603
+        | 3605
+        | 3606
+        | 3608
+        | 3609
+        | 3612
+        | 3618
+        | 3640
+        | 3642
+        | 3643
+        | 3655
+        | 3661 => {
+            entity_world_mut.insert((
+                Trigger(Box::new(EmptyTrigger)),
+                UnsupportedTrigger(object_id),
+            ));
+        }
+        _ => {
+            // Triggers are saved with this flag, so unknown ones can still be counted
+            if object_data.get("36").is_some_and(|b| str_to_bool(b)) {
+                entity_world_mut.insert(UnsupportedTrigger(object_id));
+            }
+            return Ok(());
+        }
+    }
+
+    let touch_triggered = object_data
+        .get("11")
+        .map(|b| str_to_bool(b))
+        .unwrap_or_default();
+    let spawn_triggered = object_data
+        .get("62")
+        .map(|b| str_to_bool(b))
+        .unwrap_or_default();
+    if touch_triggered {
+        entity_world_mut.insert(TouchActivate);
+    } else if spawn_triggered {
+        entity_world_mut.insert(SpawnActivate);
+    } else {
+        entity_world_mut.insert(PosActivate);
+    }
+
+    if let Some(multi_activate) = object_data.get("87") {
+        if str_to_bool(multi_activate) {
+            entity_world_mut.insert(MultiActivate);
+        }
+    }
+
+    if let Some(control_id) = object_data.get("534") {
+        entity_world_mut.insert(ControlId(control_id.parse()?));
+    }
+
+    Ok(())
+}
+
+/// Parses the effect of an area or edit area trigger, ordered the same way as their object ids
+fn parse_area_effect_kind(
+    index: u64,
+    object_data: &ObjectStorage,
+) -> Result<AreaEffectKind, anyhow::Error> {
+    let parse_f32 = |key: &str, default: f32| -> Result<f32, anyhow::Error> {
+        Ok(match object_data.get(key) {
+            Some(value) => value.parse()?,
+            None => default,
+        })
+    };
+
+    Ok(match index {
+        0 => AreaEffectKind::Move(Vec2::new(parse_f32("28", 0.)?, parse_f32("29", 0.)?)),
+        1 => AreaEffectKind::Rotate(-parse_f32("68", 0.)?.to_radians()),
+        2 => AreaEffectKind::Scale(Vec2::new(parse_f32("150", 1.)?, parse_f32("151", 1.)?)),
+        3 => AreaEffectKind::Fade(parse_f32("35", 1.)?.clamp(0., 1.)),
+        _ => AreaEffectKind::Tint(
+            match object_data.get("23") {
+                Some(channel) => channel.parse()?,
+                None => 0,
+            },
+            parse_f32("265", 1.)?.clamp(0., 1.),
+        ),
+    })
+}
+
+/// Parses the parameters of a shader trigger into the layout described on [`ShaderEffect`]
+fn parse_shader_params(
+    effect: ShaderEffect,
+    duration: f32,
+    object_data: &ObjectStorage,
+) -> Result<Vec4, anyhow::Error> {
+    let parse_f32 = |key: &str, default: f32| -> Result<f32, anyhow::Error> {
+        Ok(match object_data.get(key) {
+            Some(value) => value.parse()?,
+            None => default,
+        })
+    };
+
+    let identity = effect.identity();
+
+    Ok(match effect {
+        ShaderEffect::Shockwave | ShaderEffect::Shockline => Vec4::new(
+            // Speed is given in blocks per second
+            parse_f32("175", 1.)? * 30. * duration,
+            parse_f32("179", 10.)?,
+            parse_f32("176", 1.)?,
+            object_data
+                .get("191")
+                .map(|b| str_to_bool(b) as u8 as f32)
+                .unwrap_or_default(),
+        ),
+        ShaderEffect::Glitch | ShaderEffect::ChromaticGlitch => Vec4::new(
+            parse_f32("176", 1.)?,
+            parse_f32("175", 1.)?,
+            parse_f32("196", 10.)?,
+            0.,
+        ),
+        ShaderEffect::Chromatic
+        | ShaderEffect::Pixelate
+        | ShaderEffect::MotionBlur
+        | ShaderEffect::SplitScreen => Vec4::new(
+            parse_f32("183", identity.x)?,
+            parse_f32("184", identity.y)?,
+            0.,
+            0.,
+        ),
+        ShaderEffect::LensCircle | ShaderEffect::RadialBlur | ShaderEffect::Bulge => {
+            Vec4::new(parse_f32("179", 0.)?, parse_f32("176", 0.)?, 0., 0.)
+        }
+        ShaderEffect::Pinch => Vec4::new(
+            parse_f32("179", 0.)?,
+            parse_f32("183", 0.)?,
+            parse_f32("184", 0.)?,
+            0.,
+        ),
+        ShaderEffect::GrayScale | ShaderEffect::Sepia | ShaderEffect::InvertColor => {
+            Vec4::new(parse_f32("176", 0.)?.clamp(0., 1.), 0., 0., 0.)
+        }
+        ShaderEffect::Hue => Vec4::new(parse_f32("176", 0.)?, 0., 0., 0.),
+        ShaderEffect::EditColor => Vec4::new(
+            parse_f32("183", 1.)?,
+            parse_f32("184", 1.)?,
+            parse_f32("185", 1.)?,
+            0.,
+        ),
+    })
+}
+
+/// Options are stored as 1 to turn them on, -1 to turn them off and 0 to leave them as is
+fn parse_option_toggle(value: &str) -> Result<Option<bool>, anyhow::Error> {
+    Ok(match value.parse::<i8>()? {
+        1 => Some(true),
+        -1 => Some(false),
+        _ => None,
+    })
+}
+
+fn parse_duration_easing(object_data: &ObjectStorage) -> Result<(f32, Easing), anyhow::Error> {
+    let mut duration = 0.;
+    let mut easing = Easing::default();
+    if let Some(parsed_duration) = object_data.get("10") {
+        duration = parsed_duration.parse::<f32>()?.max(0.);
+    }
+    if let Some(parsed_easing) = object_data.get("30") {
+        let id = parsed_easing.parse()?;
+        let rate = object_data.get("85").map(|b| b.parse()).transpose()?;
+        easing = Easing::from_id(id, rate)
+    }
+    Ok((duration, easing))
+}
+
+/// Shared by song and sfx triggers, with times given in milliseconds
+fn parse_sound_settings(object_data: &ObjectStorage) -> Result<SoundSettings, anyhow::Error> {
+    let mut settings = SoundSettings::default();
+    if let Some(volume) = object_data.get("406") {
+        settings.volume = volume.parse::<f32>()?.max(0.);
+    }
+    if let Some(speed) = object_data.get("404") {
+        // Speed is given as a percentage change
+        settings.playback_rate = (1. + speed.parse::<f32>()? / 100.).max(0.01);
+    }
+    if let Some(start) = object_data.get("408") {
+        settings.start = start.parse::<f32>()?.max(0.) / 1000.;
+    }
+    if let Some(fade_in) = object_data.get("409") {
+        settings.fade_in = fade_in.parse::<f32>()?.max(0.) / 1000.;
+    }
+    if let Some(end) = object_data.get("410") {
+        settings.end = end.parse::<f32>()?.max(0.) / 1000.;
+    }
+    if let Some(fade_out) = object_data.get("411") {
+        settings.fade_out = fade_out.parse::<f32>()?.max(0.) / 1000.;
+    }
+    if let Some(looped) = object_data.get("413") {
+        settings.looped = str_to_bool(looped);
+    }
+    Ok(settings)
+}
+
+fn parse_sound_action(object_data: &ObjectStorage) -> Result<SoundAction, anyhow::Error> {
+    if object_data
+        .get("417")
+        .map(|b| str_to_bool(b))
+        .unwrap_or_default()
+    {
+        return Ok(SoundAction::Stop);
+    }
+    if !object_data
+        .get("416")
+        .map(|b| str_to_bool(b))
+        .unwrap_or_default()
+    {
+        return Ok(SoundAction::Play);
+    }
+    let settings = parse_sound_settings(object_data)?;
+    Ok(SoundAction::Edit {
+        volume: object_data.get("406").map(|_| settings.volume),
+        playback_rate: object_data.get("404").map(|_| settings.playback_rate),
+        duration: match object_data.get("10") {
+            Some(duration) => duration.parse::<f32>()?.max(0.),
+            None => 0.,
+        },
+    })
+}
+
+/// Item references with an id of 0 are unused, except for kinds that don't need an id
+fn parse_item(
+    object_data: &ObjectStorage,
+    id_key: &str,
+    kind_key: &str,
+) -> Result<Option<(ItemKind, u64)>, anyhow::Error> {
+    let id = match object_data.get(id_key) {
+        Some(id) => id.parse()?,
+        None => 0,
+    };
+    let kind = match object_data.get(kind_key) {
+        Some(kind) => ItemKind::from_id(kind.parse()?).unwrap_or_default(),
+        None => ItemKind::Item,
+    };
+    Ok(match kind {
+        ItemKind::Item | ItemKind::Timer if id == 0 => None,
+        kind => Some((kind, id)),
+    })
+}
+
+/// State the triggers keep outside of [`TriggerData`], reset on restart and saved in checkpoints
+#[derive(Clone, Default)]
+pub(crate) struct TriggerState {
+    count_listeners: CountListeners,
+    timer_states: TimerStates,
+    sequence_states: SequenceStates,
+    area_effects: AreaEffects,
+    advanced_follows: AdvancedFollows,
+    global_gradients: GlobalGradients,
+    shader_state: ShaderState,
+    shake_data: ShakeData,
+    level_settings: LevelSettings,
+    camera_settings: CameraSettings,
+    global_enter_effect: GlobalEnterEffect,
+    player_event_listeners: PlayerEventListeners,
+}
+
+impl TriggerState {
+    pub(crate) fn capture(world: &World) -> TriggerState {
+        TriggerState {
+            count_listeners: world.resource::<CountListeners>().clone(),
+            timer_states: world.resource::<TimerStates>().clone(),
+            sequence_states: world.resource::<SequenceStates>().clone(),
+            area_effects: world.resource::<AreaEffects>().clone(),
+            advanced_follows: world.resource::<AdvancedFollows>().clone(),
+            global_gradients: world.resource::<GlobalGradients>().clone(),
+            shader_state: world.resource::<ShaderState>().clone(),
+            shake_data: world.resource::<ShakeData>().clone(),
+            level_settings: world.resource::<LevelSettings>().clone(),
+            camera_settings: world.resource::<CameraSettings>().clone(),
+            global_enter_effect: world.resource::<GlobalEnterEffect>().clone(),
+            player_event_listeners: world.resource::<PlayerEventListeners>().clone(),
+        }
+    }
+
+    pub(crate) fn restore(&self, world: &mut World) {
+        world.insert_resource(self.count_listeners.clone());
+        world.insert_resource(self.timer_states.clone());
+        world.insert_resource(self.sequence_states.clone());
+        world.insert_resource(self.area_effects.clone());
+        world.insert_resource(self.advanced_follows.clone());
+        world.insert_resource(self.global_gradients.clone());
+        world.insert_resource(self.shader_state.clone());
+        world.insert_resource(self.shake_data.clone());
+        world.insert_resource(self.level_settings.clone());
+        world.insert_resource(self.camera_settings.clone());
+        world.insert_resource(self.global_enter_effect.clone());
+
+        // Events from before the restore already happened to the listeners
+        let mut player_event_listeners = self.player_event_listeners.clone();
+        player_event_listeners.skip_sent(world.resource::<Events<PlayerEvent>>());
+        world.insert_resource(player_event_listeners);
+    }
+}
+
+/// Puts everything the triggers change while the level plays back to how it was at the start
+pub(crate) fn reset_trigger_state(world: &mut World) {
+    world.insert_resource(TriggerData::default());
+    TriggerState::default().restore(world);
+    world.insert_resource(Teleports::default());
+    world.insert_resource(AudioQueue::default());
+    world.insert_resource(LevelEnd::default());
+}
+
+pub(crate) fn construct_trigger_index(world: &mut World) {
+    let mut speed_changes = SpeedChanges::default();
+
+    // Start by indexing speed changes
+    let mut speed_change_query = world.query::<(Entity, &SpeedChange, &Transform2d, &Hitbox)>();
+
+    for (entity, speed_change, transform, hitbox) in speed_change_query.iter(world) {
+        if !speed_change.on_path {
+            continue;
+        }
+
+        let global_transform = GlobalTransform2d::from(*transform);
+        let global_hitbox = GlobalHitbox::from((hitbox, transform, &global_transform));
+        speed_changes.0.push((
+            OrderedFloat(global_hitbox.aabb.x),
+            SpeedChangeData {
+                speed_per_sec: speed_change.speed * speed_change.forward_velocity,
+                // Calculate time at pos in SpeedChanges::initialize()
+                time_at_pos: 0.,
+                entity,
+            },
+        ));
+    }
+
+    speed_changes.initialize();
+
+    let mut global_triggers = GlobalTriggers {
+        speed_changes,
+        ..default()
     };
 
     // Then get each of the position activated triggers and precompute their range to create a timeline
@@ -956,6 +2345,7 @@ pub(crate) fn construct_trigger_index(world: &mut World) {
 
     let mut trigger_entities = Vec::new();
     let mut trigger_intervals = Vec::new();
+    let mut trigger_intervals_y = Vec::new();
 
     let mut sorted_triggers = Vec::new();
 
@@ -984,8 +2374,18 @@ pub(crate) fn construct_trigger_index(world: &mut World) {
             trigger_end_pos = trigger_start_pos.next_after(f32::INFINITY);
         }
 
+        // Triggers along Y keep the length they have along X
+        let trigger_start_pos_y = transform.translation.y;
+        let mut trigger_end_pos_y = trigger_start_pos_y + (trigger_end_pos - trigger_start_pos);
+
+        if trigger_start_pos_y >= trigger_end_pos_y {
+            trigger_end_pos_y = trigger_start_pos_y.next_after(f32::INFINITY);
+        }
+
         trigger_entities.push(entity);
         trigger_intervals.push(OrderedFloat(trigger_start_pos)..OrderedFloat(trigger_end_pos));
+        trigger_intervals_y
+            .push(OrderedFloat(trigger_start_pos_y)..OrderedFloat(trigger_end_pos_y));
     }
 
     let interval_ids: Vec<u32> = trigger_intervals
@@ -999,6 +2399,10 @@ pub(crate) fn construct_trigger_index(world: &mut World) {
         GlobalTriggerChannel {
             x: (
                 IntervalSetGeneric::new_with_ids(&trigger_intervals, &interval_ids).unwrap(),
+                trigger_entities.clone(),
+            ),
+            y: (
+                IntervalSetGeneric::new_with_ids(&trigger_intervals_y, &interval_ids).unwrap(),
                 trigger_entities,
             ),
         },
@@ -1006,5 +2410,22 @@ pub(crate) fn construct_trigger_index(world: &mut World) {
 
     world.insert_resource(global_triggers);
     world.init_resource::<PickupValues>();
-    world.init_resource::<ShakeData>();
+    reset_trigger_state(world);
+
+    let mut unsupported_triggers = UnsupportedTriggers::default();
+
+    let mut unsupported_query = world.query::<&UnsupportedTrigger>();
+
+    for unsupported_trigger in unsupported_query.iter(world) {
+        *unsupported_triggers
+            .0
+            .entry(unsupported_trigger.0)
+            .or_default() += 1;
+    }
+
+    unsupported_triggers
+        .0
+        .sort_unstable_by(|a, _, b, _| a.cmp(b));
+
+    world.insert_resource(unsupported_triggers);
 }
@@ -0,0 +1,351 @@
+use std::any::Any;
+use std::f32::consts::{PI, TAU};
+use std::ops::Range;
+
+use bevy::ecs::system::SystemState;
+use bevy::math::{Vec2, Vec3Swizzles};
+use bevy::prelude::{Entity, Query, Res, ResMut, Resource, Without, World};
+use bevy::time::Time;
+use indexmap::IndexMap;
+
+use crate::level::group::{GlobalGroup, GlobalGroupDeltas, GlobalGroups, RotationKind};
+use crate::level::player::Player;
+use crate::level::portal::DualPlayer;
+use crate::level::transform::Transform2d;
+use crate::level::trigger::{GlobalTriggers, GroupRemap, TriggerFunction};
+use crate::utils::U64Hash;
+
+#[derive(Clone, Debug)]
+pub(crate) struct AdvancedFollowTrigger {
+    pub(crate) target_group: u64,
+    /// Follows the player when `None`
+    pub(crate) follow_group: Option<u64>,
+    pub(crate) speed: f32,
+    pub(crate) max_speed: f32,
+    pub(crate) acceleration: f32,
+    pub(crate) friction: f32,
+    pub(crate) max_range: f32,
+    pub(crate) delay: f32,
+    pub(crate) rotate_direction: bool,
+    pub(crate) rotation_offset: f32,
+    pub(crate) rotation_easing: f32,
+}
+
+impl Default for AdvancedFollowTrigger {
+    fn default() -> Self {
+        Self {
+            target_group: 0,
+            follow_group: None,
+            speed: 1.,
+            max_speed: 0.,
+            acceleration: 0.,
+            friction: 0.,
+            max_range: 0.,
+            delay: 0.,
+            rotate_direction: false,
+            rotation_offset: 0.,
+            rotation_easing: 0.,
+        }
+    }
+}
+
+#[derive(Clone, Debug, Default)]
+pub(crate) struct EditAdvancedFollowTrigger {
+    pub(crate) target_group: u64,
+    pub(crate) speed: Option<f32>,
+    pub(crate) acceleration: Option<f32>,
+    pub(crate) friction: Option<f32>,
+    pub(crate) max_range: Option<f32>,
+    pub(crate) velocity: Option<Vec2>,
+    pub(crate) add_velocity: bool,
+}
+
+#[derive(Clone)]
+struct AdvancedFollowState {
+    follow: AdvancedFollowTrigger,
+    start_time: f32,
+    velocity: Vec2,
+    angle: f32,
+}
+
+#[derive(Clone, Default, Resource)]
+pub(crate) struct AdvancedFollows(IndexMap<u64, AdvancedFollowState, U64Hash>);
+
+pub(crate) fn update_advanced_follow(
+    time: Res<Time>,
+    mut advanced_follows: ResMut<AdvancedFollows>,
+    global_groups: Res<GlobalGroups>,
+    global_triggers: Res<GlobalTriggers>,
+    players: Query<(&Player, &Transform2d), Without<DualPlayer>>,
+    objects: Query<&Transform2d, Without<Player>>,
+    mut groups: Query<(&GlobalGroup, &mut GlobalGroupDeltas)>,
+) {
+    if advanced_follows.0.is_empty() {
+        return;
+    }
+
+    let Some((player, player_transform)) = players.iter().next() else {
+        return;
+    };
+
+    let delta_time = time.delta_seconds();
+
+    if delta_time == 0. {
+        return;
+    }
+
+    let level_time = global_triggers.speed_changes.time_for_pos(player.distance);
+
+    for (target_group, state) in advanced_follows.0.iter_mut() {
+        if level_time < state.start_time + state.follow.delay {
+            continue;
+        }
+
+        let Some(target_group_entity) = global_groups.0.get(*target_group as usize) else {
+            continue;
+        };
+
+        let Ok((target_group, _)) = groups.get(*target_group_entity) else {
+            continue;
+        };
+
+        let Some(position) = target_group
+            .root_entities
+            .first()
+            .and_then(|entity| objects.get(*entity).ok())
+        else {
+            continue;
+        };
+
+        let position = position.translation.xy();
+
+        let followed_position = match state.follow.follow_group {
+            Some(follow_group) => {
+                let Some(follow_group_entity) = global_groups.0.get(follow_group as usize) else {
+                    continue;
+                };
+
+                let Some(followed) = groups
+                    .get(*follow_group_entity)
+                    .ok()
+                    .and_then(|(group, _)| group.root_entities.first())
+                    .and_then(|entity| objects.get(*entity).ok())
+                else {
+                    continue;
+                };
+
+                followed.translation.xy()
+            }
+            None => player_transform.translation.xy(),
+        };
+
+        let offset = followed_position - position;
+        let distance = offset.length();
+
+        let in_range = state.follow.max_range <= 0. || distance <= state.follow.max_range;
+
+        let target_velocity = if in_range && distance > f32::EPSILON {
+            // Speed is given in blocks per second
+            offset / distance * state.follow.speed * 30.
+        } else {
+            Vec2::ZERO
+        };
+
+        if state.follow.acceleration > 0. {
+            let max_change = state.follow.acceleration * 30. * delta_time;
+            let change = target_velocity - state.velocity;
+            state.velocity += change.clamp_length_max(max_change);
+        } else {
+            state.velocity = target_velocity;
+        }
+
+        if state.follow.friction > 0. {
+            state.velocity *= (1. - state.follow.friction * delta_time).max(0.);
+        }
+
+        if state.follow.max_speed > 0. {
+            state.velocity = state
+                .velocity
+                .clamp_length_max(state.follow.max_speed * 30.);
+        }
+
+        let mut delta = state.velocity * delta_time;
+
+        // Don't overshoot the followed position when there is no acceleration to carry it past
+        if state.follow.acceleration <= 0. && delta.length_squared() > offset.length_squared() {
+            delta = offset;
+        }
+
+        let mut rotation = 0.;
+
+        if state.follow.rotate_direction && state.velocity.length_squared() > f32::EPSILON {
+            let target_angle =
+                state.velocity.y.atan2(state.velocity.x) + state.follow.rotation_offset;
+            let mut difference = (target_angle - state.angle).rem_euclid(TAU);
+            if difference > PI {
+                difference -= TAU;
+            }
+            if state.follow.rotation_easing > 0. {
+                difference *= (delta_time * 60. / state.follow.rotation_easing).min(1.);
+            }
+            state.angle += difference;
+            rotation = difference;
+        }
+
+        let Ok((_, mut group_deltas)) = groups.get_mut(*target_group_entity) else {
+            continue;
+        };
+
+        group_deltas.translation_delta += delta;
+
+        if rotation != 0. {
+            if let RotationKind::Angle(angle) = &mut group_deltas.rotation {
+                *angle += rotation;
+            }
+        }
+    }
+}
+
+type AdvancedFollowTriggerSystemParam = (
+    Res<'static, GlobalTriggers>,
+    ResMut<'static, AdvancedFollows>,
+);
+
+impl TriggerFunction for AdvancedFollowTrigger {
+    fn execute(
+        &self,
+        world: &mut World,
+        _: Entity,
+        system_state: &mut Box<dyn Any + Send + Sync>,
+        _: f32,
+        progress: f32,
+        range: Range<f32>,
+    ) {
+        if progress != 1. {
+            return;
+        }
+
+        let system_state: &mut SystemState<AdvancedFollowTriggerSystemParam> =
+            system_state.downcast_mut().unwrap();
+
+        let (global_triggers, mut advanced_follows) = system_state.get_mut(world);
+
+        let start_time = global_triggers.speed_changes.time_for_pos(range.start);
+
+        let (velocity, angle) = advanced_follows
+            .0
+            .get(&self.target_group)
+            .map(|state| (state.velocity, state.angle))
+            .unwrap_or_default();
+
+        advanced_follows.0.insert(
+            self.target_group,
+            AdvancedFollowState {
+                follow: self.clone(),
+                start_time,
+                velocity,
+                angle,
+            },
+        );
+    }
+
+    fn create_system_state(&self, world: &mut World) -> Box<dyn Any + Send + Sync> {
+        Box::new(SystemState::<AdvancedFollowTriggerSystemParam>::new(world))
+    }
+
+    fn target_id(&self) -> u64 {
+        self.target_group
+    }
+
+    fn duration(&self) -> f32 {
+        0.
+    }
+
+    fn exclusive(&self) -> bool {
+        false
+    }
+
+    fn post(&self) -> bool {
+        false
+    }
+
+    fn remap(&mut self, remap: &GroupRemap) {
+        self.target_group = remap.get(self.target_group);
+        if let Some(follow_group) = &mut self.follow_group {
+            *follow_group = remap.get(*follow_group);
+        }
+    }
+}
+
+type EditAdvancedFollowTriggerSystemParam = ResMut<'static, AdvancedFollows>;
+
+impl TriggerFunction for EditAdvancedFollowTrigger {
+    fn execute(
+        &self,
+        world: &mut World,
+        _: Entity,
+        system_state: &mut Box<dyn Any + Send + Sync>,
+        _: f32,
+        progress: f32,
+        _: Range<f32>,
+    ) {
+        if progress != 1. {
+            return;
+        }
+
+        let system_state: &mut SystemState<EditAdvancedFollowTriggerSystemParam> =
+            system_state.downcast_mut().unwrap();
+
+        let mut advanced_follows = system_state.get_mut(world);
+
+        let Some(state) = advanced_follows.0.get_mut(&self.target_group) else {
+            return;
+        };
+
+        if let Some(speed) = self.speed {
+            state.follow.speed = speed;
+        }
+        if let Some(acceleration) = self.acceleration {
+            state.follow.acceleration = acceleration;
+        }
+        if let Some(friction) = self.friction {
+            state.follow.friction = friction;
+        }
+        if let Some(max_range) = self.max_range {
+            state.follow.max_range = max_range;
+        }
+        if let Some(velocity) = self.velocity {
+            if self.add_velocity {
+                state.velocity += velocity * 30.;
+            } else {
+                state.velocity = velocity * 30.;
+            }
+        }
+    }
+
+    fn create_system_state(&self, world: &mut World) -> Box<dyn Any + Send + Sync> {
+        Box::new(SystemState::<EditAdvancedFollowTriggerSystemParam>::new(
+            world,
+        ))
+    }
+
+    fn target_id(&self) -> u64 {
+        self.target_group
+    }
+
+    fn duration(&self) -> f32 {
+        0.
+    }
+
+    fn exclusive(&self) -> bool {
+        false
+    }
+
+    fn post(&self) -> bool {
+        false
+    }
+
+    fn remap(&mut self, remap: &GroupRemap) {
+        self.target_group = remap.get(self.target_group);
+    }
+}
@@ -5,7 +5,7 @@ use bevy::ecs::system::SystemState;
 use bevy::prelude::{Entity, Query, Res, World};
 
 use crate::level::group::{GlobalGroup, GlobalGroups};
-use crate::level::trigger::TriggerFunction;
+use crate::level::trigger::{GroupRemap, TriggerFunction};
 use crate::utils::{lerp, lerp_start};
 
 #[derive(Clone, Debug, Default)]
@@ -67,4 +67,8 @@ impl TriggerFunction for AlphaTrigger {
     fn post(&self) -> bool {
         false
     }
+
+    fn remap(&mut self, remap: &GroupRemap) {
+        self.target_group = remap.get(self.target_group);
+    }
 }
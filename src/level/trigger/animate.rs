@@ -0,0 +1,75 @@
+use std::any::Any;
+use std::ops::Range;
+
+use bevy::ecs::system::SystemState;
+use bevy::prelude::{Entity, Query, Res, World};
+
+use crate::level::animation::FrameAnimation;
+use crate::level::group::{GlobalGroup, GlobalGroups};
+use crate::level::trigger::{GroupRemap, TriggerFunction};
+
+#[derive(Clone, Debug, Default)]
+pub(crate) struct AnimateTrigger {
+    pub(crate) target_group: u64,
+    pub(crate) animation: usize,
+}
+
+type AnimateTriggerSystemParam = (
+    Res<'static, GlobalGroups>,
+    Query<'static, 'static, &'static GlobalGroup>,
+    Query<'static, 'static, &'static mut FrameAnimation>,
+);
+
+impl TriggerFunction for AnimateTrigger {
+    fn execute(
+        &self,
+        world: &mut World,
+        _: Entity,
+        system_state: &mut Box<dyn Any + Send + Sync>,
+        _: f32,
+        _: f32,
+        _: Range<f32>,
+    ) {
+        let system_state: &mut SystemState<AnimateTriggerSystemParam> =
+            system_state.downcast_mut().unwrap();
+
+        let (global_groups, group_query, mut frame_animations) = system_state.get_mut(world);
+
+        let Some(group_entity) = global_groups.0.get(self.target_group as usize) else {
+            return;
+        };
+
+        let Ok(global_group) = group_query.get(*group_entity) else {
+            return;
+        };
+
+        let mut iter = frame_animations.iter_many_mut(&global_group.root_entities);
+        while let Some(mut frame_animation) = iter.fetch_next() {
+            frame_animation.play(self.animation);
+        }
+    }
+
+    fn create_system_state(&self, world: &mut World) -> Box<dyn Any + Send + Sync> {
+        Box::new(SystemState::<AnimateTriggerSystemParam>::new(world))
+    }
+
+    fn target_id(&self) -> u64 {
+        self.target_group
+    }
+
+    fn duration(&self) -> f32 {
+        0.
+    }
+
+    fn exclusive(&self) -> bool {
+        false
+    }
+
+    fn post(&self) -> bool {
+        false
+    }
+
+    fn remap(&mut self, remap: &GroupRemap) {
+        self.target_group = remap.get(self.target_group);
+    }
+}
@@ -0,0 +1,550 @@
+use std::any::Any;
+use std::ops::Range;
+
+use bevy::ecs::system::SystemState;
+use bevy::hierarchy::{Children, Parent};
+use bevy::math::{Vec2, Vec3Swizzles, Vec4};
+use bevy::prelude::{Entity, Query, Res, ResMut, Resource, Without, World};
+use bevy::utils::HashMap;
+
+use crate::level::color::{ColorChannelCalculated, GlobalColorChannels, ObjectColorCalculated};
+use crate::level::easing::Easing;
+use crate::level::group::{GlobalGroup, GlobalGroups};
+use crate::level::player::Player;
+use crate::level::portal::DualPlayer;
+use crate::level::section::{GlobalSections, Section};
+use crate::level::transform::Transform2d;
+use crate::level::trigger::camera::CameraSettings;
+use crate::level::trigger::enter_effect::{EnterEffect, GlobalEnterEffect};
+use crate::level::trigger::{GlobalTriggers, GroupRemap, Trigger, TriggerFunction};
+use crate::utils::{lerp, lerp_start};
+
+#[derive(Clone, Copy, Debug)]
+pub(crate) enum AreaEffectKind {
+    Move(Vec2),
+    Rotate(f32),
+    Scale(Vec2),
+    Fade(f32),
+    Tint(u64, f32),
+}
+
+impl AreaEffectKind {
+    fn lerp(self, end: AreaEffectKind, previous_progress: f32, progress: f32) -> AreaEffectKind {
+        match (self, end) {
+            (AreaEffectKind::Move(current), AreaEffectKind::Move(end)) => AreaEffectKind::Move(
+                lerp(lerp_start(current, end, previous_progress), end, progress),
+            ),
+            (AreaEffectKind::Rotate(current), AreaEffectKind::Rotate(end)) => {
+                AreaEffectKind::Rotate(lerp(
+                    lerp_start(current, end, previous_progress),
+                    end,
+                    progress,
+                ))
+            }
+            (AreaEffectKind::Scale(current), AreaEffectKind::Scale(end)) => AreaEffectKind::Scale(
+                lerp(lerp_start(current, end, previous_progress), end, progress),
+            ),
+            (AreaEffectKind::Fade(current), AreaEffectKind::Fade(end)) => AreaEffectKind::Fade(
+                lerp(lerp_start(current, end, previous_progress), end, progress),
+            ),
+            (AreaEffectKind::Tint(_, current), AreaEffectKind::Tint(channel, end)) => {
+                AreaEffectKind::Tint(
+                    channel,
+                    lerp(lerp_start(current, end, previous_progress), end, progress),
+                )
+            }
+            _ => self,
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub(crate) struct AreaEffect {
+    pub(crate) kind: AreaEffectKind,
+    pub(crate) target_group: u64,
+    pub(crate) center_group: u64,
+    pub(crate) length: f32,
+    pub(crate) deadzone: f32,
+    pub(crate) inward: bool,
+    pub(crate) falloff: Easing,
+    pub(crate) duration: f32,
+    pub(crate) easing: Easing,
+}
+
+impl AreaEffect {
+    /// Strength of the effect for an object at `distance` from the center
+    fn strength(&self, distance: f32) -> f32 {
+        if self.length <= 0. {
+            return 0.;
+        }
+
+        // Full at the center, fading out to nothing at the edge of the area
+        let x = (1. - distance / self.length).max(0.);
+
+        // Objects in the deadzone around the center get the full effect
+        let mut x = (x / (1. - self.deadzone).max(f32::EPSILON)).min(1.);
+
+        // Inward areas are the other way around, so objects are at rest at the center
+        if self.inward {
+            x = 1. - x;
+        }
+
+        self.falloff.sample(x)
+    }
+}
+
+#[derive(Clone, Debug)]
+pub(crate) struct AreaTrigger {
+    pub(crate) effect_id: u64,
+    pub(crate) effect: AreaEffect,
+}
+
+#[derive(Clone, Debug)]
+pub(crate) struct AreaEditTrigger {
+    pub(crate) effect_id: u64,
+    pub(crate) duration: f32,
+    pub(crate) kind: AreaEffectKind,
+    pub(crate) length: Option<f32>,
+    pub(crate) deadzone: Option<f32>,
+}
+
+#[derive(Clone, Debug, Default)]
+pub(crate) struct AreaStopTrigger {
+    pub(crate) effect_id: u64,
+}
+
+#[derive(Clone, Copy)]
+struct AreaOffset {
+    translation: Vec2,
+    angle: f32,
+    scale: Vec2,
+    opacity: f32,
+    tint: Option<(Entity, f32)>,
+}
+
+impl Default for AreaOffset {
+    fn default() -> Self {
+        Self {
+            translation: Vec2::ZERO,
+            angle: 0.,
+            scale: Vec2::ONE,
+            opacity: 1.,
+            tint: None,
+        }
+    }
+}
+
+#[derive(Clone, Default, Resource)]
+pub(crate) struct AreaEffects {
+    /// Effect id, start time and the effect itself
+    effects: Vec<(u64, f32, AreaEffect)>,
+    applied: HashMap<Entity, AreaOffset>,
+}
+
+pub(crate) fn update_area_effects(
+    mut area_effects: ResMut<AreaEffects>,
+    global_groups: Res<GlobalGroups>,
+    global_triggers: Res<GlobalTriggers>,
+    global_sections: Res<GlobalSections>,
+    global_color_channels: Res<GlobalColorChannels>,
+    global_enter_effect: Res<GlobalEnterEffect>,
+    camera_settings: Res<CameraSettings>,
+    players: Query<&Player, Without<DualPlayer>>,
+    groups: Query<&GlobalGroup>,
+    mut objects: Query<
+        (&mut Transform2d, &Section),
+        (Without<Parent>, Without<Trigger>, Without<Player>),
+    >,
+) {
+    let Some(player) = players.iter().next() else {
+        return;
+    };
+
+    let enter_effect = global_enter_effect.0;
+
+    if area_effects.effects.is_empty()
+        && area_effects.applied.is_empty()
+        && enter_effect == EnterEffect::None
+    {
+        return;
+    }
+
+    let time = global_triggers.speed_changes.time_for_pos(player.distance);
+
+    let visible = global_sections.visible.start as u32..global_sections.visible.end as u32;
+
+    let AreaEffects { effects, applied } = &mut *area_effects;
+
+    let mut offsets: HashMap<Entity, AreaOffset> = HashMap::new();
+
+    for (_, start_time, effect) in effects.iter() {
+        let Some(center_group) = global_groups.0.get(effect.center_group as usize) else {
+            continue;
+        };
+        let Some(target_group) = global_groups.0.get(effect.target_group as usize) else {
+            continue;
+        };
+        let (Ok(center_group), Ok(target_group)) =
+            (groups.get(*center_group), groups.get(*target_group))
+        else {
+            continue;
+        };
+        let Some(center) = center_group
+            .root_entities
+            .first()
+            .and_then(|entity| objects.get(*entity).ok())
+            .map(|(transform, _)| {
+                transform.translation.xy()
+                    - applied
+                        .get(&center_group.root_entities[0])
+                        .map(|offset| offset.translation)
+                        .unwrap_or_default()
+            })
+        else {
+            continue;
+        };
+
+        let ramp = if effect.duration > 0. {
+            effect
+                .easing
+                .sample(((time - start_time) / effect.duration).clamp(0., 1.))
+        } else {
+            1.
+        };
+
+        if ramp == 0. {
+            continue;
+        }
+
+        let tint_channel = if let AreaEffectKind::Tint(channel, _) = effect.kind {
+            global_color_channels.0.get(&channel).copied()
+        } else {
+            None
+        };
+
+        for (entity, (transform, section)) in target_group
+            .root_entities
+            .iter()
+            .filter_map(|entity| Some((*entity, objects.get(*entity).ok()?)))
+        {
+            if !visible.contains(&section.current) {
+                continue;
+            }
+
+            let base_translation = transform.translation.xy()
+                - applied
+                    .get(&entity)
+                    .map(|offset| offset.translation)
+                    .unwrap_or_default();
+
+            let strength = effect.strength(base_translation.distance(center)) * ramp;
+
+            if strength == 0. {
+                continue;
+            }
+
+            let offset = offsets.entry(entity).or_default();
+
+            match effect.kind {
+                AreaEffectKind::Move(translation) => offset.translation += translation * strength,
+                AreaEffectKind::Rotate(angle) => offset.angle += angle * strength,
+                AreaEffectKind::Scale(scale) => {
+                    offset.scale *= lerp(Vec2::ONE, scale, strength).max(Vec2::splat(0.001))
+                }
+                AreaEffectKind::Fade(opacity) => offset.opacity *= lerp(1., opacity, strength),
+                AreaEffectKind::Tint(_, amount) => {
+                    if let Some(channel) = tint_channel {
+                        offset.tint = Some((channel, amount * strength));
+                    }
+                }
+            }
+        }
+    }
+
+    // Nothing is known about the screen until the camera has been placed once
+    if enter_effect != EnterEffect::None && camera_settings.half_extents != Vec2::ZERO {
+        let left = camera_settings.position.x - camera_settings.half_extents.x;
+        let right = camera_settings.position.x + camera_settings.half_extents.x;
+
+        for section in &global_sections.sections[global_sections.visible.clone()] {
+            for entity in section {
+                let Ok((transform, _)) = objects.get(*entity) else {
+                    continue;
+                };
+
+                let base_x = transform.translation.x
+                    - applied
+                        .get(entity)
+                        .map(|offset| offset.translation.x)
+                        .unwrap_or_default();
+
+                let strength = enter_effect.strength(base_x, left, right);
+
+                if strength == 0. {
+                    continue;
+                }
+
+                let offset = offsets.entry(*entity).or_default();
+
+                match enter_effect {
+                    EnterEffect::None => (),
+                    EnterEffect::Fade(..) => offset.opacity *= 1. - strength,
+                    EnterEffect::Move(translation) => offset.translation += translation * strength,
+                    EnterEffect::Scale(scale) => {
+                        offset.scale *= lerp(1., scale, strength).max(0.001)
+                    }
+                }
+            }
+        }
+    }
+
+    for (entity, old_offset) in applied.iter() {
+        if offsets.contains_key(entity) {
+            continue;
+        }
+
+        let Ok((mut transform, section)) = objects.get_mut(*entity) else {
+            continue;
+        };
+
+        // Objects that went out of view keep their offset until they get evaluated again
+        if !visible.contains(&section.current) {
+            offsets.insert(*entity, *old_offset);
+            continue;
+        }
+
+        apply_offset(&mut transform, old_offset, &AreaOffset::default());
+    }
+
+    for (entity, offset) in offsets.iter() {
+        let old_offset = applied.get(entity).copied().unwrap_or_default();
+
+        let Ok((mut transform, _)) = objects.get_mut(*entity) else {
+            continue;
+        };
+
+        apply_offset(&mut transform, &old_offset, offset);
+    }
+
+    *applied = offsets;
+}
+
+fn apply_offset(transform: &mut Transform2d, old: &AreaOffset, new: &AreaOffset) {
+    if old.translation != new.translation {
+        let delta = new.translation - old.translation;
+        transform.translation.x += delta.x;
+        transform.translation.y += delta.y;
+    }
+    if old.angle != new.angle {
+        transform.angle += new.angle - old.angle;
+    }
+    if old.scale != new.scale {
+        transform.scale *= new.scale / old.scale;
+    }
+}
+
+pub(crate) fn apply_area_colors(
+    area_effects: Res<AreaEffects>,
+    color_channels: Query<&ColorChannelCalculated>,
+    mut objects: Query<(&mut ObjectColorCalculated, Option<&Children>)>,
+) {
+    for (entity, offset) in &area_effects.applied {
+        if offset.opacity == 1. && offset.tint.is_none() {
+            continue;
+        }
+
+        let tint = offset
+            .tint
+            .and_then(|(channel, amount)| Some((color_channels.get(channel).ok()?.color, amount)));
+
+        apply_color_recursive(*entity, offset.opacity, tint, &mut objects);
+    }
+}
+
+fn apply_color_recursive(
+    entity: Entity,
+    opacity: f32,
+    tint: Option<(Vec4, f32)>,
+    objects: &mut Query<(&mut ObjectColorCalculated, Option<&Children>)>,
+) {
+    let Ok((mut calculated, children)) = objects.get_mut(entity) else {
+        return;
+    };
+
+    if let Some((color, amount)) = tint {
+        let alpha = calculated.color[3];
+        calculated.color = lerp(calculated.color, color, amount);
+        calculated.color[3] = alpha;
+    }
+    calculated.color[3] *= opacity;
+
+    let Some(children) = children else {
+        return;
+    };
+
+    for child in children.to_vec() {
+        apply_color_recursive(child, opacity, tint, objects);
+    }
+}
+
+type AreaTriggerSystemParam = (Res<'static, GlobalTriggers>, ResMut<'static, AreaEffects>);
+
+impl TriggerFunction for AreaTrigger {
+    fn execute(
+        &self,
+        world: &mut World,
+        _: Entity,
+        system_state: &mut Box<dyn Any + Send + Sync>,
+        _: f32,
+        progress: f32,
+        range: Range<f32>,
+    ) {
+        if progress != 1. {
+            return;
+        }
+
+        let system_state: &mut SystemState<AreaTriggerSystemParam> =
+            system_state.downcast_mut().unwrap();
+
+        let (global_triggers, mut area_effects) = system_state.get_mut(world);
+
+        let start_time = global_triggers.speed_changes.time_for_pos(range.start);
+
+        area_effects
+            .effects
+            .push((self.effect_id, start_time, self.effect.clone()));
+    }
+
+    fn create_system_state(&self, world: &mut World) -> Box<dyn Any + Send + Sync> {
+        Box::new(SystemState::<AreaTriggerSystemParam>::new(world))
+    }
+
+    fn target_id(&self) -> u64 {
+        self.effect_id
+    }
+
+    fn duration(&self) -> f32 {
+        0.
+    }
+
+    fn exclusive(&self) -> bool {
+        false
+    }
+
+    fn post(&self) -> bool {
+        false
+    }
+
+    fn remap(&mut self, remap: &GroupRemap) {
+        self.effect.target_group = remap.get(self.effect.target_group);
+        self.effect.center_group = remap.get(self.effect.center_group);
+    }
+}
+
+type AreaEditTriggerSystemParam = ResMut<'static, AreaEffects>;
+
+impl TriggerFunction for AreaEditTrigger {
+    fn execute(
+        &self,
+        world: &mut World,
+        _: Entity,
+        system_state: &mut Box<dyn Any + Send + Sync>,
+        previous_progress: f32,
+        progress: f32,
+        _: Range<f32>,
+    ) {
+        let system_state: &mut SystemState<AreaEditTriggerSystemParam> =
+            system_state.downcast_mut().unwrap();
+
+        let mut area_effects = system_state.get_mut(world);
+
+        for (_, _, effect) in area_effects
+            .effects
+            .iter_mut()
+            .filter(|(effect_id, _, _)| *effect_id == self.effect_id)
+        {
+            effect.kind = effect.kind.lerp(self.kind, previous_progress, progress);
+            if let Some(length) = self.length {
+                effect.length = lerp(
+                    lerp_start(effect.length, length, previous_progress),
+                    length,
+                    progress,
+                );
+            }
+            if let Some(deadzone) = self.deadzone {
+                effect.deadzone = lerp(
+                    lerp_start(effect.deadzone, deadzone, previous_progress),
+                    deadzone,
+                    progress,
+                );
+            }
+        }
+    }
+
+    fn create_system_state(&self, world: &mut World) -> Box<dyn Any + Send + Sync> {
+        Box::new(SystemState::<AreaEditTriggerSystemParam>::new(world))
+    }
+
+    fn target_id(&self) -> u64 {
+        self.effect_id
+    }
+
+    fn duration(&self) -> f32 {
+        self.duration
+    }
+
+    fn exclusive(&self) -> bool {
+        true
+    }
+
+    fn post(&self) -> bool {
+        false
+    }
+}
+
+type AreaStopTriggerSystemParam = ResMut<'static, AreaEffects>;
+
+impl TriggerFunction for AreaStopTrigger {
+    fn execute(
+        &self,
+        world: &mut World,
+        _: Entity,
+        system_state: &mut Box<dyn Any + Send + Sync>,
+        _: f32,
+        progress: f32,
+        _: Range<f32>,
+    ) {
+        if progress != 1. {
+            return;
+        }
+
+        let system_state: &mut SystemState<AreaStopTriggerSystemParam> =
+            system_state.downcast_mut().unwrap();
+
+        let mut area_effects = system_state.get_mut(world);
+
+        area_effects
+            .effects
+            .retain(|(effect_id, _, _)| *effect_id != self.effect_id);
+    }
+
+    fn create_system_state(&self, world: &mut World) -> Box<dyn Any + Send + Sync> {
+        Box::new(SystemState::<AreaStopTriggerSystemParam>::new(world))
+    }
+
+    fn target_id(&self) -> u64 {
+        self.effect_id
+    }
+
+    fn duration(&self) -> f32 {
+        0.
+    }
+
+    fn exclusive(&self) -> bool {
+        false
+    }
+
+    fn post(&self) -> bool {
+        false
+    }
+}
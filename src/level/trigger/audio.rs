@@ -0,0 +1,207 @@
+use std::any::Any;
+use std::ops::Range;
+
+use bevy::ecs::system::SystemState;
+use bevy::prelude::{Entity, Res, ResMut, Resource, World};
+
+use crate::level::trigger::{GlobalTriggers, GroupRemap, TriggerFunction};
+
+#[derive(Clone, Debug)]
+pub(crate) struct SoundSettings {
+    pub(crate) volume: f32,
+    pub(crate) playback_rate: f32,
+    /// Times are in seconds
+    pub(crate) start: f32,
+    pub(crate) end: f32,
+    pub(crate) fade_in: f32,
+    pub(crate) fade_out: f32,
+    pub(crate) looped: bool,
+}
+
+impl Default for SoundSettings {
+    fn default() -> Self {
+        Self {
+            volume: 1.,
+            playback_rate: 1.,
+            start: 0.,
+            end: 0.,
+            fade_in: 0.,
+            fade_out: 0.,
+            looped: false,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug)]
+pub(crate) struct SpatialSettings {
+    pub(crate) center_group: u64,
+    pub(crate) min_distance: f32,
+    pub(crate) max_distance: f32,
+}
+
+#[derive(Clone, Debug, Default)]
+pub(crate) enum SoundAction {
+    #[default]
+    Play,
+    /// Changes the volume and speed of what is already playing over `duration`
+    Edit {
+        volume: Option<f32>,
+        playback_rate: Option<f32>,
+        duration: f32,
+    },
+    Stop,
+}
+
+/// Songs are identified by their channel and sound effects by their unique id
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash)]
+pub(crate) enum SoundKey {
+    Song(u64),
+    Sfx(u64),
+}
+
+#[derive(Clone, Debug)]
+pub(crate) struct AudioCommand {
+    pub(crate) key: SoundKey,
+    /// Song or sound effect id to load
+    pub(crate) sound_id: u64,
+    pub(crate) action: SoundAction,
+    pub(crate) settings: SoundSettings,
+    pub(crate) spatial: Option<SpatialSettings>,
+    /// Level time the command was issued at, used to seek when resuming
+    pub(crate) level_time: f32,
+}
+
+/// Audio changes requested by triggers, played back by the main world
+#[derive(Default, Resource)]
+pub(crate) struct AudioQueue(pub(crate) Vec<AudioCommand>);
+
+#[derive(Clone, Debug, Default)]
+pub(crate) struct SongTrigger {
+    pub(crate) song_id: u64,
+    pub(crate) channel: u64,
+    pub(crate) action: SoundAction,
+    pub(crate) settings: SoundSettings,
+}
+
+#[derive(Clone, Debug, Default)]
+pub(crate) struct SfxTrigger {
+    pub(crate) sfx_id: u64,
+    pub(crate) unique_id: u64,
+    pub(crate) action: SoundAction,
+    pub(crate) settings: SoundSettings,
+    pub(crate) spatial: Option<SpatialSettings>,
+}
+
+type AudioTriggerSystemParam = (Res<'static, GlobalTriggers>, ResMut<'static, AudioQueue>);
+
+fn queue_command(
+    world: &mut World,
+    system_state: &mut Box<dyn Any + Send + Sync>,
+    range: Range<f32>,
+    command: impl FnOnce(f32) -> AudioCommand,
+) {
+    let system_state: &mut SystemState<AudioTriggerSystemParam> =
+        system_state.downcast_mut().unwrap();
+
+    let (global_triggers, mut audio_queue) = system_state.get_mut(world);
+
+    let level_time = global_triggers.speed_changes.time_for_pos(range.start);
+
+    audio_queue.0.push(command(level_time));
+}
+
+impl TriggerFunction for SongTrigger {
+    fn execute(
+        &self,
+        world: &mut World,
+        _: Entity,
+        system_state: &mut Box<dyn Any + Send + Sync>,
+        _: f32,
+        progress: f32,
+        range: Range<f32>,
+    ) {
+        if progress != 1. {
+            return;
+        }
+
+        queue_command(world, system_state, range, |level_time| AudioCommand {
+            key: SoundKey::Song(self.channel),
+            sound_id: self.song_id,
+            action: self.action.clone(),
+            settings: self.settings.clone(),
+            spatial: None,
+            level_time,
+        });
+    }
+
+    fn create_system_state(&self, world: &mut World) -> Box<dyn Any + Send + Sync> {
+        Box::new(SystemState::<AudioTriggerSystemParam>::new(world))
+    }
+
+    fn target_id(&self) -> u64 {
+        self.channel
+    }
+
+    fn duration(&self) -> f32 {
+        0.
+    }
+
+    fn exclusive(&self) -> bool {
+        false
+    }
+
+    fn post(&self) -> bool {
+        false
+    }
+}
+
+impl TriggerFunction for SfxTrigger {
+    fn execute(
+        &self,
+        world: &mut World,
+        _: Entity,
+        system_state: &mut Box<dyn Any + Send + Sync>,
+        _: f32,
+        progress: f32,
+        range: Range<f32>,
+    ) {
+        if progress != 1. {
+            return;
+        }
+
+        queue_command(world, system_state, range, |level_time| AudioCommand {
+            key: SoundKey::Sfx(self.unique_id),
+            sound_id: self.sfx_id,
+            action: self.action.clone(),
+            settings: self.settings.clone(),
+            spatial: self.spatial,
+            level_time,
+        });
+    }
+
+    fn create_system_state(&self, world: &mut World) -> Box<dyn Any + Send + Sync> {
+        Box::new(SystemState::<AudioTriggerSystemParam>::new(world))
+    }
+
+    fn target_id(&self) -> u64 {
+        self.unique_id
+    }
+
+    fn duration(&self) -> f32 {
+        0.
+    }
+
+    fn exclusive(&self) -> bool {
+        false
+    }
+
+    fn post(&self) -> bool {
+        false
+    }
+
+    fn remap(&mut self, remap: &GroupRemap) {
+        if let Some(spatial) = &mut self.spatial {
+            spatial.center_group = remap.get(spatial.center_group);
+        }
+    }
+}
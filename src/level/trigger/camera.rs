@@ -0,0 +1,517 @@
+use std::any::Any;
+use std::ops::Range;
+
+use bevy::ecs::system::SystemState;
+use bevy::math::{Vec2, Vec3Swizzles};
+use bevy::prelude::{Entity, Query, Res, ResMut, Resource, Without, World};
+
+use crate::level::easing::Easing;
+use crate::level::group::{GlobalGroup, GlobalGroupDeltas, GlobalGroups};
+use crate::level::player::Player;
+use crate::level::transform::Transform2d;
+use crate::level::trigger::{GroupRemap, TriggerFunction};
+use crate::utils::{lerp, lerp_start};
+
+#[derive(Clone, Copy, Debug, Default)]
+pub(crate) enum CameraEdge {
+    #[default]
+    Left,
+    Right,
+    Up,
+    Down,
+}
+
+impl CameraEdge {
+    pub(crate) fn from_id(id: u8) -> Self {
+        match id {
+            2 => CameraEdge::Right,
+            3 => CameraEdge::Up,
+            4 => CameraEdge::Down,
+            _ => CameraEdge::Left,
+        }
+    }
+}
+
+/// Camera changes made by triggers, applied to the actual camera by the main world
+#[derive(Clone, Resource)]
+pub(crate) struct CameraSettings {
+    pub(crate) zoom: f32,
+    pub(crate) offset: Vec2,
+    /// In radians
+    pub(crate) angle: f32,
+    /// Set by mirror portals
+    pub(crate) mirrored: bool,
+    static_group: u64,
+    /// How far the camera has eased into the static position
+    pub(crate) static_strength: f32,
+    pub(crate) static_position: Option<Vec2>,
+    /// Limits of the visible area in the order of [`CameraEdge`]
+    pub(crate) edges: [Option<f32>; 4],
+    ui_groups: Vec<u64>,
+    /// Where the camera was last frame, written back by the main world
+    pub(crate) position: Vec2,
+    pub(crate) half_extents: Vec2,
+    last_position: Option<Vec2>,
+}
+
+impl Default for CameraSettings {
+    fn default() -> Self {
+        Self {
+            zoom: 1.,
+            offset: Vec2::ZERO,
+            angle: 0.,
+            mirrored: false,
+            static_group: 0,
+            static_strength: 0.,
+            static_position: None,
+            edges: [None; 4],
+            ui_groups: Vec::new(),
+            position: Vec2::ZERO,
+            half_extents: Vec2::ZERO,
+            last_position: None,
+        }
+    }
+}
+
+impl CameraSettings {
+    /// Applies the static camera and the edges to the camera position
+    pub(crate) fn constrain(&self, mut position: Vec2, half_extents: Vec2) -> Vec2 {
+        if let Some(static_position) = self.static_position {
+            position = lerp(position, static_position, self.static_strength);
+        }
+
+        position += self.offset;
+
+        let [left, right, up, down] = self.edges;
+
+        if let Some(left) = left {
+            position.x = position.x.max(left + half_extents.x);
+        }
+        if let Some(right) = right {
+            position.x = position.x.min(right - half_extents.x);
+        }
+        if let Some(down) = down {
+            position.y = position.y.max(down + half_extents.y);
+        }
+        if let Some(up) = up {
+            position.y = position.y.min(up - half_extents.y);
+        }
+
+        position
+    }
+}
+
+fn group_position(
+    group_id: u64,
+    global_groups: &GlobalGroups,
+    groups: &Query<&GlobalGroup>,
+    objects: &Query<&Transform2d, Without<Player>>,
+) -> Option<Vec2> {
+    global_groups
+        .0
+        .get(group_id as usize)
+        .and_then(|group_entity| groups.get(*group_entity).ok())
+        .and_then(|group| group.root_entities.first())
+        .and_then(|entity| objects.get(*entity).ok())
+        .map(|transform| transform.translation.xy())
+}
+
+pub(crate) fn update_camera_settings(
+    mut camera_settings: ResMut<CameraSettings>,
+    global_groups: Res<GlobalGroups>,
+    groups: Query<&GlobalGroup>,
+    objects: Query<&Transform2d, Without<Player>>,
+) {
+    if camera_settings.static_strength == 0. {
+        camera_settings.static_position = None;
+        return;
+    }
+
+    camera_settings.static_position = group_position(
+        camera_settings.static_group,
+        &global_groups,
+        &groups,
+        &objects,
+    );
+}
+
+/// Moves the groups attached to the screen along with the camera
+pub(crate) fn update_ui_groups(
+    mut camera_settings: ResMut<CameraSettings>,
+    global_groups: Res<GlobalGroups>,
+    mut group_deltas: Query<&mut GlobalGroupDeltas>,
+) {
+    let position = camera_settings.position;
+
+    let Some(last_position) = camera_settings.last_position.replace(position) else {
+        return;
+    };
+
+    let delta = position - last_position;
+
+    if delta == Vec2::ZERO {
+        return;
+    }
+
+    for group_id in &camera_settings.ui_groups {
+        let Some(group_entity) = global_groups.0.get(*group_id as usize) else {
+            continue;
+        };
+
+        let Ok(mut global_group_delta) = group_deltas.get_mut(*group_entity) else {
+            continue;
+        };
+
+        global_group_delta.translation_delta += delta;
+    }
+}
+
+#[derive(Clone, Debug, Default)]
+pub(crate) struct CameraZoomTrigger {
+    pub(crate) zoom: f32,
+    pub(crate) duration: f32,
+    pub(crate) easing: Easing,
+}
+
+#[derive(Clone, Debug, Default)]
+pub(crate) struct CameraOffsetTrigger {
+    pub(crate) offset: Vec2,
+    pub(crate) duration: f32,
+    pub(crate) easing: Easing,
+}
+
+#[derive(Clone, Debug, Default)]
+pub(crate) struct CameraRotateTrigger {
+    /// In degrees
+    pub(crate) degrees: f32,
+    pub(crate) duration: f32,
+    pub(crate) easing: Easing,
+}
+
+#[derive(Clone, Debug, Default)]
+pub(crate) struct StaticCameraTrigger {
+    pub(crate) target_group: u64,
+    pub(crate) exit: bool,
+    pub(crate) duration: f32,
+    pub(crate) easing: Easing,
+}
+
+#[derive(Clone, Debug, Default)]
+pub(crate) struct CameraEdgeTrigger {
+    /// The edge gets removed when there is no target
+    pub(crate) target_group: u64,
+    pub(crate) edge: CameraEdge,
+}
+
+#[derive(Clone, Debug, Default)]
+pub(crate) struct UiTrigger {
+    pub(crate) target_group: u64,
+}
+
+type CameraTriggerSystemParam = (
+    ResMut<'static, CameraSettings>,
+    Res<'static, GlobalGroups>,
+    Query<'static, 'static, &'static GlobalGroup>,
+    Query<'static, 'static, &'static Transform2d, Without<Player>>,
+);
+
+fn ease_setting<T>(
+    world: &mut World,
+    system_state: &mut Box<dyn Any + Send + Sync>,
+    easing: Easing,
+    previous_progress: f32,
+    progress: f32,
+    setting: impl FnOnce(&mut CameraSettings) -> (&mut T, T),
+) where
+    T: Copy
+        + std::ops::Mul<f32, Output = T>
+        + std::ops::Div<f32, Output = T>
+        + std::ops::Add<T, Output = T>
+        + std::ops::Sub<T, Output = T>,
+{
+    let system_state: &mut SystemState<CameraTriggerSystemParam> =
+        system_state.downcast_mut().unwrap();
+
+    let (mut camera_settings, _, _, _) = system_state.get_mut(world);
+
+    let (value, target) = setting(&mut *camera_settings);
+
+    let previous_progress = easing.sample(previous_progress);
+    let eased_progress = easing.sample(progress);
+
+    let original = lerp_start(*value, target, previous_progress);
+    *value = lerp(original, target, eased_progress);
+}
+
+impl TriggerFunction for CameraZoomTrigger {
+    fn execute(
+        &self,
+        world: &mut World,
+        _: Entity,
+        system_state: &mut Box<dyn Any + Send + Sync>,
+        previous_progress: f32,
+        progress: f32,
+        _: Range<f32>,
+    ) {
+        ease_setting(
+            world,
+            system_state,
+            self.easing,
+            previous_progress,
+            progress,
+            |settings| (&mut settings.zoom, self.zoom),
+        );
+    }
+
+    fn create_system_state(&self, world: &mut World) -> Box<dyn Any + Send + Sync> {
+        Box::new(SystemState::<CameraTriggerSystemParam>::new(world))
+    }
+
+    fn target_id(&self) -> u64 {
+        0
+    }
+
+    fn duration(&self) -> f32 {
+        self.duration
+    }
+
+    fn exclusive(&self) -> bool {
+        true
+    }
+
+    fn post(&self) -> bool {
+        false
+    }
+}
+
+impl TriggerFunction for CameraOffsetTrigger {
+    fn execute(
+        &self,
+        world: &mut World,
+        _: Entity,
+        system_state: &mut Box<dyn Any + Send + Sync>,
+        previous_progress: f32,
+        progress: f32,
+        _: Range<f32>,
+    ) {
+        ease_setting(
+            world,
+            system_state,
+            self.easing,
+            previous_progress,
+            progress,
+            |settings| (&mut settings.offset, self.offset),
+        );
+    }
+
+    fn create_system_state(&self, world: &mut World) -> Box<dyn Any + Send + Sync> {
+        Box::new(SystemState::<CameraTriggerSystemParam>::new(world))
+    }
+
+    fn target_id(&self) -> u64 {
+        0
+    }
+
+    fn duration(&self) -> f32 {
+        self.duration
+    }
+
+    fn exclusive(&self) -> bool {
+        true
+    }
+
+    fn post(&self) -> bool {
+        false
+    }
+}
+
+impl TriggerFunction for CameraRotateTrigger {
+    fn execute(
+        &self,
+        world: &mut World,
+        _: Entity,
+        system_state: &mut Box<dyn Any + Send + Sync>,
+        previous_progress: f32,
+        progress: f32,
+        _: Range<f32>,
+    ) {
+        ease_setting(
+            world,
+            system_state,
+            self.easing,
+            previous_progress,
+            progress,
+            // Positive degrees rotate clockwise
+            |settings| (&mut settings.angle, -self.degrees.to_radians()),
+        );
+    }
+
+    fn create_system_state(&self, world: &mut World) -> Box<dyn Any + Send + Sync> {
+        Box::new(SystemState::<CameraTriggerSystemParam>::new(world))
+    }
+
+    fn target_id(&self) -> u64 {
+        0
+    }
+
+    fn duration(&self) -> f32 {
+        self.duration
+    }
+
+    fn exclusive(&self) -> bool {
+        true
+    }
+
+    fn post(&self) -> bool {
+        false
+    }
+}
+
+impl TriggerFunction for StaticCameraTrigger {
+    fn execute(
+        &self,
+        world: &mut World,
+        _: Entity,
+        system_state: &mut Box<dyn Any + Send + Sync>,
+        previous_progress: f32,
+        progress: f32,
+        _: Range<f32>,
+    ) {
+        if !self.exit {
+            let system_state: &mut SystemState<CameraTriggerSystemParam> =
+                system_state.downcast_mut().unwrap();
+
+            let (mut camera_settings, _, _, _) = system_state.get_mut(world);
+
+            camera_settings.static_group = self.target_group;
+        }
+
+        let target = if self.exit { 0. } else { 1. };
+
+        ease_setting(
+            world,
+            system_state,
+            self.easing,
+            previous_progress,
+            progress,
+            |settings| (&mut settings.static_strength, target),
+        );
+    }
+
+    fn create_system_state(&self, world: &mut World) -> Box<dyn Any + Send + Sync> {
+        Box::new(SystemState::<CameraTriggerSystemParam>::new(world))
+    }
+
+    fn target_id(&self) -> u64 {
+        0
+    }
+
+    fn duration(&self) -> f32 {
+        self.duration
+    }
+
+    fn exclusive(&self) -> bool {
+        true
+    }
+
+    fn post(&self) -> bool {
+        false
+    }
+
+    fn remap(&mut self, remap: &GroupRemap) {
+        self.target_group = remap.get(self.target_group);
+    }
+}
+
+impl TriggerFunction for CameraEdgeTrigger {
+    fn execute(
+        &self,
+        world: &mut World,
+        _: Entity,
+        system_state: &mut Box<dyn Any + Send + Sync>,
+        _: f32,
+        _: f32,
+        _: Range<f32>,
+    ) {
+        let system_state: &mut SystemState<CameraTriggerSystemParam> =
+            system_state.downcast_mut().unwrap();
+
+        let (mut camera_settings, global_groups, groups, objects) = system_state.get_mut(world);
+
+        let position = group_position(self.target_group, &global_groups, &groups, &objects);
+
+        camera_settings.edges[self.edge as usize] = position.map(|position| match self.edge {
+            CameraEdge::Left | CameraEdge::Right => position.x,
+            CameraEdge::Up | CameraEdge::Down => position.y,
+        });
+    }
+
+    fn create_system_state(&self, world: &mut World) -> Box<dyn Any + Send + Sync> {
+        Box::new(SystemState::<CameraTriggerSystemParam>::new(world))
+    }
+
+    fn target_id(&self) -> u64 {
+        self.edge as u64
+    }
+
+    fn duration(&self) -> f32 {
+        0.
+    }
+
+    fn exclusive(&self) -> bool {
+        false
+    }
+
+    fn post(&self) -> bool {
+        false
+    }
+
+    fn remap(&mut self, remap: &GroupRemap) {
+        self.target_group = remap.get(self.target_group);
+    }
+}
+
+impl TriggerFunction for UiTrigger {
+    fn execute(
+        &self,
+        world: &mut World,
+        _: Entity,
+        system_state: &mut Box<dyn Any + Send + Sync>,
+        _: f32,
+        _: f32,
+        _: Range<f32>,
+    ) {
+        let system_state: &mut SystemState<CameraTriggerSystemParam> =
+            system_state.downcast_mut().unwrap();
+
+        let (mut camera_settings, _, _, _) = system_state.get_mut(world);
+
+        if !camera_settings.ui_groups.contains(&self.target_group) {
+            camera_settings.ui_groups.push(self.target_group);
+        }
+    }
+
+    fn create_system_state(&self, world: &mut World) -> Box<dyn Any + Send + Sync> {
+        Box::new(SystemState::<CameraTriggerSystemParam>::new(world))
+    }
+
+    fn target_id(&self) -> u64 {
+        self.target_group
+    }
+
+    fn duration(&self) -> f32 {
+        0.
+    }
+
+    fn exclusive(&self) -> bool {
+        false
+    }
+
+    fn post(&self) -> bool {
+        false
+    }
+
+    fn remap(&mut self, remap: &GroupRemap) {
+        self.target_group = remap.get(self.target_group);
+    }
+}
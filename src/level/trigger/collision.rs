@@ -10,7 +10,8 @@ use crate::level::collision::ActiveCollider;
 use crate::level::color::ObjectColorCalculated;
 use crate::level::group::{GlobalGroup, GlobalGroups, ObjectGroups};
 use crate::level::trigger::{
-    Activated, GlobalTriggers, MultiActivate, SpawnActivate, Trigger, TriggerData, TriggerFunction,
+    Activated, GlobalTriggers, GroupRemap, MultiActivate, SpawnActivate, Trigger, TriggerData,
+    TriggerFunction,
 };
 
 #[derive(Clone, Debug, Default)]
@@ -199,4 +200,8 @@ impl TriggerFunction for CollisionTrigger {
     fn post(&self) -> bool {
         true
     }
+
+    fn remap(&mut self, remap: &GroupRemap) {
+        self.target_group = remap.get(self.target_group);
+    }
 }
@@ -2,15 +2,17 @@ use std::any::Any;
 use std::ops::Range;
 
 use bevy::ecs::system::SystemState;
-use bevy::prelude::{Entity, Query, Res, ResMut, With, Without, World};
-use bevy::time::Time;
-use float_next_after::NextAfter;
+use bevy::prelude::{Commands, Entity, Query, Res, ResMut, Resource, With, Without, World};
 
 use crate::level::color::ObjectColorCalculated;
 use crate::level::group::{GlobalGroup, GlobalGroups, ObjectGroups};
+use crate::level::player::Player;
+use crate::level::portal::DualPlayer;
+use crate::level::trigger::instant_count::InstantCountMode;
 use crate::level::trigger::pickup::PickupValues;
 use crate::level::trigger::{
-    Activated, GlobalTriggers, MultiActivate, SpawnActivate, Trigger, TriggerData, TriggerFunction,
+    queue_group_spawn, Activated, GlobalTriggers, GroupRemap, MultiActivate, SpawnActivate,
+    Trigger, TriggerData, TriggerFunction,
 };
 
 #[derive(Clone, Debug, Default)]
@@ -19,125 +21,158 @@ pub(crate) struct CountTrigger {
     pub(crate) target_count: i64,
     pub(crate) target_group: u64,
     pub(crate) activate: bool,
+    pub(crate) multi_activate: bool,
 }
 
-type CountTriggerSystemParam = (
-    Res<'static, Time>,
-    Res<'static, GlobalGroups>,
-    Res<'static, PickupValues>,
-    Res<'static, GlobalTriggers>,
-    ResMut<'static, TriggerData>,
-    Query<'static, 'static, &'static mut GlobalGroup>,
-    Query<
-        'static,
-        'static,
+#[derive(Clone, Debug)]
+pub(crate) struct CountListener {
+    pub(crate) item_id: u64,
+    pub(crate) target_count: i64,
+    pub(crate) mode: InstantCountMode,
+    pub(crate) target_group: u64,
+    pub(crate) activate: bool,
+    pub(crate) multi_activate: bool,
+    /// Only checks the count once when activated instead of waiting for it to change
+    pub(crate) instant: bool,
+}
+
+impl CountListener {
+    fn matches(&self, count: i64) -> bool {
+        match self.mode {
+            InstantCountMode::Equal => count == self.target_count,
+            InstantCountMode::Larger => count > self.target_count,
+            InstantCountMode::Smaller => count < self.target_count,
+        }
+    }
+}
+
+/// Count triggers waiting for the items they watch to change
+#[derive(Clone, Default, Resource)]
+pub(crate) struct CountListeners {
+    /// Activated this frame, checked against the current count first
+    pending: Vec<CountListener>,
+    listeners: Vec<CountListener>,
+}
+
+impl CountListeners {
+    pub(crate) fn listen(&mut self, listener: CountListener) {
+        self.pending.push(listener);
+    }
+}
+
+pub(crate) fn update_count_listeners(
+    mut commands: Commands,
+    mut count_listeners: ResMut<CountListeners>,
+    mut pickup_values: ResMut<PickupValues>,
+    global_groups: Res<GlobalGroups>,
+    global_triggers: Res<GlobalTriggers>,
+    mut trigger_data: ResMut<TriggerData>,
+    players: Query<&Player, Without<DualPlayer>>,
+    mut group_query: Query<&mut GlobalGroup>,
+    trigger_query: Query<
         (
             Entity,
-            &'static Trigger,
-            &'static ObjectGroups,
-            &'static ObjectColorCalculated,
-            Option<&'static MultiActivate>,
+            &Trigger,
+            &ObjectGroups,
+            &ObjectColorCalculated,
+            Option<&MultiActivate>,
         ),
         (With<SpawnActivate>, Without<Activated>),
     >,
-);
+) {
+    let Some(player) = players.iter().next() else {
+        return;
+    };
 
-impl TriggerFunction for CountTrigger {
-    fn execute(
-        &self,
-        world: &mut World,
-        entity: Entity,
-        system_state: &mut Box<dyn Any + Send + Sync>,
-        _: f32,
-        progress: f32,
-        range: Range<f32>,
-    ) {
-        if progress != 1. {
-            return;
-        }
+    let level_time = global_triggers.speed_changes.time_for_pos(player.distance);
 
-        let system_state: &mut SystemState<CountTriggerSystemParam> =
-            system_state.downcast_mut().unwrap();
+    let mut to_activate = Vec::new();
 
-        let (
-            time,
-            global_groups,
-            pickup_values,
-            global_triggers,
-            mut trigger_data,
-            mut group_query,
-            trigger_query,
-        ) = system_state.get_mut(world);
-
-        let Some(entry) = pickup_values.0.get(self.item_id as usize) else {
-            return;
-        };
+    let CountListeners { pending, listeners } = &mut *count_listeners;
 
-        if *entry != self.target_count {
-            let next_pos = global_triggers
-                .speed_changes
-                .pos_for_time(time.elapsed_seconds());
-            trigger_data.to_spawn.push((
-                entity,
-                Trigger(Box::new(self.clone())),
-                Vec::new(),
-                next_pos..next_pos,
-            ));
-            return;
+    for item_change in pickup_values.take_item_changes() {
+        listeners.retain(|listener| {
+            if listener.item_id != item_change.item_id
+                || listener.matches(item_change.previous)
+                || !listener.matches(item_change.current)
+            {
+                return true;
+            }
+            to_activate.push((listener.target_group, listener.activate));
+            listener.multi_activate
+        });
+    }
+
+    for listener in pending.drain(..) {
+        if listener.matches(pickup_values.item(listener.item_id)) {
+            to_activate.push((listener.target_group, listener.activate));
+            if !listener.multi_activate {
+                continue;
+            }
         }
 
-        let Some(group_entity) = global_groups.0.get(self.target_group as usize) else {
-            return;
+        if !listener.instant {
+            listeners.push(listener);
+        }
+    }
+
+    for (target_group, activate) in to_activate {
+        let Some(group_entity) = global_groups.0.get(target_group as usize) else {
+            continue;
         };
 
         let Ok(mut group) = group_query.get_mut(*group_entity) else {
-            return;
+            continue;
         };
 
-        group.enabled = self.activate;
+        group.enabled = activate;
 
         if !group.enabled {
-            return;
+            continue;
         }
 
-        let trigger_time = global_triggers.speed_changes.time_for_pos(range.start);
-        let start_time = trigger_time + self.duration();
-        let start_pos = global_triggers.speed_changes.pos_for_time(start_time);
-
-        let mut activated = Vec::new();
-
-        for (entity, trigger, object_groups, calculated, multi_activate) in
-            trigger_query.iter_many(&group.root_entities)
-        {
-            if !calculated.enabled {
-                continue;
-            }
-
-            let mut end_pos = global_triggers
-                .speed_changes
-                .pos_for_time(start_time + trigger.0.duration());
-
-            if end_pos == start_pos {
-                end_pos = end_pos.next_after(f32::INFINITY);
-            }
+        for entity in queue_group_spawn(
+            &group,
+            level_time,
+            &global_triggers,
+            &mut trigger_data,
+            &trigger_query,
+        ) {
+            commands.entity(entity).insert(Activated);
+        }
+    }
+}
 
-            trigger_data.to_spawn.push((
-                entity,
-                trigger.clone(),
-                object_groups.groups.clone(),
-                start_pos..end_pos,
-            ));
+type CountTriggerSystemParam = ResMut<'static, CountListeners>;
 
-            if multi_activate.is_some() {
-                continue;
-            }
-
-            activated.push(entity);
+impl TriggerFunction for CountTrigger {
+    fn execute(
+        &self,
+        world: &mut World,
+        _: Entity,
+        system_state: &mut Box<dyn Any + Send + Sync>,
+        _: f32,
+        progress: f32,
+        _: Range<f32>,
+    ) {
+        if progress != 1. {
+            return;
         }
 
-        for entity in activated {
-            world.entity_mut(entity).insert(Activated);
-        }
+        let system_state: &mut SystemState<CountTriggerSystemParam> =
+            system_state.downcast_mut().unwrap();
+
+        let mut count_listeners = system_state.get_mut(world);
+
+        count_listeners.listen(CountListener {
+            item_id: self.item_id,
+            target_count: self.target_count,
+            mode: InstantCountMode::Equal,
+            target_group: self.target_group,
+            activate: self.activate,
+            multi_activate: self.multi_activate,
+            instant: false,
+        });
     }
 
     fn create_system_state(&self, world: &mut World) -> Box<dyn Any + Send + Sync> {
@@ -159,4 +194,8 @@ impl TriggerFunction for CountTrigger {
     fn post(&self) -> bool {
         true
     }
+
+    fn remap(&mut self, remap: &GroupRemap) {
+        self.target_group = remap.get(self.target_group);
+    }
 }
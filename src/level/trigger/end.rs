@@ -0,0 +1,132 @@
+use std::any::Any;
+use std::ops::Range;
+
+use bevy::ecs::system::SystemState;
+use bevy::math::{Vec2, Vec3Swizzles};
+use bevy::prelude::{Entity, Query, Res, ResMut, Resource, Without, World};
+use bevy::time::Time;
+
+use crate::level::group::{GlobalGroup, GlobalGroups};
+use crate::level::player::Player;
+use crate::level::transform::Transform2d;
+use crate::level::trigger::{GroupRemap, TriggerFunction};
+
+#[derive(Clone, Debug, Default)]
+pub(crate) struct EndTrigger {
+    /// Group the player gets pulled into, the trigger itself if there is none
+    pub(crate) target_group: u64,
+    pub(crate) instant: bool,
+}
+
+#[derive(Default, Resource)]
+pub(crate) struct LevelEnd {
+    pub(crate) reached: bool,
+    target: Option<Vec2>,
+    instant: bool,
+}
+
+/// Pulls the player into the end position once the level is finished
+pub(crate) fn update_level_end(
+    level_end: Res<LevelEnd>,
+    time: Res<Time>,
+    mut players: Query<(&mut Player, &mut Transform2d)>,
+) {
+    if !level_end.reached {
+        return;
+    }
+
+    let Some(target) = level_end.target else {
+        return;
+    };
+
+    for (mut player, mut transform) in &mut players {
+        player.last_translation = transform.translation.xy();
+
+        let translation = if level_end.instant {
+            target
+        } else {
+            let amount = (time.delta_seconds() * 5.).min(1.);
+            player.last_translation.lerp(target, amount)
+        };
+
+        transform.translation.x = translation.x;
+        transform.translation.y = translation.y;
+    }
+}
+
+type EndTriggerSystemParam = (
+    ResMut<'static, LevelEnd>,
+    Res<'static, GlobalGroups>,
+    Query<'static, 'static, &'static GlobalGroup>,
+    Query<'static, 'static, &'static Transform2d, Without<Player>>,
+    Query<'static, 'static, &'static mut Player>,
+);
+
+impl TriggerFunction for EndTrigger {
+    fn execute(
+        &self,
+        world: &mut World,
+        entity: Entity,
+        system_state: &mut Box<dyn Any + Send + Sync>,
+        _: f32,
+        progress: f32,
+        _: Range<f32>,
+    ) {
+        if progress != 1. {
+            return;
+        }
+
+        let system_state: &mut SystemState<EndTriggerSystemParam> =
+            system_state.downcast_mut().unwrap();
+
+        let (mut level_end, global_groups, groups, objects, mut players) =
+            system_state.get_mut(world);
+
+        if level_end.reached {
+            return;
+        }
+
+        let target = if self.target_group != 0 {
+            global_groups
+                .0
+                .get(self.target_group as usize)
+                .and_then(|group_entity| groups.get(*group_entity).ok())
+                .and_then(|group| group.root_entities.first())
+                .and_then(|entity| objects.get(*entity).ok())
+        } else {
+            objects.get(entity).ok()
+        };
+
+        level_end.reached = true;
+        level_end.target = target.map(|transform| transform.translation.xy());
+        level_end.instant = self.instant;
+
+        for mut player in &mut players {
+            player.velocity.y = 0.;
+        }
+    }
+
+    fn create_system_state(&self, world: &mut World) -> Box<dyn Any + Send + Sync> {
+        Box::new(SystemState::<EndTriggerSystemParam>::new(world))
+    }
+
+    fn target_id(&self) -> u64 {
+        self.target_group
+    }
+
+    fn duration(&self) -> f32 {
+        0.
+    }
+
+    fn exclusive(&self) -> bool {
+        false
+    }
+
+    fn post(&self) -> bool {
+        false
+    }
+
+    fn remap(&mut self, remap: &GroupRemap) {
+        self.target_group = remap.get(self.target_group);
+    }
+}
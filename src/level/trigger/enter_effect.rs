@@ -0,0 +1,107 @@
+use std::any::Any;
+use std::ops::Range;
+
+use bevy::ecs::system::SystemState;
+use bevy::math::Vec2;
+use bevy::prelude::{Entity, ResMut, Resource, World};
+
+use crate::level::trigger::TriggerFunction;
+
+/// How far from the edge of the screen objects are affected
+const ENTER_EFFECT_DISTANCE: f32 = 90.;
+
+/// The effect objects get when they come into view, set by the legacy enter effect triggers
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub(crate) enum EnterEffect {
+    #[default]
+    None,
+    /// Fades on the left and right edges of the screen
+    Fade(bool, bool),
+    Move(Vec2),
+    Scale(f32),
+}
+
+impl EnterEffect {
+    pub(crate) fn from_object_id(object_id: u64) -> Self {
+        match object_id {
+            23 => EnterEffect::Move(Vec2::new(0., -ENTER_EFFECT_DISTANCE)),
+            24 => EnterEffect::Move(Vec2::new(0., ENTER_EFFECT_DISTANCE)),
+            25 => EnterEffect::Move(Vec2::new(-ENTER_EFFECT_DISTANCE, 0.)),
+            26 => EnterEffect::Move(Vec2::new(ENTER_EFFECT_DISTANCE, 0.)),
+            27 => EnterEffect::Scale(0.),
+            28 => EnterEffect::Scale(2.),
+            56 => EnterEffect::Fade(true, false),
+            57 => EnterEffect::Fade(false, true),
+            55 | 58 | 59 => EnterEffect::Fade(true, true),
+            _ => EnterEffect::None,
+        }
+    }
+
+    /// Strength of the effect for an object at `x`, with the screen spanning `left..right`
+    pub(crate) fn strength(self, x: f32, left: f32, right: f32) -> f32 {
+        let (fade_left, fade_right) = match self {
+            EnterEffect::None => return 0.,
+            EnterEffect::Fade(fade_left, fade_right) => (fade_left, fade_right),
+            _ => (true, true),
+        };
+
+        let mut distance = f32::INFINITY;
+        if fade_left {
+            distance = distance.min(x - left);
+        }
+        if fade_right {
+            distance = distance.min(right - x);
+        }
+
+        1. - (distance / ENTER_EFFECT_DISTANCE).clamp(0., 1.)
+    }
+}
+
+#[derive(Clone, Default, Resource)]
+pub(crate) struct GlobalEnterEffect(pub(crate) EnterEffect);
+
+#[derive(Clone, Debug, Default)]
+pub(crate) struct EnterEffectTrigger {
+    pub(crate) effect: EnterEffect,
+}
+
+type EnterEffectTriggerSystemParam = ResMut<'static, GlobalEnterEffect>;
+
+impl TriggerFunction for EnterEffectTrigger {
+    fn execute(
+        &self,
+        world: &mut World,
+        _: Entity,
+        system_state: &mut Box<dyn Any + Send + Sync>,
+        _: f32,
+        _: f32,
+        _: Range<f32>,
+    ) {
+        let system_state: &mut SystemState<EnterEffectTriggerSystemParam> =
+            system_state.downcast_mut().unwrap();
+
+        let mut global_enter_effect = system_state.get_mut(world);
+
+        global_enter_effect.0 = self.effect;
+    }
+
+    fn create_system_state(&self, world: &mut World) -> Box<dyn Any + Send + Sync> {
+        Box::new(SystemState::<EnterEffectTriggerSystemParam>::new(world))
+    }
+
+    fn target_id(&self) -> u64 {
+        0
+    }
+
+    fn duration(&self) -> f32 {
+        0.
+    }
+
+    fn exclusive(&self) -> bool {
+        false
+    }
+
+    fn post(&self) -> bool {
+        false
+    }
+}
@@ -0,0 +1,371 @@
+use std::any::Any;
+use std::ops::Range;
+
+use bevy::ecs::event::ManualEventReader;
+use bevy::prelude::{Entity, Events, Local, Mut, Resource, World};
+
+use crate::level::event::{GameEvent, PlayerEvent, PlayerEventKind};
+use crate::level::group::{GlobalGroup, GlobalGroups};
+use crate::level::player::Player;
+use crate::level::trigger::spawn::SpawnTrigger;
+use crate::level::trigger::{GroupRemap, TriggerFunction};
+
+#[derive(Clone, Copy, Debug)]
+enum ListenerAction {
+    /// Spawns the group like a spawn trigger would
+    Spawn(u64),
+    /// Toggles the group on or off
+    Toggle(u64, bool),
+}
+
+#[derive(Clone, Debug)]
+struct PlayerEventListener {
+    entity: Entity,
+    death: bool,
+    /// Ids of the [`GameEvent`]s to react to
+    events: Vec<u64>,
+    player2_only: bool,
+    action: ListenerAction,
+}
+
+/// Activated triggers waiting for player events
+#[derive(Default, Resource)]
+pub(crate) struct PlayerEventListeners {
+    reader: ManualEventReader<PlayerEvent>,
+    listeners: Vec<PlayerEventListener>,
+}
+
+impl Clone for PlayerEventListeners {
+    /// The reader of the clone starts over, see [`PlayerEventListeners::skip_sent`]
+    fn clone(&self) -> PlayerEventListeners {
+        PlayerEventListeners {
+            reader: ManualEventReader::default(),
+            listeners: self.listeners.clone(),
+        }
+    }
+}
+
+impl PlayerEventListener {
+    fn matches(&self, event: &PlayerEvent) -> bool {
+        if self.player2_only && !event.player2 {
+            return false;
+        }
+
+        match event.kind {
+            PlayerEventKind::Death => self.death,
+            PlayerEventKind::Game(game_event) => self.events.contains(&game_event.id()),
+        }
+    }
+}
+
+impl PlayerEventListeners {
+    /// Ignores the events that were sent before the listeners were put back
+    pub(crate) fn skip_sent(&mut self, events: &Events<PlayerEvent>) {
+        self.reader = events.get_reader_current();
+    }
+
+    /// Replaces whatever the trigger was listening for before
+    fn listen(&mut self, entity: Entity, listeners: Vec<PlayerEventListener>) {
+        self.listeners.retain(|listener| listener.entity != entity);
+        self.listeners.extend(listeners);
+    }
+}
+
+pub(crate) fn dispatch_player_events(
+    world: &mut World,
+    mut spawn_system_state: Local<Option<Box<dyn Any + Send + Sync>>>,
+) {
+    let mut actions = Vec::new();
+
+    world.resource_scope(
+        |world, mut player_event_listeners: Mut<PlayerEventListeners>| {
+            let PlayerEventListeners { reader, listeners } = &mut *player_event_listeners;
+
+            for event in reader.read(world.resource::<Events<PlayerEvent>>()) {
+                for listener in &*listeners {
+                    if listener.matches(event) {
+                        actions.push((listener.entity, listener.action));
+                    }
+                }
+            }
+        },
+    );
+
+    if actions.is_empty() {
+        return;
+    }
+
+    let position = world
+        .query::<&Player>()
+        .iter(world)
+        .next()
+        .map(|player| player.distance)
+        .unwrap_or_default();
+
+    let spawn_system_state = spawn_system_state
+        .get_or_insert_with(|| SpawnTrigger::default().create_system_state(world));
+
+    for (entity, action) in actions {
+        match action {
+            ListenerAction::Spawn(target_group) => {
+                SpawnTrigger {
+                    target_group,
+                    ..Default::default()
+                }
+                .execute(
+                    world,
+                    entity,
+                    spawn_system_state,
+                    0.,
+                    1.,
+                    position..position,
+                );
+            }
+            ListenerAction::Toggle(target_group, enabled) => {
+                let Some(group_entity) = world
+                    .resource::<GlobalGroups>()
+                    .0
+                    .get(target_group as usize)
+                    .copied()
+                else {
+                    continue;
+                };
+
+                if let Some(mut global_group) = world.get_mut::<GlobalGroup>(group_entity) {
+                    global_group.enabled = enabled;
+                }
+            }
+        }
+    }
+}
+
+fn listen(world: &mut World, entity: Entity, listeners: Vec<PlayerEventListener>) {
+    world
+        .resource_mut::<PlayerEventListeners>()
+        .listen(entity, listeners);
+}
+
+#[derive(Clone, Debug, Default)]
+pub(crate) struct OnDeathTrigger {
+    pub(crate) target_group: u64,
+    pub(crate) activate: bool,
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub(crate) enum TouchToggleMode {
+    #[default]
+    Default,
+    On,
+    Off,
+}
+
+impl TouchToggleMode {
+    pub(crate) fn from_id(id: u8) -> Self {
+        match id {
+            1 => TouchToggleMode::On,
+            2 => TouchToggleMode::Off,
+            _ => TouchToggleMode::Default,
+        }
+    }
+}
+
+#[derive(Clone, Debug, Default)]
+pub(crate) struct TouchTrigger {
+    pub(crate) target_group: u64,
+    /// Undo the toggle once the button is released
+    pub(crate) hold_mode: bool,
+    pub(crate) toggle_mode: TouchToggleMode,
+    /// Only react to the second player in dual mode
+    pub(crate) dual_mode: bool,
+}
+
+#[derive(Clone, Debug, Default)]
+pub(crate) struct EventTrigger {
+    pub(crate) target_group: u64,
+    pub(crate) events: Vec<u64>,
+}
+
+impl TriggerFunction for OnDeathTrigger {
+    fn execute(
+        &self,
+        world: &mut World,
+        entity: Entity,
+        _: &mut Box<dyn Any + Send + Sync>,
+        _: f32,
+        progress: f32,
+        _: Range<f32>,
+    ) {
+        if progress != 1. {
+            return;
+        }
+
+        let listener = |action| PlayerEventListener {
+            entity,
+            death: true,
+            events: Vec::new(),
+            player2_only: false,
+            action,
+        };
+
+        let mut listeners = vec![listener(ListenerAction::Toggle(
+            self.target_group,
+            self.activate,
+        ))];
+
+        if self.activate {
+            listeners.push(listener(ListenerAction::Spawn(self.target_group)));
+        }
+
+        listen(world, entity, listeners);
+    }
+
+    fn create_system_state(&self, _: &mut World) -> Box<dyn Any + Send + Sync> {
+        Box::new(())
+    }
+
+    fn target_id(&self) -> u64 {
+        self.target_group
+    }
+
+    fn duration(&self) -> f32 {
+        0.
+    }
+
+    fn exclusive(&self) -> bool {
+        false
+    }
+
+    fn post(&self) -> bool {
+        false
+    }
+
+    fn remap(&mut self, remap: &GroupRemap) {
+        self.target_group = remap.get(self.target_group);
+    }
+}
+
+impl TriggerFunction for TouchTrigger {
+    fn execute(
+        &self,
+        world: &mut World,
+        entity: Entity,
+        _: &mut Box<dyn Any + Send + Sync>,
+        _: f32,
+        progress: f32,
+        _: Range<f32>,
+    ) {
+        if progress != 1. {
+            return;
+        }
+
+        let listener = |event: GameEvent, action| PlayerEventListener {
+            entity,
+            death: false,
+            events: vec![event.id()],
+            player2_only: self.dual_mode,
+            action,
+        };
+
+        let listeners = match self.toggle_mode {
+            TouchToggleMode::Default => vec![listener(
+                GameEvent::JumpPush,
+                ListenerAction::Spawn(self.target_group),
+            )],
+            TouchToggleMode::On | TouchToggleMode::Off => {
+                let enabled = self.toggle_mode == TouchToggleMode::On;
+
+                let mut listeners = vec![listener(
+                    GameEvent::JumpPush,
+                    ListenerAction::Toggle(self.target_group, enabled),
+                )];
+
+                if self.hold_mode {
+                    listeners.push(listener(
+                        GameEvent::JumpRelease,
+                        ListenerAction::Toggle(self.target_group, !enabled),
+                    ));
+                }
+
+                listeners
+            }
+        };
+
+        listen(world, entity, listeners);
+    }
+
+    fn create_system_state(&self, _: &mut World) -> Box<dyn Any + Send + Sync> {
+        Box::new(())
+    }
+
+    fn target_id(&self) -> u64 {
+        self.target_group
+    }
+
+    fn duration(&self) -> f32 {
+        0.
+    }
+
+    fn exclusive(&self) -> bool {
+        false
+    }
+
+    fn post(&self) -> bool {
+        false
+    }
+
+    fn remap(&mut self, remap: &GroupRemap) {
+        self.target_group = remap.get(self.target_group);
+    }
+}
+
+impl TriggerFunction for EventTrigger {
+    fn execute(
+        &self,
+        world: &mut World,
+        entity: Entity,
+        _: &mut Box<dyn Any + Send + Sync>,
+        _: f32,
+        progress: f32,
+        _: Range<f32>,
+    ) {
+        if progress != 1. {
+            return;
+        }
+
+        listen(
+            world,
+            entity,
+            vec![PlayerEventListener {
+                entity,
+                death: false,
+                events: self.events.clone(),
+                player2_only: false,
+                action: ListenerAction::Spawn(self.target_group),
+            }],
+        );
+    }
+
+    fn create_system_state(&self, _: &mut World) -> Box<dyn Any + Send + Sync> {
+        Box::new(())
+    }
+
+    fn target_id(&self) -> u64 {
+        self.target_group
+    }
+
+    fn duration(&self) -> f32 {
+        0.
+    }
+
+    fn exclusive(&self) -> bool {
+        false
+    }
+
+    fn post(&self) -> bool {
+        false
+    }
+
+    fn remap(&mut self, remap: &GroupRemap) {
+        self.target_group = remap.get(self.target_group);
+    }
+}
@@ -3,11 +3,13 @@ use std::ops::Range;
 
 use bevy::ecs::system::SystemState;
 use bevy::math::{Vec2, Vec3Swizzles};
-use bevy::prelude::{Entity, Query, Res, World};
+use bevy::prelude::{Entity, Query, Res, With, Without, World};
 
 use crate::level::group::{GlobalGroup, GlobalGroupDeltas, GlobalGroups, RotationKind};
+use crate::level::player::Player;
+use crate::level::portal::DualPlayer;
 use crate::level::transform::Transform2d;
-use crate::level::trigger::TriggerFunction;
+use crate::level::trigger::{GroupRemap, TriggerFunction};
 
 #[derive(Clone, Debug, Default)]
 pub(crate) struct FollowTrigger {
@@ -17,6 +19,17 @@ pub(crate) struct FollowTrigger {
     pub(crate) scale: Vec2,
 }
 
+#[derive(Clone, Debug, Default)]
+pub(crate) struct FollowPlayerYTrigger {
+    pub(crate) duration: f32,
+    pub(crate) target_group: u64,
+    /// Fraction of the distance covered each 60th of a second
+    pub(crate) speed: f32,
+    pub(crate) offset: f32,
+    /// Units per 60th of a second, unlimited when zero
+    pub(crate) max_speed: f32,
+}
+
 type FollowTriggerSystemParam = (
     Res<'static, GlobalGroups>,
     Query<'static, 'static, (&'static GlobalGroup, &'static mut GlobalGroupDeltas)>,
@@ -103,4 +116,98 @@ impl TriggerFunction for FollowTrigger {
     fn post(&self) -> bool {
         true
     }
+
+    fn remap(&mut self, remap: &GroupRemap) {
+        self.target_group = remap.get(self.target_group);
+        self.follow_group = remap.get(self.follow_group);
+    }
+}
+
+type FollowPlayerYTriggerSystemParam = (
+    Res<'static, GlobalGroups>,
+    Query<'static, 'static, (&'static GlobalGroup, &'static mut GlobalGroupDeltas)>,
+    Query<'static, 'static, &'static Transform2d>,
+    Query<'static, 'static, &'static Transform2d, (With<Player>, Without<DualPlayer>)>,
+);
+
+impl TriggerFunction for FollowPlayerYTrigger {
+    fn execute(
+        &self,
+        world: &mut World,
+        _: Entity,
+        system_state: &mut Box<dyn Any + Send + Sync>,
+        previous_progress: f32,
+        progress: f32,
+        _: Range<f32>,
+    ) {
+        let system_state: &mut SystemState<FollowPlayerYTriggerSystemParam> =
+            &mut *system_state.downcast_mut().unwrap();
+
+        let (global_groups, mut group_delta_query, transform_query, player_query) =
+            system_state.get_mut(world);
+
+        let Some(player_transform) = player_query.iter().next() else {
+            return;
+        };
+
+        let Some(target_group_entity) = global_groups.0.get(self.target_group as usize) else {
+            return;
+        };
+
+        let Ok((target_group, mut target_group_delta)) =
+            group_delta_query.get_mut(*target_group_entity)
+        else {
+            return;
+        };
+
+        let Some(current) = target_group
+            .root_entities
+            .first()
+            .and_then(|entity| transform_query.get(*entity).ok())
+            .map(|transform| transform.translation.y + target_group_delta.translation_delta.y)
+        else {
+            return;
+        };
+
+        let frames = (progress - previous_progress) * self.duration * 60.;
+
+        if frames <= 0. {
+            return;
+        }
+
+        let distance = player_transform.translation.y + self.offset - current;
+
+        let mut delta = distance * (1. - (1. - self.speed.clamp(0., 1.)).powf(frames));
+
+        if self.max_speed > 0. {
+            let limit = self.max_speed * frames;
+            delta = delta.clamp(-limit, limit);
+        }
+
+        target_group_delta.translation_delta.y += delta;
+    }
+
+    fn create_system_state(&self, world: &mut World) -> Box<dyn Any + Send + Sync> {
+        Box::new(SystemState::<FollowPlayerYTriggerSystemParam>::new(world))
+    }
+
+    fn target_id(&self) -> u64 {
+        self.target_group
+    }
+
+    fn duration(&self) -> f32 {
+        self.duration
+    }
+
+    fn exclusive(&self) -> bool {
+        false
+    }
+
+    fn post(&self) -> bool {
+        false
+    }
+
+    fn remap(&mut self, remap: &GroupRemap) {
+        self.target_group = remap.get(self.target_group);
+    }
 }
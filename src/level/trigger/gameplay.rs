@@ -0,0 +1,360 @@
+use std::any::Any;
+use std::ops::Range;
+
+use bevy::ecs::system::SystemState;
+use bevy::prelude::{Entity, Query, World};
+use bevy::time::{Time, Virtual};
+
+use crate::level::player::Player;
+use crate::level::trigger::TriggerFunction;
+
+#[derive(Clone, Debug)]
+pub(crate) struct GravityTrigger {
+    pub(crate) gravity: f32,
+}
+
+impl Default for GravityTrigger {
+    fn default() -> Self {
+        Self { gravity: 1. }
+    }
+}
+
+#[derive(Clone, Debug, Default)]
+pub(crate) struct ReverseTrigger;
+
+#[derive(Clone, Copy, Debug, Default)]
+pub(crate) enum GameplayDirection {
+    Up,
+    Down,
+    Left,
+    #[default]
+    Right,
+}
+
+impl GameplayDirection {
+    pub(crate) fn from_id(id: u8) -> Self {
+        match id {
+            1 => GameplayDirection::Up,
+            2 => GameplayDirection::Down,
+            3 => GameplayDirection::Left,
+            _ => GameplayDirection::Right,
+        }
+    }
+}
+
+#[derive(Clone, Debug, Default)]
+pub(crate) struct RotateGameplayTrigger {
+    pub(crate) direction: GameplayDirection,
+}
+
+#[derive(Clone, Debug, Default)]
+pub(crate) struct PlayerVisibilityTrigger {
+    pub(crate) visible: bool,
+}
+
+#[derive(Clone, Debug, Default)]
+pub(crate) struct GhostTrailTrigger {
+    pub(crate) enabled: bool,
+}
+
+#[derive(Clone, Debug, Default)]
+pub(crate) struct PlayerControlTrigger {
+    pub(crate) stop_jump: bool,
+    pub(crate) stop_move: bool,
+}
+
+#[derive(Clone, Debug)]
+pub(crate) struct TimeWarpTrigger {
+    pub(crate) time_mod: f32,
+}
+
+impl Default for TimeWarpTrigger {
+    fn default() -> Self {
+        Self { time_mod: 1. }
+    }
+}
+
+type GameplayTriggerSystemParam = Query<'static, 'static, &'static mut Player>;
+
+fn modify_players(
+    world: &mut World,
+    system_state: &mut Box<dyn Any + Send + Sync>,
+    modify: impl Fn(&mut Player),
+) {
+    let system_state: &mut SystemState<GameplayTriggerSystemParam> =
+        system_state.downcast_mut().unwrap();
+
+    let mut players = system_state.get_mut(world);
+
+    for mut player in &mut players {
+        modify(&mut player);
+    }
+}
+
+impl TriggerFunction for GravityTrigger {
+    fn execute(
+        &self,
+        world: &mut World,
+        _: Entity,
+        system_state: &mut Box<dyn Any + Send + Sync>,
+        _: f32,
+        _: f32,
+        _: Range<f32>,
+    ) {
+        modify_players(world, system_state, |player| {
+            player.gravity_multiplier = self.gravity
+        });
+    }
+
+    fn create_system_state(&self, world: &mut World) -> Box<dyn Any + Send + Sync> {
+        Box::new(SystemState::<GameplayTriggerSystemParam>::new(world))
+    }
+
+    fn target_id(&self) -> u64 {
+        0
+    }
+
+    fn duration(&self) -> f32 {
+        0.
+    }
+
+    fn exclusive(&self) -> bool {
+        false
+    }
+
+    fn post(&self) -> bool {
+        false
+    }
+}
+
+impl TriggerFunction for ReverseTrigger {
+    fn execute(
+        &self,
+        world: &mut World,
+        _: Entity,
+        system_state: &mut Box<dyn Any + Send + Sync>,
+        _: f32,
+        progress: f32,
+        _: Range<f32>,
+    ) {
+        // Toggling more than once would undo itself
+        if progress != 1. {
+            return;
+        }
+
+        modify_players(world, system_state, |player| {
+            player.reverse = !player.reverse
+        });
+    }
+
+    fn create_system_state(&self, world: &mut World) -> Box<dyn Any + Send + Sync> {
+        Box::new(SystemState::<GameplayTriggerSystemParam>::new(world))
+    }
+
+    fn target_id(&self) -> u64 {
+        0
+    }
+
+    fn duration(&self) -> f32 {
+        0.
+    }
+
+    fn exclusive(&self) -> bool {
+        false
+    }
+
+    fn post(&self) -> bool {
+        false
+    }
+}
+
+impl TriggerFunction for RotateGameplayTrigger {
+    fn execute(
+        &self,
+        world: &mut World,
+        _: Entity,
+        system_state: &mut Box<dyn Any + Send + Sync>,
+        _: f32,
+        _: f32,
+        _: Range<f32>,
+    ) {
+        let (vertical_is_x, reverse) = match self.direction {
+            GameplayDirection::Up => (true, false),
+            GameplayDirection::Down => (true, true),
+            GameplayDirection::Left => (false, true),
+            GameplayDirection::Right => (false, false),
+        };
+
+        modify_players(world, system_state, |player| {
+            player.vertical_is_x = vertical_is_x;
+            player.reverse = reverse;
+        });
+    }
+
+    fn create_system_state(&self, world: &mut World) -> Box<dyn Any + Send + Sync> {
+        Box::new(SystemState::<GameplayTriggerSystemParam>::new(world))
+    }
+
+    fn target_id(&self) -> u64 {
+        0
+    }
+
+    fn duration(&self) -> f32 {
+        0.
+    }
+
+    fn exclusive(&self) -> bool {
+        false
+    }
+
+    fn post(&self) -> bool {
+        false
+    }
+}
+
+impl TriggerFunction for PlayerVisibilityTrigger {
+    fn execute(
+        &self,
+        world: &mut World,
+        _: Entity,
+        system_state: &mut Box<dyn Any + Send + Sync>,
+        _: f32,
+        _: f32,
+        _: Range<f32>,
+    ) {
+        modify_players(world, system_state, |player| player.hidden = !self.visible);
+    }
+
+    fn create_system_state(&self, world: &mut World) -> Box<dyn Any + Send + Sync> {
+        Box::new(SystemState::<GameplayTriggerSystemParam>::new(world))
+    }
+
+    fn target_id(&self) -> u64 {
+        0
+    }
+
+    fn duration(&self) -> f32 {
+        0.
+    }
+
+    fn exclusive(&self) -> bool {
+        false
+    }
+
+    fn post(&self) -> bool {
+        false
+    }
+}
+
+impl TriggerFunction for GhostTrailTrigger {
+    fn execute(
+        &self,
+        world: &mut World,
+        _: Entity,
+        system_state: &mut Box<dyn Any + Send + Sync>,
+        _: f32,
+        _: f32,
+        _: Range<f32>,
+    ) {
+        modify_players(world, system_state, |player| {
+            player.ghost_trail = self.enabled
+        });
+    }
+
+    fn create_system_state(&self, world: &mut World) -> Box<dyn Any + Send + Sync> {
+        Box::new(SystemState::<GameplayTriggerSystemParam>::new(world))
+    }
+
+    fn target_id(&self) -> u64 {
+        0
+    }
+
+    fn duration(&self) -> f32 {
+        0.
+    }
+
+    fn exclusive(&self) -> bool {
+        false
+    }
+
+    fn post(&self) -> bool {
+        false
+    }
+}
+
+impl TriggerFunction for PlayerControlTrigger {
+    fn execute(
+        &self,
+        world: &mut World,
+        _: Entity,
+        system_state: &mut Box<dyn Any + Send + Sync>,
+        _: f32,
+        _: f32,
+        _: Range<f32>,
+    ) {
+        modify_players(world, system_state, |player| {
+            if self.stop_move {
+                player.velocity.y = 0.;
+            } else if self.stop_jump {
+                // Only cuts the jump short, falling is left alone
+                player.velocity.y = player.velocity.y.min(0.);
+            }
+        });
+    }
+
+    fn create_system_state(&self, world: &mut World) -> Box<dyn Any + Send + Sync> {
+        Box::new(SystemState::<GameplayTriggerSystemParam>::new(world))
+    }
+
+    fn target_id(&self) -> u64 {
+        0
+    }
+
+    fn duration(&self) -> f32 {
+        0.
+    }
+
+    fn exclusive(&self) -> bool {
+        false
+    }
+
+    fn post(&self) -> bool {
+        false
+    }
+}
+
+impl TriggerFunction for TimeWarpTrigger {
+    fn execute(
+        &self,
+        world: &mut World,
+        _: Entity,
+        _: &mut Box<dyn Any + Send + Sync>,
+        _: f32,
+        _: f32,
+        _: Range<f32>,
+    ) {
+        world
+            .resource_mut::<Time<Virtual>>()
+            .set_relative_speed(self.time_mod);
+    }
+
+    fn create_system_state(&self, _: &mut World) -> Box<dyn Any + Send + Sync> {
+        Box::new(())
+    }
+
+    fn target_id(&self) -> u64 {
+        0
+    }
+
+    fn duration(&self) -> f32 {
+        0.
+    }
+
+    fn exclusive(&self) -> bool {
+        false
+    }
+
+    fn post(&self) -> bool {
+        false
+    }
+}
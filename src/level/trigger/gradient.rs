@@ -0,0 +1,196 @@
+use std::any::Any;
+use std::ops::Range;
+
+use bevy::ecs::system::SystemState;
+use bevy::math::{Vec2, Vec3Swizzles, Vec4};
+use bevy::prelude::{Entity, Query, Res, ResMut, Resource, World};
+use indexmap::IndexMap;
+
+use crate::level::color::{ColorChannelCalculated, ObjectColor};
+use crate::level::group::{GlobalGroup, GlobalGroups};
+use crate::level::object::Object;
+use crate::level::transform::Transform2d;
+use crate::level::trigger::{GroupRemap, TriggerFunction};
+use crate::utils::U64Hash;
+
+#[derive(Clone, Debug, Default)]
+pub(crate) struct GradientTrigger {
+    pub(crate) gradient_id: u64,
+    /// Up, down, left and right groups, or the bottom left, bottom right, top left and top right
+    /// corners when in vertex mode
+    pub(crate) groups: [u64; 4],
+    pub(crate) vertex_mode: bool,
+    pub(crate) additive: bool,
+    pub(crate) disable: bool,
+}
+
+#[derive(Clone, Debug)]
+struct GradientLayer {
+    groups: [u64; 4],
+    vertex_mode: bool,
+    additive: bool,
+    z_layer: i32,
+    z_order: f32,
+}
+
+#[derive(Clone, Copy, Debug)]
+pub(crate) struct GradientQuad {
+    /// Bottom left, bottom right, top left and top right
+    pub(crate) corners: [Vec2; 4],
+    pub(crate) colors: [Vec4; 4],
+    pub(crate) additive: bool,
+    pub(crate) z_layer: i32,
+    pub(crate) z_order: f32,
+}
+
+#[derive(Clone, Default, Resource)]
+pub(crate) struct GlobalGradients {
+    layers: IndexMap<u64, GradientLayer, U64Hash>,
+    pub(crate) quads: Vec<GradientQuad>,
+}
+
+pub(crate) fn update_gradients(
+    mut global_gradients: ResMut<GlobalGradients>,
+    global_groups: Res<GlobalGroups>,
+    groups: Query<&GlobalGroup>,
+    objects: Query<(&Transform2d, Option<&ObjectColor>)>,
+    color_channels: Query<&ColorChannelCalculated>,
+) {
+    let GlobalGradients { layers, quads } = &mut *global_gradients;
+
+    quads.clear();
+
+    for layer in layers.values() {
+        let mut points = [(Vec2::ZERO, Vec4::ONE); 4];
+
+        let mut found_all = true;
+
+        for (point, group_id) in points.iter_mut().zip(layer.groups) {
+            let Some((transform, object_color)) = global_groups
+                .0
+                .get(group_id as usize)
+                .and_then(|group_entity| groups.get(*group_entity).ok())
+                .and_then(|group| group.root_entities.first())
+                .and_then(|entity| objects.get(*entity).ok())
+            else {
+                found_all = false;
+                break;
+            };
+
+            let color = object_color
+                .and_then(|object_color| color_channels.get(object_color.channel_entity).ok())
+                .map(|calculated| calculated.color)
+                .unwrap_or(Vec4::ONE);
+
+            *point = (transform.translation.xy(), color);
+        }
+
+        if !found_all {
+            continue;
+        }
+
+        let (corners, colors) = if layer.vertex_mode {
+            (
+                points.map(|(position, _)| position),
+                points.map(|(_, color)| color),
+            )
+        } else {
+            let [(up, up_color), (down, down_color), (left, left_color), (right, right_color)] =
+                points;
+            (
+                [
+                    Vec2::new(left.x, down.y),
+                    Vec2::new(right.x, down.y),
+                    Vec2::new(left.x, up.y),
+                    Vec2::new(right.x, up.y),
+                ],
+                [
+                    (down_color + left_color) / 2.,
+                    (down_color + right_color) / 2.,
+                    (up_color + left_color) / 2.,
+                    (up_color + right_color) / 2.,
+                ],
+            )
+        };
+
+        quads.push(GradientQuad {
+            corners,
+            colors,
+            additive: layer.additive,
+            z_layer: layer.z_layer,
+            z_order: layer.z_order,
+        });
+    }
+}
+
+type GradientTriggerSystemParam = (
+    ResMut<'static, GlobalGradients>,
+    Query<'static, 'static, (&'static Object, &'static Transform2d)>,
+);
+
+impl TriggerFunction for GradientTrigger {
+    fn execute(
+        &self,
+        world: &mut World,
+        entity: Entity,
+        system_state: &mut Box<dyn Any + Send + Sync>,
+        _: f32,
+        progress: f32,
+        _: Range<f32>,
+    ) {
+        if progress != 1. {
+            return;
+        }
+
+        let system_state: &mut SystemState<GradientTriggerSystemParam> =
+            system_state.downcast_mut().unwrap();
+
+        let (mut global_gradients, objects) = system_state.get_mut(world);
+
+        if self.disable {
+            global_gradients.layers.shift_remove(&self.gradient_id);
+            return;
+        }
+
+        let Ok((object, transform)) = objects.get(entity) else {
+            return;
+        };
+
+        global_gradients.layers.insert(
+            self.gradient_id,
+            GradientLayer {
+                groups: self.groups,
+                vertex_mode: self.vertex_mode,
+                additive: self.additive,
+                z_layer: object.z_layer,
+                z_order: transform.translation.z,
+            },
+        );
+    }
+
+    fn create_system_state(&self, world: &mut World) -> Box<dyn Any + Send + Sync> {
+        Box::new(SystemState::<GradientTriggerSystemParam>::new(world))
+    }
+
+    fn target_id(&self) -> u64 {
+        self.gradient_id
+    }
+
+    fn duration(&self) -> f32 {
+        0.
+    }
+
+    fn exclusive(&self) -> bool {
+        false
+    }
+
+    fn post(&self) -> bool {
+        false
+    }
+
+    fn remap(&mut self, remap: &GroupRemap) {
+        for group in &mut self.groups {
+            *group = remap.get(*group);
+        }
+    }
+}
@@ -0,0 +1,199 @@
+use std::any::Any;
+use std::ops::Range;
+
+use bevy::ecs::system::SystemState;
+use bevy::prelude::{Entity, Query, Res, ResMut, With, Without, World};
+
+use crate::level::color::ObjectColorCalculated;
+use crate::level::group::{GlobalGroup, GlobalGroups, ObjectGroups};
+use crate::level::trigger::pickup::{ItemKind, ItemOperator, PickupValues, RoundMode, SignMode};
+use crate::level::trigger::{
+    queue_group_spawn, Activated, GlobalTriggers, GroupRemap, MultiActivate, SpawnActivate,
+    Trigger, TriggerData, TriggerFunction,
+};
+
+#[derive(Clone, Debug, Default)]
+pub(crate) struct ItemCompareTrigger {
+    pub(crate) first: ItemOperand,
+    pub(crate) second: ItemOperand,
+    pub(crate) compare_operator: CompareOperator,
+    pub(crate) tolerance: f64,
+    pub(crate) true_group: u64,
+    pub(crate) false_group: u64,
+}
+
+#[derive(Clone, Debug)]
+pub(crate) struct ItemOperand {
+    pub(crate) item: Option<(ItemKind, u64)>,
+    pub(crate) modifier: f64,
+    pub(crate) operator: ItemOperator,
+    pub(crate) round_mode: RoundMode,
+    pub(crate) sign_mode: SignMode,
+}
+
+impl Default for ItemOperand {
+    fn default() -> Self {
+        Self {
+            item: None,
+            modifier: 1.,
+            operator: ItemOperator::Multiply,
+            round_mode: RoundMode::None,
+            sign_mode: SignMode::None,
+        }
+    }
+}
+
+impl ItemOperand {
+    fn evaluate(&self, pickup_values: &PickupValues) -> f64 {
+        let value = match self.item {
+            Some((kind, id)) => self
+                .operator
+                .apply(pickup_values.get(kind, id), self.modifier),
+            None => self.modifier,
+        };
+
+        self.sign_mode.apply(self.round_mode.apply(value))
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default)]
+pub(crate) enum CompareOperator {
+    #[default]
+    Equal,
+    Larger,
+    LargerOrEqual,
+    Smaller,
+    SmallerOrEqual,
+    NotEqual,
+}
+
+impl CompareOperator {
+    pub(crate) fn from_id(id: u8) -> CompareOperator {
+        match id {
+            1 => CompareOperator::Larger,
+            2 => CompareOperator::LargerOrEqual,
+            3 => CompareOperator::Smaller,
+            4 => CompareOperator::SmallerOrEqual,
+            5 => CompareOperator::NotEqual,
+            _ => CompareOperator::Equal,
+        }
+    }
+
+    fn compare(self, lhs: f64, rhs: f64, tolerance: f64) -> bool {
+        match self {
+            CompareOperator::Equal => (lhs - rhs).abs() <= tolerance,
+            CompareOperator::Larger => lhs > rhs,
+            CompareOperator::LargerOrEqual => lhs >= rhs,
+            CompareOperator::Smaller => lhs < rhs,
+            CompareOperator::SmallerOrEqual => lhs <= rhs,
+            CompareOperator::NotEqual => (lhs - rhs).abs() > tolerance,
+        }
+    }
+}
+
+type ItemCompareTriggerSystemParam = (
+    Res<'static, GlobalGroups>,
+    Res<'static, PickupValues>,
+    Res<'static, GlobalTriggers>,
+    ResMut<'static, TriggerData>,
+    Query<'static, 'static, &'static GlobalGroup>,
+    Query<
+        'static,
+        'static,
+        (
+            Entity,
+            &'static Trigger,
+            &'static ObjectGroups,
+            &'static ObjectColorCalculated,
+            Option<&'static MultiActivate>,
+        ),
+        (With<SpawnActivate>, Without<Activated>),
+    >,
+);
+
+impl TriggerFunction for ItemCompareTrigger {
+    fn execute(
+        &self,
+        world: &mut World,
+        _: Entity,
+        system_state: &mut Box<dyn Any + Send + Sync>,
+        _: f32,
+        progress: f32,
+        range: Range<f32>,
+    ) {
+        if progress != 1. {
+            return;
+        }
+
+        let system_state: &mut SystemState<ItemCompareTriggerSystemParam> =
+            system_state.downcast_mut().unwrap();
+
+        let (
+            global_groups,
+            pickup_values,
+            global_triggers,
+            mut trigger_data,
+            group_query,
+            trigger_query,
+        ) = system_state.get_mut(world);
+
+        let result = self.compare_operator.compare(
+            self.first.evaluate(&pickup_values),
+            self.second.evaluate(&pickup_values),
+            self.tolerance,
+        );
+
+        let target_group = if result {
+            self.true_group
+        } else {
+            self.false_group
+        };
+
+        let Some(group_entity) = global_groups.0.get(target_group as usize) else {
+            return;
+        };
+
+        let Ok(group) = group_query.get(*group_entity) else {
+            return;
+        };
+
+        let start_time = global_triggers.speed_changes.time_for_pos(range.start);
+
+        let activated = queue_group_spawn(
+            group,
+            start_time,
+            &global_triggers,
+            &mut trigger_data,
+            &trigger_query,
+        );
+
+        for entity in activated {
+            world.entity_mut(entity).insert(Activated);
+        }
+    }
+
+    fn create_system_state(&self, world: &mut World) -> Box<dyn Any + Send + Sync> {
+        Box::new(SystemState::<ItemCompareTriggerSystemParam>::new(world))
+    }
+
+    fn target_id(&self) -> u64 {
+        0
+    }
+
+    fn duration(&self) -> f32 {
+        0.
+    }
+
+    fn exclusive(&self) -> bool {
+        false
+    }
+
+    fn post(&self) -> bool {
+        false
+    }
+
+    fn remap(&mut self, remap: &GroupRemap) {
+        self.true_group = remap.get(self.true_group);
+        self.false_group = remap.get(self.false_group);
+    }
+}
@@ -0,0 +1,114 @@
+use std::any::Any;
+use std::ops::Range;
+
+use bevy::ecs::system::SystemState;
+use bevy::prelude::{Entity, ResMut, World};
+
+use crate::level::trigger::pickup::{ItemKind, ItemOperator, PickupValues, RoundMode, SignMode};
+use crate::level::trigger::TriggerFunction;
+
+#[derive(Clone, Debug)]
+pub(crate) struct ItemEditTrigger {
+    pub(crate) first: Option<(ItemKind, u64)>,
+    pub(crate) second: Option<(ItemKind, u64)>,
+    pub(crate) target: (ItemKind, u64),
+    pub(crate) modifier: f64,
+    pub(crate) operator: ItemOperator,
+    pub(crate) modifier_operator: ItemOperator,
+    pub(crate) assign_operator: ItemOperator,
+    pub(crate) round_mode: RoundMode,
+    pub(crate) sign_mode: SignMode,
+    pub(crate) target_round_mode: RoundMode,
+    pub(crate) target_sign_mode: SignMode,
+}
+
+impl Default for ItemEditTrigger {
+    fn default() -> Self {
+        Self {
+            first: None,
+            second: None,
+            target: (ItemKind::Item, 0),
+            modifier: 1.,
+            operator: ItemOperator::Add,
+            modifier_operator: ItemOperator::Multiply,
+            assign_operator: ItemOperator::Set,
+            round_mode: RoundMode::None,
+            sign_mode: SignMode::None,
+            target_round_mode: RoundMode::None,
+            target_sign_mode: SignMode::None,
+        }
+    }
+}
+
+type ItemEditTriggerSystemParam = ResMut<'static, PickupValues>;
+
+impl TriggerFunction for ItemEditTrigger {
+    fn execute(
+        &self,
+        world: &mut World,
+        _: Entity,
+        system_state: &mut Box<dyn Any + Send + Sync>,
+        _: f32,
+        progress: f32,
+        _: Range<f32>,
+    ) {
+        if progress != 1. {
+            return;
+        }
+
+        let system_state: &mut SystemState<ItemEditTriggerSystemParam> =
+            system_state.downcast_mut().unwrap();
+
+        let mut pickup_values = system_state.get_mut(world);
+
+        let first = self.first.map(|(kind, id)| pickup_values.get(kind, id));
+        let second = self.second.map(|(kind, id)| pickup_values.get(kind, id));
+
+        let value = match (first, second) {
+            (Some(first), Some(second)) => Some(self.operator.apply(first, second)),
+            (Some(value), None) | (None, Some(value)) => Some(value),
+            (None, None) => None,
+        };
+
+        // Without any items the modifier is used as a constant
+        let value = match value {
+            Some(value) => self.modifier_operator.apply(value, self.modifier),
+            None => self.modifier,
+        };
+
+        let value = self.sign_mode.apply(self.round_mode.apply(value));
+
+        let (target_kind, target_id) = self.target;
+
+        let target_value = self
+            .assign_operator
+            .apply(pickup_values.get(target_kind, target_id), value);
+
+        pickup_values.set(
+            target_kind,
+            target_id,
+            self.target_sign_mode
+                .apply(self.target_round_mode.apply(target_value)),
+        );
+    }
+
+    fn create_system_state(&self, world: &mut World) -> Box<dyn Any + Send + Sync> {
+        Box::new(SystemState::<ItemEditTriggerSystemParam>::new(world))
+    }
+
+    fn target_id(&self) -> u64 {
+        0
+    }
+
+    fn duration(&self) -> f32 {
+        0.
+    }
+
+    fn exclusive(&self) -> bool {
+        false
+    }
+
+    fn post(&self) -> bool {
+        false
+    }
+}
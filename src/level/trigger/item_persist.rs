@@ -0,0 +1,73 @@
+use std::any::Any;
+use std::ops::Range;
+
+use bevy::ecs::system::SystemState;
+use bevy::prelude::{Entity, ResMut, World};
+
+use crate::level::trigger::pickup::{ItemKind, PickupValues};
+use crate::level::trigger::TriggerFunction;
+
+#[derive(Clone, Debug, Default)]
+pub(crate) struct ItemPersistTrigger {
+    pub(crate) item_id: u64,
+    pub(crate) kind: ItemKind,
+    pub(crate) persistent: bool,
+    pub(crate) target_all: bool,
+    pub(crate) reset: bool,
+}
+
+type ItemPersistTriggerSystemParam = ResMut<'static, PickupValues>;
+
+impl TriggerFunction for ItemPersistTrigger {
+    fn execute(
+        &self,
+        world: &mut World,
+        _: Entity,
+        system_state: &mut Box<dyn Any + Send + Sync>,
+        _: f32,
+        progress: f32,
+        _: Range<f32>,
+    ) {
+        if progress != 1. {
+            return;
+        }
+
+        let system_state: &mut SystemState<ItemPersistTriggerSystemParam> =
+            system_state.downcast_mut().unwrap();
+
+        let mut pickup_values = system_state.get_mut(world);
+
+        let id = if self.target_all {
+            None
+        } else {
+            Some(self.item_id)
+        };
+
+        if self.reset {
+            pickup_values.reset(self.kind, id);
+            return;
+        }
+
+        pickup_values.set_persistent(self.kind, id, self.persistent);
+    }
+
+    fn create_system_state(&self, world: &mut World) -> Box<dyn Any + Send + Sync> {
+        Box::new(SystemState::<ItemPersistTriggerSystemParam>::new(world))
+    }
+
+    fn target_id(&self) -> u64 {
+        0
+    }
+
+    fn duration(&self) -> f32 {
+        0.
+    }
+
+    fn exclusive(&self) -> bool {
+        false
+    }
+
+    fn post(&self) -> bool {
+        false
+    }
+}
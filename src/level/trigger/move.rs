@@ -9,8 +9,9 @@ use crate::level::easing::Easing;
 use crate::level::group::{GlobalGroupDeltas, GlobalGroups};
 use crate::level::object::Object;
 use crate::level::player::Player;
+use crate::level::portal::DualPlayer;
 use crate::level::transform::Transform2d;
-use crate::level::trigger::TriggerFunction;
+use crate::level::trigger::{GroupRemap, TriggerFunction};
 
 #[derive(Clone, Debug, Default)]
 pub(crate) struct MoveTrigger {
@@ -24,7 +25,12 @@ pub(crate) struct MoveTrigger {
 type MoveTriggerSystemParam = (
     Res<'static, GlobalGroups>,
     Query<'static, 'static, &'static mut GlobalGroupDeltas>,
-    Query<'static, 'static, (&'static Player, &'static Transform2d), Without<Object>>,
+    Query<
+        'static,
+        'static,
+        (&'static Player, &'static Transform2d),
+        (Without<Object>, Without<DualPlayer>),
+    >,
 );
 
 impl TriggerFunction for MoveTrigger {
@@ -97,4 +103,8 @@ impl TriggerFunction for MoveTrigger {
     fn post(&self) -> bool {
         false
     }
+
+    fn remap(&mut self, remap: &GroupRemap) {
+        self.target_group = remap.get(self.target_group);
+    }
 }
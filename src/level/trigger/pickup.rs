@@ -2,9 +2,12 @@ use std::any::Any;
 use std::ops::Range;
 
 use bevy::ecs::system::SystemState;
-use bevy::prelude::{Entity, ResMut, Resource, World};
+use bevy::prelude::{Entity, Res, ResMut, Resource, World};
+use bevy::time::Time;
+use bevy::utils::{hashbrown, HashSet};
 
 use crate::level::trigger::TriggerFunction;
+use crate::utils::U64Hash;
 
 #[derive(Clone, Debug, Default)]
 pub(crate) struct PickupTrigger {
@@ -12,15 +15,268 @@ pub(crate) struct PickupTrigger {
     pub(crate) count: i64,
 }
 
-#[derive(Resource)]
-pub(crate) struct PickupValues(pub(crate) [i64; 1000]);
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, PartialEq)]
+pub(crate) enum ItemKind {
+    #[default]
+    Item,
+    Timer,
+    Points,
+    MainTime,
+    Attempts,
+}
+
+impl ItemKind {
+    pub(crate) fn from_id(id: u8) -> Option<ItemKind> {
+        match id {
+            1 => Some(ItemKind::Item),
+            2 => Some(ItemKind::Timer),
+            3 => Some(ItemKind::Points),
+            4 => Some(ItemKind::MainTime),
+            5 => Some(ItemKind::Attempts),
+            _ => None,
+        }
+    }
+}
+
+/// A change of an item's count, read by the count triggers listening to that item
+#[derive(Clone, Copy, Debug)]
+pub(crate) struct ItemChange {
+    pub(crate) item_id: u64,
+    pub(crate) previous: i64,
+    pub(crate) current: i64,
+}
+
+#[derive(Clone, Default, Resource)]
+pub(crate) struct PickupValues {
+    items: hashbrown::HashMap<u64, i64, U64Hash>,
+    timers: hashbrown::HashMap<u64, f64, U64Hash>,
+    points: i64,
+    main_time: f64,
+    pub(crate) attempts: i64,
+    /// `None` as the id marks every item of that kind as persistent
+    persistent: HashSet<(ItemKind, Option<u64>)>,
+    item_changes: Vec<ItemChange>,
+}
+
+impl PickupValues {
+    pub(crate) fn get(&self, kind: ItemKind, id: u64) -> f64 {
+        match kind {
+            ItemKind::Item => self.item(id) as f64,
+            ItemKind::Timer => self.timers.get(&id).copied().unwrap_or_default(),
+            ItemKind::Points => self.points as f64,
+            ItemKind::MainTime => self.main_time,
+            ItemKind::Attempts => self.attempts as f64,
+        }
+    }
+
+    pub(crate) fn set(&mut self, kind: ItemKind, id: u64, value: f64) {
+        match kind {
+            ItemKind::Item => {
+                let previous = self.items.insert(id, value as i64).unwrap_or_default();
+                self.record_item_change(id, previous);
+            }
+            ItemKind::Timer => {
+                self.timers.insert(id, value);
+            }
+            ItemKind::Points => self.points = value as i64,
+            // These are read-only
+            ItemKind::MainTime | ItemKind::Attempts => (),
+        }
+    }
+
+    pub(crate) fn item(&self, id: u64) -> i64 {
+        self.items.get(&id).copied().unwrap_or_default()
+    }
+
+    pub(crate) fn add_item(&mut self, id: u64, count: i64) {
+        let item = self.items.entry(id).or_default();
+        let previous = *item;
+        *item += count;
+        self.record_item_change(id, previous);
+    }
+
+    fn record_item_change(&mut self, id: u64, previous: i64) {
+        let current = self.item(id);
+        if previous != current {
+            self.item_changes.push(ItemChange {
+                item_id: id,
+                previous,
+                current,
+            });
+        }
+    }
+
+    /// Takes the item changes made since the last call
+    pub(crate) fn take_item_changes(&mut self) -> Vec<ItemChange> {
+        std::mem::take(&mut self.item_changes)
+    }
+
+    fn clear_items(&mut self, keep: impl Fn(u64) -> bool) {
+        let removed: Vec<_> = self
+            .items
+            .iter()
+            .filter(|(id, _)| !keep(**id))
+            .map(|(id, count)| (*id, *count))
+            .collect();
+
+        for (id, previous) in removed {
+            self.items.remove(&id);
+            self.record_item_change(id, previous);
+        }
+    }
+
+    pub(crate) fn set_persistent(&mut self, kind: ItemKind, id: Option<u64>, persistent: bool) {
+        if persistent {
+            self.persistent.insert((kind, id));
+        } else {
+            self.persistent.remove(&(kind, id));
+        }
+    }
+
+    pub(crate) fn is_persistent(&self, kind: ItemKind, id: u64) -> bool {
+        self.persistent.contains(&(kind, Some(id))) || self.persistent.contains(&(kind, None))
+    }
+
+    pub(crate) fn reset(&mut self, kind: ItemKind, id: Option<u64>) {
+        match (kind, id) {
+            (ItemKind::Item, Some(id)) => self.clear_items(|item_id| item_id != id),
+            (ItemKind::Item, None) => self.clear_items(|_| false),
+            (ItemKind::Timer, Some(id)) => {
+                self.timers.remove(&id);
+            }
+            (ItemKind::Timer, None) => self.timers.clear(),
+            (ItemKind::Points, _) => self.points = 0,
+            (ItemKind::MainTime | ItemKind::Attempts, _) => (),
+        }
+    }
 
-impl Default for PickupValues {
-    fn default() -> Self {
-        Self([0; 1000])
+    /// Resets everything that wasn't marked as persistent by an item persist trigger
+    pub(crate) fn reset_non_persistent(&mut self) {
+        let persistent = std::mem::take(&mut self.persistent);
+        if !persistent.contains(&(ItemKind::Item, None)) {
+            self.clear_items(|id| persistent.contains(&(ItemKind::Item, Some(id))));
+        }
+        if !persistent.contains(&(ItemKind::Timer, None)) {
+            self.timers
+                .retain(|id, _| persistent.contains(&(ItemKind::Timer, Some(*id))));
+        }
+        if !persistent.contains(&(ItemKind::Points, None)) {
+            self.points = 0;
+        }
+        self.main_time = 0.;
+        self.persistent = persistent;
     }
 }
 
+#[derive(Clone, Copy, Debug, Default)]
+pub(crate) enum ItemOperator {
+    #[default]
+    Set,
+    Add,
+    Subtract,
+    Multiply,
+    Divide,
+}
+
+impl ItemOperator {
+    pub(crate) fn from_id(id: u8) -> ItemOperator {
+        match id {
+            1 => ItemOperator::Add,
+            2 => ItemOperator::Subtract,
+            3 => ItemOperator::Multiply,
+            4 => ItemOperator::Divide,
+            _ => ItemOperator::Set,
+        }
+    }
+
+    pub(crate) fn apply(self, lhs: f64, rhs: f64) -> f64 {
+        match self {
+            ItemOperator::Set => rhs,
+            ItemOperator::Add => lhs + rhs,
+            ItemOperator::Subtract => lhs - rhs,
+            ItemOperator::Multiply => lhs * rhs,
+            ItemOperator::Divide => {
+                // GD leaves the value untouched instead of producing infinity
+                if rhs == 0. {
+                    lhs
+                } else {
+                    lhs / rhs
+                }
+            }
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default)]
+pub(crate) enum RoundMode {
+    #[default]
+    None,
+    Round,
+    Floor,
+    Ceil,
+}
+
+impl RoundMode {
+    pub(crate) fn from_id(id: u8) -> RoundMode {
+        match id {
+            1 => RoundMode::Round,
+            2 => RoundMode::Floor,
+            3 => RoundMode::Ceil,
+            _ => RoundMode::None,
+        }
+    }
+
+    pub(crate) fn apply(self, value: f64) -> f64 {
+        match self {
+            RoundMode::None => value,
+            RoundMode::Round => value.round(),
+            RoundMode::Floor => value.floor(),
+            RoundMode::Ceil => value.ceil(),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default)]
+pub(crate) enum SignMode {
+    #[default]
+    None,
+    Absolute,
+    Negative,
+}
+
+impl SignMode {
+    pub(crate) fn from_id(id: u8) -> SignMode {
+        match id {
+            1 => SignMode::Absolute,
+            2 => SignMode::Negative,
+            _ => SignMode::None,
+        }
+    }
+
+    pub(crate) fn apply(self, value: f64) -> f64 {
+        match self {
+            SignMode::None => value,
+            SignMode::Absolute => value.abs(),
+            SignMode::Negative => -value.abs(),
+        }
+    }
+}
+
+/// Seconds since the start of the attempt, advanced by every tick
+#[derive(Clone, Copy, Default, Resource)]
+pub(crate) struct LevelTime(pub(crate) f64);
+
+pub(crate) fn update_level_time(time: Res<Time>, mut level_time: ResMut<LevelTime>) {
+    level_time.0 += time.delta_seconds_f64();
+}
+
+pub(crate) fn update_main_time(
+    level_time: Res<LevelTime>,
+    mut pickup_values: ResMut<PickupValues>,
+) {
+    pickup_values.main_time = level_time.0;
+}
+
 type PickupTriggerSystemParam = ResMut<'static, PickupValues>;
 
 impl TriggerFunction for PickupTrigger {
@@ -42,11 +298,7 @@ impl TriggerFunction for PickupTrigger {
 
         let mut pickup_values = system_state.get_mut(world);
 
-        let Some(entry) = pickup_values.0.get_mut(self.item_id as usize) else {
-            return;
-        };
-
-        *entry += self.count;
+        pickup_values.add_item(self.item_id, self.count);
     }
 
     fn create_system_state(&self, world: &mut World) -> Box<dyn Any + Send + Sync> {
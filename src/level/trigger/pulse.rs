@@ -9,7 +9,7 @@ use crate::level::color::{
     ColorChannelCalculated, ColorMod, GlobalColorChannels, ObjectColorKind, Pulses,
 };
 use crate::level::group::GlobalGroups;
-use crate::level::trigger::TriggerFunction;
+use crate::level::trigger::{GroupRemap, TriggerFunction};
 
 #[derive(Clone, Debug, Default)]
 pub(crate) struct PulseTrigger {
@@ -142,4 +142,10 @@ impl TriggerFunction for PulseTrigger {
     fn post(&self) -> bool {
         false
     }
+
+    fn remap(&mut self, remap: &GroupRemap) {
+        if self.target_is_group {
+            self.target_id = remap.get(self.target_id);
+        }
+    }
 }
@@ -0,0 +1,203 @@
+use std::any::Any;
+use std::ops::Range;
+
+use bevy::ecs::system::SystemState;
+use bevy::prelude::{Entity, ResMut, Resource, World};
+
+use crate::level::trigger::spawn::SpawnTrigger;
+use crate::level::trigger::{GroupRemap, TriggerFunction};
+
+#[derive(Clone, Debug, Default)]
+pub(crate) struct RandomTrigger {
+    pub(crate) group_a: u64,
+    pub(crate) group_b: u64,
+    /// Chance of activating the first group in percent
+    pub(crate) chance: f32,
+}
+
+#[derive(Clone, Debug, Default)]
+pub(crate) struct AdvancedRandomTrigger {
+    /// Groups with their weights
+    pub(crate) groups: Vec<(u64, u64)>,
+}
+
+/// Generator the random triggers roll with, reseeded from the level on restart so attempts and
+/// replays roll the same
+#[derive(Clone, Resource)]
+pub(crate) struct LevelRng {
+    seed: u64,
+    rng: fastrand::Rng,
+}
+
+impl LevelRng {
+    pub(crate) fn new(seed: u64) -> LevelRng {
+        LevelRng {
+            seed,
+            rng: fastrand::Rng::with_seed(seed),
+        }
+    }
+
+    pub(crate) fn reseed(&mut self) {
+        self.rng.seed(self.seed);
+    }
+}
+
+impl Default for LevelRng {
+    fn default() -> Self {
+        LevelRng::new(0)
+    }
+}
+
+type RandomTriggerSystemParam = ResMut<'static, LevelRng>;
+
+/// State of the generator next to the state of the spawn trigger activating the chosen group
+type RandomTriggerSystemState = (
+    SystemState<RandomTriggerSystemParam>,
+    Box<dyn Any + Send + Sync>,
+);
+
+fn create_random_system_state(world: &mut World) -> Box<dyn Any + Send + Sync> {
+    let system_state: RandomTriggerSystemState = (
+        SystemState::new(world),
+        SpawnTrigger::default().create_system_state(world),
+    );
+    Box::new(system_state)
+}
+
+/// Activates the chosen group the same way a spawn trigger would
+fn activate_group(
+    target_group: u64,
+    world: &mut World,
+    entity: Entity,
+    system_state: &mut Box<dyn Any + Send + Sync>,
+    range: Range<f32>,
+) {
+    if target_group == 0 {
+        return;
+    }
+
+    SpawnTrigger {
+        target_group,
+        ..Default::default()
+    }
+    .execute(world, entity, system_state, 0., 1., range);
+}
+
+impl TriggerFunction for RandomTrigger {
+    fn execute(
+        &self,
+        world: &mut World,
+        entity: Entity,
+        system_state: &mut Box<dyn Any + Send + Sync>,
+        _: f32,
+        progress: f32,
+        range: Range<f32>,
+    ) {
+        if progress != 1. {
+            return;
+        }
+
+        let (rng_state, spawn_state): &mut RandomTriggerSystemState =
+            system_state.downcast_mut().unwrap();
+
+        let roll = rng_state.get_mut(world).rng.f32();
+
+        let target_group = if roll * 100. < self.chance {
+            self.group_a
+        } else {
+            self.group_b
+        };
+
+        activate_group(target_group, world, entity, spawn_state, range);
+    }
+
+    fn create_system_state(&self, world: &mut World) -> Box<dyn Any + Send + Sync> {
+        create_random_system_state(world)
+    }
+
+    fn target_id(&self) -> u64 {
+        0
+    }
+
+    fn duration(&self) -> f32 {
+        0.
+    }
+
+    fn exclusive(&self) -> bool {
+        false
+    }
+
+    fn post(&self) -> bool {
+        false
+    }
+
+    fn remap(&mut self, remap: &GroupRemap) {
+        self.group_a = remap.get(self.group_a);
+        self.group_b = remap.get(self.group_b);
+    }
+}
+
+impl TriggerFunction for AdvancedRandomTrigger {
+    fn execute(
+        &self,
+        world: &mut World,
+        entity: Entity,
+        system_state: &mut Box<dyn Any + Send + Sync>,
+        _: f32,
+        progress: f32,
+        range: Range<f32>,
+    ) {
+        if progress != 1. {
+            return;
+        }
+
+        let total_weight: u64 = self.groups.iter().map(|(_, weight)| weight).sum();
+
+        if total_weight == 0 {
+            return;
+        }
+
+        let (rng_state, spawn_state): &mut RandomTriggerSystemState =
+            system_state.downcast_mut().unwrap();
+
+        let mut roll = rng_state.get_mut(world).rng.u64(0..total_weight);
+
+        let Some((target_group, _)) = self.groups.iter().find(|(_, weight)| {
+            if roll < *weight {
+                return true;
+            }
+            roll -= weight;
+            false
+        }) else {
+            return;
+        };
+
+        activate_group(*target_group, world, entity, spawn_state, range);
+    }
+
+    fn create_system_state(&self, world: &mut World) -> Box<dyn Any + Send + Sync> {
+        create_random_system_state(world)
+    }
+
+    fn target_id(&self) -> u64 {
+        0
+    }
+
+    fn duration(&self) -> f32 {
+        0.
+    }
+
+    fn exclusive(&self) -> bool {
+        false
+    }
+
+    fn post(&self) -> bool {
+        false
+    }
+
+    fn remap(&mut self, remap: &GroupRemap) {
+        for (group, _) in &mut self.groups {
+            *group = remap.get(*group);
+        }
+    }
+}
@@ -7,7 +7,7 @@ use bevy::prelude::{Entity, Query, Res, World};
 
 use crate::level::easing::Easing;
 use crate::level::group::{GlobalGroup, GlobalGroupDeltas, GlobalGroups, RotationKind};
-use crate::level::trigger::TriggerFunction;
+use crate::level::trigger::{GroupRemap, TriggerFunction};
 
 #[derive(Clone, Debug, Default)]
 pub(crate) struct RotateTrigger {
@@ -91,4 +91,9 @@ impl TriggerFunction for RotateTrigger {
     fn post(&self) -> bool {
         false
     }
+
+    fn remap(&mut self, remap: &GroupRemap) {
+        self.target_group = remap.get(self.target_group);
+        self.center_group = remap.get(self.center_group);
+    }
 }
@@ -0,0 +1,111 @@
+use std::any::Any;
+use std::ops::Range;
+
+use bevy::ecs::system::SystemState;
+use bevy::math::Vec2;
+use bevy::prelude::{Entity, Query, Res, World};
+
+use crate::level::easing::Easing;
+use crate::level::group::{GlobalGroup, GlobalGroupDeltas, GlobalGroups, ScaleDelta};
+use crate::level::trigger::{GroupRemap, TriggerFunction};
+
+#[derive(Clone, Debug)]
+pub(crate) struct ScaleTrigger {
+    pub(crate) duration: f32,
+    pub(crate) easing: Easing,
+    pub(crate) target_group: u64,
+    pub(crate) center_group: u64,
+    pub(crate) scale: Vec2,
+    pub(crate) only_move: bool,
+}
+
+impl Default for ScaleTrigger {
+    fn default() -> Self {
+        Self {
+            duration: 0.,
+            easing: Easing::default(),
+            target_group: 0,
+            center_group: 0,
+            scale: Vec2::ONE,
+            only_move: false,
+        }
+    }
+}
+
+type ScaleTriggerSystemParam = (
+    Res<'static, GlobalGroups>,
+    Query<'static, 'static, &'static GlobalGroup>,
+    Query<'static, 'static, &'static mut GlobalGroupDeltas>,
+);
+
+impl TriggerFunction for ScaleTrigger {
+    fn execute(
+        &self,
+        world: &mut World,
+        _: Entity,
+        system_state: &mut Box<dyn Any + Send + Sync>,
+        previous_progress: f32,
+        progress: f32,
+        _: Range<f32>,
+    ) {
+        let system_state: &mut SystemState<ScaleTriggerSystemParam> =
+            &mut *system_state.downcast_mut().unwrap();
+
+        let (global_groups, group_query, mut group_delta_query) = system_state.get_mut(world);
+
+        let Some(group_entity) = global_groups.0.get(self.target_group as usize) else {
+            return;
+        };
+
+        let center = global_groups
+            .0
+            .get(self.center_group as usize)
+            .and_then(|entity| group_query.get(*entity).ok())
+            .and_then(|group| group.root_entities.first().copied());
+
+        let Ok(mut global_group_delta) = group_delta_query.get_mut(*group_entity) else {
+            return;
+        };
+
+        // Scaling is multiplicative, so the eased progress is applied as an exponent
+        let amount = self.easing.sample(progress) - self.easing.sample(previous_progress);
+
+        let factor = self.scale.powf(amount);
+
+        let factor = match global_group_delta.scale {
+            Some(scale) => scale.factor * factor,
+            None => factor,
+        };
+
+        global_group_delta.scale = Some(ScaleDelta {
+            center,
+            factor,
+            only_move: self.only_move,
+        });
+    }
+
+    fn create_system_state(&self, world: &mut World) -> Box<dyn Any + Send + Sync> {
+        Box::new(SystemState::<ScaleTriggerSystemParam>::new(world))
+    }
+
+    fn target_id(&self) -> u64 {
+        self.target_group
+    }
+
+    fn duration(&self) -> f32 {
+        self.duration
+    }
+
+    fn exclusive(&self) -> bool {
+        false
+    }
+
+    fn post(&self) -> bool {
+        false
+    }
+
+    fn remap(&mut self, remap: &GroupRemap) {
+        self.target_group = remap.get(self.target_group);
+        self.center_group = remap.get(self.center_group);
+    }
+}
@@ -0,0 +1,157 @@
+use std::any::Any;
+use std::ops::Range;
+
+use bevy::ecs::system::SystemState;
+use bevy::prelude::{Entity, Query, Res, ResMut, Resource, With, Without, World};
+use bevy::utils::HashMap;
+
+use crate::level::color::ObjectColorCalculated;
+use crate::level::group::{GlobalGroup, GlobalGroups, ObjectGroups};
+use crate::level::trigger::{
+    queue_group_spawn, Activated, GlobalTriggers, GroupRemap, MultiActivate, SpawnActivate,
+    Trigger, TriggerData, TriggerFunction,
+};
+
+#[derive(Clone, Debug, Default)]
+pub(crate) struct SequenceTrigger {
+    /// Groups to spawn paired with how many activations each step lasts
+    pub(crate) sequence: Vec<(u64, u64)>,
+    pub(crate) mode: SequenceMode,
+}
+
+#[derive(Clone, Copy, Debug, Default)]
+pub(crate) enum SequenceMode {
+    #[default]
+    Stop,
+    Loop,
+    Last,
+}
+
+impl SequenceMode {
+    pub(crate) fn from_id(id: u8) -> SequenceMode {
+        match id {
+            1 => SequenceMode::Loop,
+            2 => SequenceMode::Last,
+            _ => SequenceMode::Stop,
+        }
+    }
+}
+
+/// Current step and the activations spent on it for every sequence trigger
+#[derive(Clone, Default, Resource)]
+pub(crate) struct SequenceStates(HashMap<Entity, (usize, u64)>);
+
+type SequenceTriggerSystemParam = (
+    Res<'static, GlobalGroups>,
+    Res<'static, GlobalTriggers>,
+    ResMut<'static, TriggerData>,
+    ResMut<'static, SequenceStates>,
+    Query<'static, 'static, &'static GlobalGroup>,
+    Query<
+        'static,
+        'static,
+        (
+            Entity,
+            &'static Trigger,
+            &'static ObjectGroups,
+            &'static ObjectColorCalculated,
+            Option<&'static MultiActivate>,
+        ),
+        (With<SpawnActivate>, Without<Activated>),
+    >,
+);
+
+impl TriggerFunction for SequenceTrigger {
+    fn execute(
+        &self,
+        world: &mut World,
+        entity: Entity,
+        system_state: &mut Box<dyn Any + Send + Sync>,
+        _: f32,
+        progress: f32,
+        range: Range<f32>,
+    ) {
+        if progress != 1. || self.sequence.is_empty() {
+            return;
+        }
+
+        let system_state: &mut SystemState<SequenceTriggerSystemParam> =
+            system_state.downcast_mut().unwrap();
+
+        let (
+            global_groups,
+            global_triggers,
+            mut trigger_data,
+            mut sequence_states,
+            group_query,
+            trigger_query,
+        ) = system_state.get_mut(world);
+
+        let (step, spent) = sequence_states.0.entry(entity).or_default();
+
+        if *step >= self.sequence.len() {
+            match self.mode {
+                SequenceMode::Stop => return,
+                SequenceMode::Loop => *step = 0,
+                SequenceMode::Last => *step = self.sequence.len() - 1,
+            }
+            *spent = 0;
+        }
+
+        let (target_group, count) = self.sequence[*step];
+
+        *spent += 1;
+        if *spent >= count {
+            *step += 1;
+            *spent = 0;
+        }
+
+        let Some(group_entity) = global_groups.0.get(target_group as usize) else {
+            return;
+        };
+
+        let Ok(group) = group_query.get(*group_entity) else {
+            return;
+        };
+
+        let start_time = global_triggers.speed_changes.time_for_pos(range.start);
+
+        let activated = queue_group_spawn(
+            group,
+            start_time,
+            &global_triggers,
+            &mut trigger_data,
+            &trigger_query,
+        );
+
+        for entity in activated {
+            world.entity_mut(entity).insert(Activated);
+        }
+    }
+
+    fn create_system_state(&self, world: &mut World) -> Box<dyn Any + Send + Sync> {
+        Box::new(SystemState::<SequenceTriggerSystemParam>::new(world))
+    }
+
+    fn target_id(&self) -> u64 {
+        0
+    }
+
+    fn duration(&self) -> f32 {
+        0.
+    }
+
+    fn exclusive(&self) -> bool {
+        false
+    }
+
+    fn post(&self) -> bool {
+        false
+    }
+
+    fn remap(&mut self, remap: &GroupRemap) {
+        for (target_group, _) in &mut self.sequence {
+            *target_group = remap.get(*target_group);
+        }
+    }
+}
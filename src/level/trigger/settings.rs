@@ -0,0 +1,168 @@
+use std::any::Any;
+use std::ops::Range;
+
+use bevy::ecs::system::SystemState;
+use bevy::prelude::{Entity, Query, ResMut, Resource, World};
+
+use crate::level::death::RESPAWN_DELAY;
+use crate::level::player::Player;
+use crate::level::trigger::TriggerFunction;
+
+/// Level wide settings changed by triggers, for the parts of the game that read them
+#[derive(Clone, Resource)]
+pub(crate) struct LevelSettings {
+    pub(crate) bg_effects: bool,
+    pub(crate) streak_additive: bool,
+    pub(crate) unlink_dual_gravity: bool,
+    pub(crate) hide_ground: bool,
+    pub(crate) hide_middleground: bool,
+    pub(crate) hide_attempts: bool,
+    pub(crate) disable_controls: bool,
+    pub(crate) respawn_time: Option<f32>,
+}
+
+impl Default for LevelSettings {
+    fn default() -> Self {
+        Self {
+            bg_effects: true,
+            streak_additive: false,
+            unlink_dual_gravity: false,
+            hide_ground: false,
+            hide_middleground: false,
+            hide_attempts: false,
+            disable_controls: false,
+            respawn_time: None,
+        }
+    }
+}
+
+impl LevelSettings {
+    /// How long the death effect plays before the player respawns, in seconds
+    pub(crate) fn respawn_delay(&self) -> f32 {
+        self.respawn_time.unwrap_or(RESPAWN_DELAY)
+    }
+}
+
+#[derive(Clone, Debug, Default)]
+pub(crate) struct BgEffectTrigger {
+    pub(crate) enabled: bool,
+}
+
+/// Options left as `None` are not changed by the trigger
+#[derive(Clone, Debug, Default)]
+pub(crate) struct OptionsTrigger {
+    pub(crate) streak_additive: Option<bool>,
+    pub(crate) unlink_dual_gravity: Option<bool>,
+    pub(crate) hide_ground: Option<bool>,
+    pub(crate) hide_player: Option<bool>,
+    pub(crate) disable_controls: Option<bool>,
+    pub(crate) hide_middleground: Option<bool>,
+    pub(crate) hide_attempts: Option<bool>,
+    pub(crate) respawn_time: Option<f32>,
+}
+
+type SettingsTriggerSystemParam = (
+    ResMut<'static, LevelSettings>,
+    Query<'static, 'static, &'static mut Player>,
+);
+
+impl TriggerFunction for BgEffectTrigger {
+    fn execute(
+        &self,
+        world: &mut World,
+        _: Entity,
+        system_state: &mut Box<dyn Any + Send + Sync>,
+        _: f32,
+        _: f32,
+        _: Range<f32>,
+    ) {
+        let system_state: &mut SystemState<SettingsTriggerSystemParam> =
+            system_state.downcast_mut().unwrap();
+
+        let (mut level_settings, _) = system_state.get_mut(world);
+
+        level_settings.bg_effects = self.enabled;
+    }
+
+    fn create_system_state(&self, world: &mut World) -> Box<dyn Any + Send + Sync> {
+        Box::new(SystemState::<SettingsTriggerSystemParam>::new(world))
+    }
+
+    fn target_id(&self) -> u64 {
+        0
+    }
+
+    fn duration(&self) -> f32 {
+        0.
+    }
+
+    fn exclusive(&self) -> bool {
+        false
+    }
+
+    fn post(&self) -> bool {
+        false
+    }
+}
+
+impl TriggerFunction for OptionsTrigger {
+    fn execute(
+        &self,
+        world: &mut World,
+        _: Entity,
+        system_state: &mut Box<dyn Any + Send + Sync>,
+        _: f32,
+        _: f32,
+        _: Range<f32>,
+    ) {
+        let system_state: &mut SystemState<SettingsTriggerSystemParam> =
+            system_state.downcast_mut().unwrap();
+
+        let (mut level_settings, mut players) = system_state.get_mut(world);
+
+        let settings = &mut *level_settings;
+
+        for (option, value) in [
+            (self.streak_additive, &mut settings.streak_additive),
+            (self.unlink_dual_gravity, &mut settings.unlink_dual_gravity),
+            (self.hide_ground, &mut settings.hide_ground),
+            (self.disable_controls, &mut settings.disable_controls),
+            (self.hide_middleground, &mut settings.hide_middleground),
+            (self.hide_attempts, &mut settings.hide_attempts),
+        ] {
+            if let Some(option) = option {
+                *value = option;
+            }
+        }
+
+        if self.respawn_time.is_some() {
+            settings.respawn_time = self.respawn_time;
+        }
+
+        if let Some(hide_player) = self.hide_player {
+            for mut player in &mut players {
+                player.hidden = hide_player;
+            }
+        }
+    }
+
+    fn create_system_state(&self, world: &mut World) -> Box<dyn Any + Send + Sync> {
+        Box::new(SystemState::<SettingsTriggerSystemParam>::new(world))
+    }
+
+    fn target_id(&self) -> u64 {
+        0
+    }
+
+    fn duration(&self) -> f32 {
+        0.
+    }
+
+    fn exclusive(&self) -> bool {
+        false
+    }
+
+    fn post(&self) -> bool {
+        false
+    }
+}
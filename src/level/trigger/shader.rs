@@ -0,0 +1,269 @@
+use std::any::Any;
+use std::ops::Range;
+
+use bevy::ecs::system::SystemState;
+use bevy::math::{Vec2, Vec3Swizzles, Vec4};
+use bevy::prelude::{Entity, Query, Res, ResMut, Resource, With, Without, World};
+use bevy::time::Time;
+
+use crate::level::easing::Easing;
+use crate::level::group::{GlobalGroup, GlobalGroups};
+use crate::level::player::Player;
+use crate::level::transform::Transform2d;
+use crate::level::trigger::{GroupRemap, TriggerFunction};
+use crate::utils::{lerp, lerp_start};
+
+pub(crate) const SHADER_EFFECT_COUNT: usize = 17;
+
+/// The parameters of each effect are stored in a [`Vec4`], laid out as noted on each variant
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub(crate) enum ShaderEffect {
+    /// Radius, thickness, strength
+    #[default]
+    Shockwave,
+    /// Offset, thickness, strength, vertical
+    Shockline,
+    /// Strength, speed, slice height
+    Glitch,
+    /// X offset, y offset
+    Chromatic,
+    /// Strength, speed, line thickness
+    ChromaticGlitch,
+    /// X size, y size
+    Pixelate,
+    /// Radius, strength
+    LensCircle,
+    /// Size, power
+    RadialBlur,
+    /// X intensity, y intensity
+    MotionBlur,
+    /// Radius, strength
+    Bulge,
+    /// Radius, x strength, y strength
+    Pinch,
+    /// Amount
+    GrayScale,
+    /// Amount
+    Sepia,
+    /// Amount
+    InvertColor,
+    /// Degrees
+    Hue,
+    /// Red, green, blue multipliers
+    EditColor,
+    /// Columns, rows
+    SplitScreen,
+}
+
+impl ShaderEffect {
+    pub(crate) fn from_object_id(id: u64) -> Option<ShaderEffect> {
+        Some(match id {
+            2905 => ShaderEffect::Shockwave,
+            2907 => ShaderEffect::Shockline,
+            2909 => ShaderEffect::Glitch,
+            2910 => ShaderEffect::Chromatic,
+            2911 => ShaderEffect::ChromaticGlitch,
+            2912 => ShaderEffect::Pixelate,
+            2913 => ShaderEffect::LensCircle,
+            2914 => ShaderEffect::RadialBlur,
+            2915 => ShaderEffect::MotionBlur,
+            2916 => ShaderEffect::Bulge,
+            2917 => ShaderEffect::Pinch,
+            2919 => ShaderEffect::GrayScale,
+            2920 => ShaderEffect::Sepia,
+            2921 => ShaderEffect::InvertColor,
+            2922 => ShaderEffect::Hue,
+            2923 => ShaderEffect::EditColor,
+            2924 => ShaderEffect::SplitScreen,
+            _ => return None,
+        })
+    }
+
+    pub(crate) fn from_index(index: usize) -> ShaderEffect {
+        const EFFECTS: [ShaderEffect; SHADER_EFFECT_COUNT] = [
+            ShaderEffect::Shockwave,
+            ShaderEffect::Shockline,
+            ShaderEffect::Glitch,
+            ShaderEffect::Chromatic,
+            ShaderEffect::ChromaticGlitch,
+            ShaderEffect::Pixelate,
+            ShaderEffect::LensCircle,
+            ShaderEffect::RadialBlur,
+            ShaderEffect::MotionBlur,
+            ShaderEffect::Bulge,
+            ShaderEffect::Pinch,
+            ShaderEffect::GrayScale,
+            ShaderEffect::Sepia,
+            ShaderEffect::InvertColor,
+            ShaderEffect::Hue,
+            ShaderEffect::EditColor,
+            ShaderEffect::SplitScreen,
+        ];
+        EFFECTS[index]
+    }
+
+    /// Parameters that leave the screen untouched
+    pub(crate) fn identity(self) -> Vec4 {
+        match self {
+            ShaderEffect::Pixelate | ShaderEffect::SplitScreen => Vec4::new(1., 1., 0., 0.),
+            ShaderEffect::EditColor => Vec4::new(1., 1., 1., 0.),
+            _ => Vec4::ZERO,
+        }
+    }
+
+    pub(crate) fn is_active(self, params: Vec4) -> bool {
+        match self {
+            ShaderEffect::Shockwave | ShaderEffect::Shockline => params.z != 0. && params.y > 0.,
+            ShaderEffect::Pixelate | ShaderEffect::SplitScreen => {
+                params.x.round() > 1. || params.y.round() > 1.
+            }
+            _ => params != self.identity(),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default)]
+pub(crate) enum ShaderCenter {
+    #[default]
+    Screen,
+    Player,
+    Group(u64),
+}
+
+#[derive(Clone, Copy, Debug)]
+pub(crate) struct ShaderLayer {
+    pub(crate) params: Vec4,
+    pub(crate) center: ShaderCenter,
+    /// World position of the center, `None` when centered on the screen
+    pub(crate) center_position: Option<Vec2>,
+}
+
+#[derive(Clone, Resource)]
+pub(crate) struct ShaderState {
+    pub(crate) layers: [ShaderLayer; SHADER_EFFECT_COUNT],
+    pub(crate) time: f32,
+}
+
+impl Default for ShaderState {
+    fn default() -> Self {
+        let mut layers = [ShaderLayer {
+            params: Vec4::ZERO,
+            center: ShaderCenter::Screen,
+            center_position: None,
+        }; SHADER_EFFECT_COUNT];
+
+        for (index, layer) in layers.iter_mut().enumerate() {
+            layer.params = ShaderEffect::from_index(index).identity();
+        }
+
+        Self { layers, time: 0. }
+    }
+}
+
+pub(crate) fn update_shader_state(
+    time: Res<Time>,
+    mut shader_state: ResMut<ShaderState>,
+    global_groups: Res<GlobalGroups>,
+    groups: Query<&GlobalGroup>,
+    players: Query<&Transform2d, With<Player>>,
+    objects: Query<&Transform2d, Without<Player>>,
+) {
+    shader_state.time += time.delta_seconds();
+
+    for layer in &mut shader_state.layers {
+        layer.center_position = match layer.center {
+            ShaderCenter::Screen => None,
+            ShaderCenter::Player => players
+                .iter()
+                .next()
+                .map(|transform| transform.translation.xy()),
+            ShaderCenter::Group(group) => global_groups
+                .0
+                .get(group as usize)
+                .and_then(|group_entity| groups.get(*group_entity).ok())
+                .and_then(|group| group.root_entities.first())
+                .and_then(|entity| objects.get(*entity).ok())
+                .map(|transform| transform.translation.xy()),
+        };
+    }
+}
+
+#[derive(Clone, Debug, Default)]
+pub(crate) struct ShaderTrigger {
+    pub(crate) effect: ShaderEffect,
+    pub(crate) duration: f32,
+    pub(crate) easing: Easing,
+    pub(crate) params: Vec4,
+    pub(crate) center: ShaderCenter,
+}
+
+type ShaderTriggerSystemParam = ResMut<'static, ShaderState>;
+
+impl TriggerFunction for ShaderTrigger {
+    fn execute(
+        &self,
+        world: &mut World,
+        _: Entity,
+        system_state: &mut Box<dyn Any + Send + Sync>,
+        previous_progress: f32,
+        progress: f32,
+        _: Range<f32>,
+    ) {
+        let system_state: &mut SystemState<ShaderTriggerSystemParam> =
+            system_state.downcast_mut().unwrap();
+
+        let mut shader_state = system_state.get_mut(world);
+
+        let layer = &mut shader_state.layers[self.effect as usize];
+
+        layer.center = self.center;
+
+        let is_wave = matches!(
+            self.effect,
+            ShaderEffect::Shockwave | ShaderEffect::Shockline
+        );
+
+        if is_wave && previous_progress == 0. {
+            // Waves start from their center at full strength
+            layer.params = self.params;
+            layer.params.x = 0.;
+        }
+
+        let previous_progress = self.easing.sample(previous_progress);
+        let eased_progress = self.easing.sample(progress);
+
+        let original_params = lerp_start(layer.params, self.params, previous_progress);
+        layer.params = lerp(original_params, self.params, eased_progress);
+
+        if is_wave && progress == 1. {
+            // The wave has travelled its full distance
+            layer.params = self.effect.identity();
+        }
+    }
+
+    fn create_system_state(&self, world: &mut World) -> Box<dyn Any + Send + Sync> {
+        Box::new(SystemState::<ShaderTriggerSystemParam>::new(world))
+    }
+
+    fn target_id(&self) -> u64 {
+        self.effect as u64
+    }
+
+    fn duration(&self) -> f32 {
+        self.duration
+    }
+
+    fn exclusive(&self) -> bool {
+        true
+    }
+
+    fn post(&self) -> bool {
+        false
+    }
+
+    fn remap(&mut self, remap: &GroupRemap) {
+        if let ShaderCenter::Group(group) = &mut self.center {
+            *group = remap.get(*group);
+        }
+    }
+}
@@ -7,7 +7,7 @@ use bevy::prelude::{Entity, ResMut, Resource, World};
 
 use crate::level::trigger::TriggerFunction;
 
-#[derive(Default, Resource)]
+#[derive(Clone, Default, Resource)]
 pub(crate) struct ShakeData(pub(crate) f32, pub(crate) f32);
 
 #[derive(Clone, Debug, Default)]
@@ -7,14 +7,19 @@ use float_next_after::NextAfter;
 
 use crate::level::color::ObjectColorCalculated;
 use crate::level::group::{GlobalGroup, GlobalGroups, ObjectGroups};
+use crate::level::transform::Transform2d;
 use crate::level::trigger::{
-    Activated, GlobalTriggers, MultiActivate, SpawnActivate, Trigger, TriggerData, TriggerFunction,
+    Activated, GlobalTriggers, GroupRemap, MultiActivate, SpawnActivate, Trigger, TriggerData,
+    TriggerFunction,
 };
 
 #[derive(Clone, Debug, Default)]
 pub(crate) struct SpawnTrigger {
     pub(crate) target_group: u64,
     pub(crate) delay: f32,
+    pub(crate) ordered: bool,
+    pub(crate) remap: GroupRemap,
+    pub(crate) reset_remap: bool,
 }
 
 type SpawnTriggerSystemParam = (
@@ -30,6 +35,7 @@ type SpawnTriggerSystemParam = (
             &'static Trigger,
             &'static ObjectGroups,
             &'static ObjectColorCalculated,
+            &'static Transform2d,
             Option<&'static MultiActivate>,
         ),
         (With<SpawnActivate>, Without<Activated>),
@@ -58,7 +64,6 @@ impl TriggerFunction for SpawnTrigger {
 
         let trigger_time = global_triggers.speed_changes.time_for_pos(range.start);
         let start_time = trigger_time + self.duration();
-        let start_pos = global_triggers.speed_changes.pos_for_time(start_time);
 
         let Some(target_group) = global_groups.0.get(self.target_group as usize) else {
             return;
@@ -68,26 +73,56 @@ impl TriggerFunction for SpawnTrigger {
             return;
         };
 
+        let mut to_spawn: Vec<_> = trigger_query
+            .iter_many(&group.root_entities)
+            .filter(|(_, _, _, calculated, _, _)| calculated.enabled)
+            .collect();
+
+        if self.ordered {
+            to_spawn.sort_by(|(_, _, _, _, a, _), (_, _, _, _, b, _)| {
+                a.translation.x.total_cmp(&b.translation.x)
+            });
+        }
+
+        // Ordered triggers are delayed by how far right of the first one they are
+        let first_x = to_spawn
+            .first()
+            .map_or(0., |(_, _, _, _, transform, _)| transform.translation.x);
+        let speed_per_sec = global_triggers
+            .speed_changes
+            .speed_data_at_time(start_time)
+            .1
+            .speed_per_sec;
+
         let mut activated = Vec::new();
 
-        for (entity, trigger, object_groups, calculated, multi_activate) in
-            trigger_query.iter_many(&group.root_entities)
-        {
-            if !calculated.enabled {
-                continue;
+        for (entity, trigger, object_groups, _, transform, multi_activate) in to_spawn {
+            let mut trigger_start_time = start_time;
+
+            if self.ordered {
+                trigger_start_time += (transform.translation.x - first_x) / speed_per_sec;
             }
 
+            let start_pos = global_triggers
+                .speed_changes
+                .pos_for_time(trigger_start_time);
             let mut end_pos = global_triggers
                 .speed_changes
-                .pos_for_time(start_time + trigger.0.duration());
+                .pos_for_time(trigger_start_time + trigger.0.duration());
 
             if end_pos == start_pos {
                 end_pos = end_pos.next_after(f32::INFINITY);
             }
 
+            let mut trigger = trigger.clone();
+
+            if !self.remap.is_empty() {
+                trigger.0.remap(&self.remap);
+            }
+
             trigger_data.to_spawn.push((
                 entity,
-                trigger.clone(),
+                trigger,
                 object_groups.groups.clone(),
                 start_pos..end_pos,
             ));
@@ -123,4 +158,11 @@ impl TriggerFunction for SpawnTrigger {
     fn post(&self) -> bool {
         false
     }
+
+    fn remap(&mut self, remap: &GroupRemap) {
+        self.target_group = remap.get(self.target_group);
+        if !self.reset_remap {
+            self.remap.inherit(remap);
+        }
+    }
 }
@@ -4,11 +4,39 @@ use std::ops::Range;
 use bevy::ecs::system::SystemState;
 use bevy::prelude::{Entity, ResMut, World};
 
-use crate::level::trigger::{TriggerData, TriggerFunction};
+use crate::level::trigger::{GroupRemap, TriggerData, TriggerFunction};
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub(crate) enum StopOption {
+    #[default]
+    Stop,
+    Pause,
+    Resume,
+}
+
+impl StopOption {
+    pub(crate) fn from_id(id: u8) -> Self {
+        match id {
+            1 => StopOption::Pause,
+            2 => StopOption::Resume,
+            _ => StopOption::Stop,
+        }
+    }
+}
+
+/// What triggers a stop trigger affects
+#[derive(Clone, Copy, Debug)]
+pub(crate) enum StopTarget {
+    Group(u64),
+    ControlId(u64),
+}
 
 #[derive(Clone, Debug, Default)]
 pub(crate) struct StopTrigger {
+    /// The control id instead when `use_control_id` is set
     pub(crate) target_group: u64,
+    pub(crate) use_control_id: bool,
+    pub(crate) option: StopOption,
 }
 
 type StopTriggerSystemParam = ResMut<'static, TriggerData>;
@@ -27,7 +55,31 @@ impl TriggerFunction for StopTrigger {
             system_state.downcast_mut().unwrap();
 
         let mut trigger_data = system_state.get_mut(world);
-        trigger_data.stopped.insert(self.target_group, range.start);
+
+        let target = if self.use_control_id {
+            StopTarget::ControlId(self.target_group)
+        } else {
+            StopTarget::Group(self.target_group)
+        };
+
+        match (self.option, target) {
+            (StopOption::Stop, StopTarget::Group(group_id)) => {
+                trigger_data.stopped.insert(group_id, range.start);
+            }
+            (StopOption::Stop, StopTarget::ControlId(control_id)) => {
+                trigger_data
+                    .stopped_control_ids
+                    .insert(control_id, range.start);
+            }
+            (StopOption::Pause, _) => {
+                trigger_data.pause_changes.push((target, range.start, true));
+            }
+            (StopOption::Resume, _) => {
+                trigger_data
+                    .pause_changes
+                    .push((target, range.start, false));
+            }
+        }
     }
 
     fn create_system_state(&self, world: &mut World) -> Box<dyn Any + Send + Sync> {
@@ -49,4 +101,10 @@ impl TriggerFunction for StopTrigger {
     fn post(&self) -> bool {
         false
     }
+
+    fn remap(&mut self, remap: &GroupRemap) {
+        if !self.use_control_id {
+            self.target_group = remap.get(self.target_group);
+        }
+    }
 }
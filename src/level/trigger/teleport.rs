@@ -0,0 +1,151 @@
+use std::any::Any;
+use std::ops::Range;
+
+use bevy::ecs::system::SystemState;
+use bevy::math::{Vec2, Vec3Swizzles};
+use bevy::prelude::{Entity, EventWriter, Query, Res, ResMut, Resource, Without, World};
+
+use crate::level::event::{GameEvent, PlayerEvent, PlayerEventKind};
+use crate::level::group::{GlobalGroup, GlobalGroups};
+use crate::level::player::Player;
+use crate::level::portal::DualPlayer;
+use crate::level::transform::Transform2d;
+use crate::level::trigger::{GroupRemap, TriggerFunction};
+
+#[derive(Clone, Debug)]
+pub(crate) enum TeleportTarget {
+    /// Teleports to the first object of the group
+    Group(u64),
+    /// Teleports relative to the trigger itself, used by teleport portals
+    Offset(Vec2),
+}
+
+impl Default for TeleportTarget {
+    fn default() -> Self {
+        TeleportTarget::Group(0)
+    }
+}
+
+#[derive(Clone, Debug, Default)]
+pub(crate) struct TeleportTrigger {
+    pub(crate) target: TeleportTarget,
+}
+
+/// Teleports waiting to be applied once the triggers for this frame are processed
+#[derive(Default, Resource)]
+pub(crate) struct Teleports(Option<Vec2>);
+
+impl Teleports {
+    pub(crate) fn teleport(&mut self, destination: Vec2) {
+        self.0 = Some(destination);
+    }
+}
+
+/// Position of the first object of the group
+pub(crate) fn group_position(
+    global_groups: &GlobalGroups,
+    groups: &Query<&GlobalGroup>,
+    objects: &Query<&Transform2d, Without<Player>>,
+    target_group: u64,
+) -> Option<Vec2> {
+    global_groups
+        .0
+        .get(target_group as usize)
+        .and_then(|group_entity| groups.get(*group_entity).ok())
+        .and_then(|group| group.root_entities.first())
+        .and_then(|entity| objects.get(*entity).ok())
+        .map(|transform| transform.translation.xy())
+}
+
+pub(crate) fn apply_teleports(
+    mut teleports: ResMut<Teleports>,
+    mut players: Query<(&mut Player, &mut Transform2d, Option<&DualPlayer>)>,
+    mut player_events: EventWriter<PlayerEvent>,
+) {
+    let Some(destination) = teleports.0.take() else {
+        return;
+    };
+
+    for (mut player, mut transform, dual_player) in &mut players {
+        transform.translation.x = destination.x;
+        transform.translation.y = destination.y;
+        // Start the next sweep from the destination instead of activating everything in between
+        player.last_translation = destination;
+
+        player_events.send(PlayerEvent {
+            kind: PlayerEventKind::Game(GameEvent::Teleported),
+            player2: dual_player.is_some(),
+        });
+    }
+}
+
+type TeleportTriggerSystemParam = (
+    ResMut<'static, Teleports>,
+    Res<'static, GlobalGroups>,
+    Query<'static, 'static, &'static GlobalGroup>,
+    Query<'static, 'static, &'static Transform2d, Without<Player>>,
+);
+
+impl TriggerFunction for TeleportTrigger {
+    fn execute(
+        &self,
+        world: &mut World,
+        entity: Entity,
+        system_state: &mut Box<dyn Any + Send + Sync>,
+        _: f32,
+        progress: f32,
+        _: Range<f32>,
+    ) {
+        if progress != 1. {
+            return;
+        }
+
+        let system_state: &mut SystemState<TeleportTriggerSystemParam> =
+            system_state.downcast_mut().unwrap();
+
+        let (mut teleports, global_groups, groups, objects) = system_state.get_mut(world);
+
+        let destination = match &self.target {
+            TeleportTarget::Group(target_group) => {
+                group_position(&global_groups, &groups, &objects, *target_group)
+            }
+            TeleportTarget::Offset(offset) => objects
+                .get(entity)
+                .ok()
+                .map(|transform| transform.translation.xy() + *offset),
+        };
+
+        if let Some(destination) = destination {
+            teleports.teleport(destination);
+        }
+    }
+
+    fn create_system_state(&self, world: &mut World) -> Box<dyn Any + Send + Sync> {
+        Box::new(SystemState::<TeleportTriggerSystemParam>::new(world))
+    }
+
+    fn target_id(&self) -> u64 {
+        match self.target {
+            TeleportTarget::Group(target_group) => target_group,
+            TeleportTarget::Offset(_) => 0,
+        }
+    }
+
+    fn duration(&self) -> f32 {
+        0.
+    }
+
+    fn exclusive(&self) -> bool {
+        false
+    }
+
+    fn post(&self) -> bool {
+        false
+    }
+
+    fn remap(&mut self, remap: &GroupRemap) {
+        if let TeleportTarget::Group(target_group) = &mut self.target {
+            *target_group = remap.get(*target_group);
+        }
+    }
+}
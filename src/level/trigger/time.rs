@@ -0,0 +1,373 @@
+use std::any::Any;
+use std::ops::Range;
+
+use bevy::ecs::system::SystemState;
+use bevy::prelude::{Commands, Entity, Query, Res, ResMut, Resource, With, Without, World};
+use bevy::time::{Time, Virtual};
+use bevy::utils::hashbrown;
+
+use crate::level::color::ObjectColorCalculated;
+use crate::level::group::{GlobalGroup, GlobalGroups, ObjectGroups};
+use crate::level::player::Player;
+use crate::level::portal::DualPlayer;
+use crate::level::trigger::pickup::{ItemKind, PickupValues};
+use crate::level::trigger::{
+    queue_group_spawn, Activated, GlobalTriggers, GroupRemap, MultiActivate, SpawnActivate,
+    Trigger, TriggerData, TriggerFunction,
+};
+use crate::utils::U64Hash;
+
+#[derive(Clone, Debug)]
+pub(crate) struct TimeTrigger {
+    pub(crate) item_id: u64,
+    pub(crate) start_time: f64,
+    pub(crate) dont_override: bool,
+    pub(crate) ignore_timewarp: bool,
+    pub(crate) time_mod: f64,
+    pub(crate) start_paused: bool,
+    pub(crate) stop_time: Option<f64>,
+    pub(crate) target_group: u64,
+}
+
+impl Default for TimeTrigger {
+    fn default() -> Self {
+        Self {
+            item_id: 0,
+            start_time: 0.,
+            dont_override: false,
+            ignore_timewarp: false,
+            time_mod: 1.,
+            start_paused: false,
+            stop_time: None,
+            target_group: 0,
+        }
+    }
+}
+
+#[derive(Clone, Debug, Default)]
+pub(crate) struct TimeEventTrigger {
+    pub(crate) item_id: u64,
+    pub(crate) target_time: f64,
+    pub(crate) target_group: u64,
+    pub(crate) multi_activate: bool,
+}
+
+#[derive(Clone, Debug, Default)]
+pub(crate) struct TimeControlTrigger {
+    pub(crate) item_id: u64,
+    pub(crate) stop: bool,
+}
+
+#[derive(Clone, Debug)]
+struct TimerState {
+    running: bool,
+    time_mod: f64,
+    ignore_timewarp: bool,
+    stop_time: Option<f64>,
+    target_group: u64,
+}
+
+#[derive(Clone, Debug)]
+struct TimeEvent {
+    item_id: u64,
+    target_time: f64,
+    target_group: u64,
+    multi_activate: bool,
+}
+
+/// Run state of the timers, the values themselves are stored in [`PickupValues`]
+#[derive(Clone, Default, Resource)]
+pub(crate) struct TimerStates {
+    timers: hashbrown::HashMap<u64, TimerState, U64Hash>,
+    events: Vec<TimeEvent>,
+    /// Value of each timer after the last update, to catch changes made in between
+    values: hashbrown::HashMap<u64, f64, U64Hash>,
+}
+
+pub(crate) fn update_timers(
+    mut commands: Commands,
+    time: Res<Time<Virtual>>,
+    mut timer_states: ResMut<TimerStates>,
+    mut pickup_values: ResMut<PickupValues>,
+    global_groups: Res<GlobalGroups>,
+    global_triggers: Res<GlobalTriggers>,
+    mut trigger_data: ResMut<TriggerData>,
+    players: Query<&Player, Without<DualPlayer>>,
+    group_query: Query<&GlobalGroup>,
+    trigger_query: Query<
+        (
+            Entity,
+            &Trigger,
+            &ObjectGroups,
+            &ObjectColorCalculated,
+            Option<&MultiActivate>,
+        ),
+        (With<SpawnActivate>, Without<Activated>),
+    >,
+) {
+    let Some(player) = players.iter().next() else {
+        return;
+    };
+
+    let level_time = global_triggers.speed_changes.time_for_pos(player.distance);
+
+    let mut to_spawn = Vec::new();
+
+    let TimerStates {
+        timers,
+        events,
+        values,
+    } = &mut *timer_states;
+
+    // Timers with events are checked even when stopped, as item edits and time triggers can
+    // change their value at any time
+    let mut item_ids: Vec<u64> = timers
+        .keys()
+        .chain(events.iter().map(|event| &event.item_id))
+        .copied()
+        .collect();
+    item_ids.sort_unstable();
+    item_ids.dedup();
+
+    for item_id in item_ids {
+        let previous_value = values.get(&item_id).copied().unwrap_or_default();
+        let mut value = pickup_values.get(ItemKind::Timer, item_id);
+
+        if let Some(timer_state) = timers
+            .get_mut(&item_id)
+            .filter(|timer_state| timer_state.running)
+        {
+            let delta = if timer_state.ignore_timewarp {
+                time.delta_seconds_f64() / time.relative_speed_f64().max(f64::EPSILON)
+            } else {
+                time.delta_seconds_f64()
+            };
+
+            value += delta * timer_state.time_mod;
+
+            if let Some(stop_time) = timer_state.stop_time {
+                if crossed(previous_value, value, stop_time) {
+                    value = stop_time;
+                    timer_state.running = false;
+                    to_spawn.push(timer_state.target_group);
+                }
+            }
+
+            pickup_values.set(ItemKind::Timer, item_id, value);
+        }
+
+        values.insert(item_id, value);
+
+        events.retain(|event| {
+            if event.item_id != item_id || !crossed(previous_value, value, event.target_time) {
+                return true;
+            }
+            to_spawn.push(event.target_group);
+            event.multi_activate
+        });
+    }
+
+    for target_group in to_spawn {
+        let Some(group_entity) = global_groups.0.get(target_group as usize) else {
+            continue;
+        };
+
+        let Ok(group) = group_query.get(*group_entity) else {
+            continue;
+        };
+
+        for entity in queue_group_spawn(
+            group,
+            level_time,
+            &global_triggers,
+            &mut trigger_data,
+            &trigger_query,
+        ) {
+            commands.entity(entity).insert(Activated);
+        }
+    }
+}
+
+/// Whether `target` was reached while moving from `previous` to `current` in either direction
+fn crossed(previous: f64, current: f64, target: f64) -> bool {
+    if previous < current {
+        previous < target && target <= current
+    } else if previous > current {
+        current <= target && target < previous
+    } else {
+        false
+    }
+}
+
+type TimeTriggerSystemParam = (ResMut<'static, TimerStates>, ResMut<'static, PickupValues>);
+
+impl TriggerFunction for TimeTrigger {
+    fn execute(
+        &self,
+        world: &mut World,
+        _: Entity,
+        system_state: &mut Box<dyn Any + Send + Sync>,
+        _: f32,
+        progress: f32,
+        _: Range<f32>,
+    ) {
+        if progress != 1. {
+            return;
+        }
+
+        let system_state: &mut SystemState<TimeTriggerSystemParam> =
+            system_state.downcast_mut().unwrap();
+
+        let (mut timer_states, mut pickup_values) = system_state.get_mut(world);
+
+        if !self.dont_override || !timer_states.timers.contains_key(&self.item_id) {
+            pickup_values.set(ItemKind::Timer, self.item_id, self.start_time);
+        }
+
+        timer_states.timers.insert(
+            self.item_id,
+            TimerState {
+                running: !self.start_paused,
+                time_mod: self.time_mod,
+                ignore_timewarp: self.ignore_timewarp,
+                stop_time: self.stop_time,
+                target_group: self.target_group,
+            },
+        );
+    }
+
+    fn create_system_state(&self, world: &mut World) -> Box<dyn Any + Send + Sync> {
+        Box::new(SystemState::<TimeTriggerSystemParam>::new(world))
+    }
+
+    fn target_id(&self) -> u64 {
+        self.item_id
+    }
+
+    fn duration(&self) -> f32 {
+        0.
+    }
+
+    fn exclusive(&self) -> bool {
+        false
+    }
+
+    fn post(&self) -> bool {
+        false
+    }
+
+    fn remap(&mut self, remap: &GroupRemap) {
+        self.target_group = remap.get(self.target_group);
+    }
+}
+
+type TimeEventTriggerSystemParam = ResMut<'static, TimerStates>;
+
+impl TriggerFunction for TimeEventTrigger {
+    fn execute(
+        &self,
+        world: &mut World,
+        _: Entity,
+        system_state: &mut Box<dyn Any + Send + Sync>,
+        _: f32,
+        progress: f32,
+        _: Range<f32>,
+    ) {
+        if progress != 1. {
+            return;
+        }
+
+        let system_state: &mut SystemState<TimeEventTriggerSystemParam> =
+            system_state.downcast_mut().unwrap();
+
+        let mut timer_states = system_state.get_mut(world);
+
+        timer_states.events.push(TimeEvent {
+            item_id: self.item_id,
+            target_time: self.target_time,
+            target_group: self.target_group,
+            multi_activate: self.multi_activate,
+        });
+    }
+
+    fn create_system_state(&self, world: &mut World) -> Box<dyn Any + Send + Sync> {
+        Box::new(SystemState::<TimeEventTriggerSystemParam>::new(world))
+    }
+
+    fn target_id(&self) -> u64 {
+        self.item_id
+    }
+
+    fn duration(&self) -> f32 {
+        0.
+    }
+
+    fn exclusive(&self) -> bool {
+        false
+    }
+
+    fn post(&self) -> bool {
+        false
+    }
+
+    fn remap(&mut self, remap: &GroupRemap) {
+        self.target_group = remap.get(self.target_group);
+    }
+}
+
+type TimeControlTriggerSystemParam = ResMut<'static, TimerStates>;
+
+impl TriggerFunction for TimeControlTrigger {
+    fn execute(
+        &self,
+        world: &mut World,
+        _: Entity,
+        system_state: &mut Box<dyn Any + Send + Sync>,
+        _: f32,
+        progress: f32,
+        _: Range<f32>,
+    ) {
+        if progress != 1. {
+            return;
+        }
+
+        let system_state: &mut SystemState<TimeControlTriggerSystemParam> =
+            system_state.downcast_mut().unwrap();
+
+        let mut timer_states = system_state.get_mut(world);
+
+        // Starting a timer that was never set up by a time trigger counts up from its current value
+        let timer_state = timer_states
+            .timers
+            .entry(self.item_id)
+            .or_insert_with(|| TimerState {
+                running: false,
+                time_mod: 1.,
+                ignore_timewarp: false,
+                stop_time: None,
+                target_group: 0,
+            });
+
+        timer_state.running = !self.stop;
+    }
+
+    fn create_system_state(&self, world: &mut World) -> Box<dyn Any + Send + Sync> {
+        Box::new(SystemState::<TimeControlTriggerSystemParam>::new(world))
+    }
+
+    fn target_id(&self) -> u64 {
+        self.item_id
+    }
+
+    fn duration(&self) -> f32 {
+        0.
+    }
+
+    fn exclusive(&self) -> bool {
+        false
+    }
+
+    fn post(&self) -> bool {
+        false
+    }
+}
@@ -30,9 +30,12 @@ use steamlocate::SteamDir;
 use winit::window::Icon;
 
 use crate::asset::AssetPlugin;
+use crate::level::bot::autoplay_command;
 use crate::level::section::GlobalSections;
+use crate::level::trigger::UnsupportedTriggers;
 use crate::level::LevelWorld;
 use crate::render::RenderPlugins;
+use crate::state::startup::ResourcesDir;
 use crate::state::StatePlugin;
 
 mod api;
@@ -43,6 +46,16 @@ mod state;
 mod utils;
 
 fn main() {
+    // Levels can be autoplayed in bulk without opening a window
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    if args.first().is_some_and(|arg| arg == "--autoplay") {
+        if let Err(err) = autoplay_command(&args[1..]) {
+            eprintln!("{}", err);
+            std::process::exit(1);
+        }
+        return;
+    }
+
     let mut app = App::new();
 
     setup_asset_dirs(&mut app);
@@ -210,12 +223,16 @@ fn setup_asset_dirs(app: &mut App) {
         )
         .unwrap();
 
+    let resources_path = path_config.gd_path + "/Resources";
+
+    app.insert_resource(ResourcesDir(PathBuf::from(&resources_path)));
+
     let mut sources = app
         .world
         .get_resource_or_insert_with::<AssetSourceBuilders>(default);
     sources.insert(
         "resources",
-        AssetSourceBuilder::platform_default(&(path_config.gd_path + "/Resources"), None),
+        AssetSourceBuilder::platform_default(&resources_path, None),
     );
     sources.insert(
         "data",
@@ -311,6 +328,21 @@ fn setup(
                     ..default()
                 },
             ),
+            TextSection::new("\n", TextStyle::default()),
+            TextSection::new(
+                "Unsupported triggers: ",
+                TextStyle {
+                    font_size: 15.,
+                    ..default()
+                },
+            ),
+            TextSection::new(
+                "0",
+                TextStyle {
+                    font_size: 15.,
+                    ..default()
+                },
+            ),
         ]))
         .insert(InfoText)
         .id();
@@ -356,6 +388,23 @@ fn update_info(
     }
 
     text.sections[7].value = active_objects.to_string();
+
+    let unsupported_triggers = world.resource::<UnsupportedTriggers>();
+
+    let total: usize = unsupported_triggers.0.values().sum();
+
+    let mut unsupported = total.to_string();
+
+    if total != 0 {
+        let by_id: Vec<String> = unsupported_triggers
+            .0
+            .iter()
+            .map(|(id, count)| format!("{}: {}", id, count))
+            .collect();
+        unsupported += &format!(" ({})", by_id.join(", "));
+    }
+
+    text.sections[10].value = unsupported;
 }
 
 fn update_scale_factor(
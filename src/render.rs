@@ -2,9 +2,11 @@ use bevy::app::{PluginGroup, PluginGroupBuilder};
 
 use crate::render::object::ObjectRenderPlugin;
 use crate::render::remove_srgb::RemoveSrgbPlugin;
+use crate::render::shader::ShaderEffectsPlugin;
 
 mod object;
 mod remove_srgb;
+mod shader;
 
 pub(crate) struct RenderPlugins;
 
@@ -12,7 +14,11 @@ impl PluginGroup for RenderPlugins {
     fn build(self) -> PluginGroupBuilder {
         let mut group = PluginGroupBuilder::start::<Self>();
 
-        group = group.add(ObjectRenderPlugin).add(RemoveSrgbPlugin);
+        group = group
+            .add(ObjectRenderPlugin)
+            .add(RemoveSrgbPlugin)
+            // Needs the remove srgb node to already be in the graph
+            .add(ShaderEffectsPlugin);
 
         group
     }
@@ -44,10 +44,11 @@ use bevy::render::{
     Extract, ExtractSchedule, Render, RenderApp, RenderSet,
 };
 use bevy::tasks::ComputeTaskPool;
-use bevy::utils::{syncunsafecell::SyncUnsafeCell, FloatOrd};
+use bevy::utils::{default, syncunsafecell::SyncUnsafeCell, FloatOrd};
 
 use crate::level::color::{HsvMod, ObjectColor, ObjectColorCalculated, ObjectColorKind};
 use crate::level::transform::GlobalTransform2d;
+use crate::level::trigger::gradient::{GlobalGradients, GradientQuad};
 use crate::level::trigger::Trigger;
 use crate::level::{object::Object, section::GlobalSections, LevelWorld};
 use crate::state::level::Options;
@@ -318,6 +319,15 @@ pub struct ExtractedObject {
     anchor: Vec2,
     rotated: bool,
     entity: Entity,
+    gradient: Option<ExtractedGradient>,
+}
+
+#[derive(Copy, Clone)]
+struct ExtractedGradient {
+    /// Offset of the top right corner from where the transform would put it
+    corner_offset: Vec2,
+    /// Packed RGBA8 colors of the bottom left, bottom right, top left and top right corners
+    colors: [u32; 4],
 }
 
 impl Default for ExtractedObject {
@@ -334,6 +344,7 @@ impl Default for ExtractedObject {
             anchor: Vec2::default(),
             rotated: false,
             entity: Entity::PLACEHOLDER,
+            gradient: None,
         }
     }
 }
@@ -348,6 +359,7 @@ pub(crate) struct ExtractSystemStateCache {
     cached_system_state: Option<
         SystemState<(
             Res<'static, GlobalSections>,
+            Res<'static, GlobalGradients>,
             Query<
                 'static,
                 'static,
@@ -402,6 +414,7 @@ pub(crate) fn extract_objects(
 
             let system_state: SystemState<(
                 Res<GlobalSections>,
+                Res<GlobalGradients>,
                 Query<(
                     Entity,
                     &GlobalTransform2d,
@@ -422,7 +435,7 @@ pub(crate) fn extract_objects(
         }
     };
 
-    let (global_sections, objects) = system_state.get(world);
+    let (global_sections, global_gradients, objects) = system_state.get(world);
 
     let visible_sections = &global_sections.sections[global_sections.visible.clone()];
 
@@ -432,6 +445,13 @@ pub(crate) fn extract_objects(
         .get_mut()
         .resize(total, ExtractedObject::default());
 
+    for gradient in &global_gradients.quads {
+        extracted_objects
+            .objects
+            .get_mut()
+            .push(ExtractedObject::from(gradient));
+    }
+
     let task_pool = ComputeTaskPool::get();
     let chunk_size = (global_sections.visible.len() / task_pool.thread_num()).max(1);
 
@@ -493,6 +513,7 @@ pub(crate) fn extract_objects(
                         anchor: object.frame.anchor + object.anchor,
                         rotated: object.frame.rotated,
                         entity,
+                        gradient: None,
                     };
                 }
 
@@ -512,6 +533,46 @@ pub(crate) fn extract_objects(
     });
 }
 
+impl From<&GradientQuad> for ExtractedObject {
+    fn from(gradient: &GradientQuad) -> Self {
+        let [bottom_left, bottom_right, top_left, top_right] = gradient.corners;
+
+        let affine = Affine2::from_cols(
+            bottom_right - bottom_left,
+            top_left - bottom_left,
+            bottom_left,
+        );
+
+        // Blending layers sit one below the odd layers, same as regular objects
+        let z_layer = gradient.z_layer
+            - if gradient.additive ^ (gradient.z_layer % 2 == 0) {
+                1
+            } else {
+                0
+            };
+
+        ExtractedObject {
+            enabled: true,
+            z_layer,
+            transform: GlobalTransform2d::from_affine(affine, gradient.z_order),
+            color: Vec4::ONE,
+            blending: gradient.additive,
+            gradient: Some(ExtractedGradient {
+                corner_offset: top_right - (bottom_right + top_left - bottom_left),
+                colors: gradient.colors.map(|color| {
+                    u32::from_le_bytes(
+                        color
+                            .clamp(Vec4::ZERO, Vec4::ONE)
+                            .to_array()
+                            .map(|channel| (channel * u8::MAX as f32).round() as u8),
+                    )
+                }),
+            }),
+            ..default()
+        }
+    }
+}
+
 #[repr(C)]
 #[derive(Copy, Clone, Default, Pod, Zeroable)]
 struct ObjectInstance {
@@ -560,6 +621,7 @@ impl ObjectInstance {
 const FLAGS_BLENDING: u32 = 1 << 0;
 const FLAGS_HSV_S_ABSOLUTE: u32 = 1 << 1;
 const FLAGS_HSV_V_ABSOLUTE: u32 = 1 << 2;
+const FLAGS_GRADIENT: u32 = 1 << 3;
 
 impl From<HsvMod> for ([f32; 3], u32) {
     fn from(hsv: HsvMod) -> Self {
@@ -702,6 +764,32 @@ pub(crate) fn prepare_objects(
                         continue;
                     }
 
+                    // Gradients are untextured so they can join whatever batch they are in
+                    if let Some(gradient) = extracted_object.gradient {
+                        let transform = extracted_object.transform.affine();
+
+                        let mut i_flags = FLAGS_GRADIENT;
+                        if extracted_object.blending {
+                            i_flags |= FLAGS_BLENDING;
+                        }
+
+                        *buffer_entry = ObjectInstance {
+                            i_model: [
+                                transform.matrix2.x_axis,
+                                transform.matrix2.y_axis,
+                                transform.translation.xy(),
+                            ],
+                            i_color: extracted_object.color.to_array(),
+                            i_uv_offset_scale: gradient.colors.map(f32::from_bits),
+                            i_texture_index: 0,
+                            i_hsv: [gradient.corner_offset.x, gradient.corner_offset.y, 0.],
+                            i_flags,
+                        };
+
+                        batch_range.end += 1;
+                        continue;
+                    }
+
                     let (image_group_index, texture_index, current_image_size) = match images
                         .iter()
                         .position(|(asset_id, _, _, _)| {
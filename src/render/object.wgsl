@@ -33,6 +33,8 @@ struct VertexOutput {
 #ifndef NO_TEXTURE_ARRAY
     @location(2) texture_index: u32,
 #endif
+    @location(3) gradient_color: vec4<f32>,
+    @location(4) @interpolate(flat) gradient: u32,
 };
 
 // From https://github.com/lolengine/lolengine/blob/3c26/doc/legacy/front_camera_sprite.lolfx#L56
@@ -62,12 +64,18 @@ fn vertex(in: VertexInput) -> VertexOutput {
         f32((in.index & 2) >> 1),
     );
 
-    out.clip_position = vec4<f32>(vertex_position, 0.0, 1.0)
+    // Flag: gradient
+    out.gradient = (in.i_flags >> 3) & 1;
+
+    // Gradients store the offset of their top right corner in the hsv slot
+    let corner_offset = vec2<f32>(in.i_hsv.xy) * f32(out.gradient * u32(in.index == 3));
+
+    out.clip_position = (vec4<f32>(vertex_position, 0.0, 1.0)
      * transpose(affine2_to_square(mat3x2<f32>(
         in.i_model_row0,
         in.i_model_row1,
         in.i_model_row2,
-    ))) * transpose(view.view_proj);
+    ))) + vec4<f32>(corner_offset, 0.0, 0.0)) * transpose(view.view_proj);
 
     out.uv = vertex_position * in.i_uv_offset_scale.zw + in.i_uv_offset_scale.xy;
 
@@ -103,6 +111,13 @@ fn vertex(in: VertexInput) -> VertexOutput {
     out.texture_index = in.i_texture_index;
 #endif
 
+    // Gradients store a packed color for each corner in the uv slot
+    let gradient_color = unpack4x8unorm(bitcast<vec4<u32>>(in.i_uv_offset_scale)[in.index]);
+    out.gradient_color = vec4<f32>(
+        gradient_color.rgb * mix(gradient_color.a, gradient_color.a * gradient_color.a, blending),
+        mix(gradient_color.a, 0.0, blending),
+    );
+
     return out;
 }
 
@@ -122,7 +137,7 @@ fn fragment(in: VertexOutput) -> @location(0) vec4<f32> {
     var color = textureSample(sprite_texture, sprite_sampler, in.uv);
 #endif
 
-    color = in.color * color;
+    color = select(in.color * color, in.gradient_color, in.gradient == 1);
 
     return color;
 }
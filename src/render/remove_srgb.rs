@@ -77,7 +77,7 @@ impl Plugin for RemoveSrgbPlugin {
 }
 
 #[derive(Debug, Hash, PartialEq, Eq, Clone, RenderLabel)]
-struct RemoveSrgbLabel;
+pub(crate) struct RemoveSrgbLabel;
 
 /// The post process node used for the render graph
 #[derive(Default)]
@@ -0,0 +1,303 @@
+use bevy::app::{App, Plugin};
+use bevy::asset::{load_internal_asset, Handle};
+use bevy::core_pipeline::core_2d::graph::{Core2d, Node2d};
+use bevy::core_pipeline::fullscreen_vertex_shader::fullscreen_shader_vertex_state;
+use bevy::ecs::query::QueryItem;
+use bevy::math::{Vec2, Vec4, Vec4Swizzles};
+use bevy::prelude::{
+    Commands, Component, Entity, FromWorld, IntoSystemConfigs, Query, Res, ResMut, Resource,
+    Shader, World,
+};
+use bevy::render::render_graph::RenderLabel;
+use bevy::render::render_resource::binding_types::{sampler, texture_2d, uniform_buffer};
+use bevy::render::render_resource::{BindGroupLayoutEntries, ShaderType, UniformBuffer};
+use bevy::render::{
+    render_graph::{NodeRunError, RenderGraphApp, RenderGraphContext, ViewNode, ViewNodeRunner},
+    render_resource::{
+        BindGroupEntries, BindGroupLayout, CachedRenderPipelineId, ColorTargetState, ColorWrites,
+        FragmentState, MultisampleState, Operations, PipelineCache, PrimitiveState,
+        RenderPassColorAttachment, RenderPassDescriptor, RenderPipelineDescriptor, Sampler,
+        SamplerBindingType, SamplerDescriptor, ShaderStages, TextureFormat, TextureSampleType,
+    },
+    renderer::{RenderContext, RenderDevice, RenderQueue},
+    texture::BevyDefault,
+    view::{ExtractedView, ViewTarget},
+    Extract, ExtractSchedule, Render, RenderApp, RenderSet,
+};
+
+use crate::level::trigger::shader::{ShaderEffect, ShaderState, SHADER_EFFECT_COUNT};
+use crate::level::LevelWorld;
+use crate::render::remove_srgb::RemoveSrgbLabel;
+
+pub const SHADER_EFFECTS_SHADER_HANDLE: Handle<Shader> =
+    Handle::weak_from_u128(39572018465729103846);
+
+/// Entry points of the shader, run in this order
+const PASSES: [&str; 3] = ["distort", "blur", "adjust_color"];
+
+pub(crate) struct ShaderEffectsPlugin;
+
+impl Plugin for ShaderEffectsPlugin {
+    fn build(&self, app: &mut App) {
+        load_internal_asset!(
+            app,
+            SHADER_EFFECTS_SHADER_HANDLE,
+            "shader.wgsl",
+            Shader::from_wgsl
+        );
+
+        let Ok(render_app) = app.get_sub_app_mut(RenderApp) else {
+            return;
+        };
+
+        render_app
+            .init_resource::<ExtractedShaderState>()
+            .add_systems(ExtractSchedule, extract_shader_state)
+            .add_systems(Render, prepare_shader_effects.in_set(RenderSet::Prepare))
+            .add_render_graph_node::<ViewNodeRunner<ShaderEffectsNode>>(Core2d, ShaderEffectsLabel)
+            .add_render_graph_edges(
+                Core2d,
+                // Effects are applied before the colors get converted, like the objects themselves
+                (Node2d::Tonemapping, ShaderEffectsLabel, RemoveSrgbLabel),
+            );
+    }
+
+    fn finish(&self, app: &mut App) {
+        let Ok(render_app) = app.get_sub_app_mut(RenderApp) else {
+            return;
+        };
+
+        render_app.init_resource::<ShaderEffectsPipeline>();
+    }
+}
+
+#[derive(Debug, Hash, PartialEq, Eq, Clone, RenderLabel)]
+struct ShaderEffectsLabel;
+
+#[derive(Default, Resource)]
+struct ExtractedShaderState(Option<ShaderState>);
+
+fn extract_shader_state(
+    mut extracted_shader_state: ResMut<ExtractedShaderState>,
+    level_world: Extract<Option<Res<LevelWorld>>>,
+) {
+    extracted_shader_state.0 = match level_world.as_deref() {
+        Some(LevelWorld::World(world)) => world.get_resource::<ShaderState>().cloned(),
+        _ => None,
+    };
+}
+
+#[derive(Clone, Default, ShaderType)]
+struct ShaderEffectsUniform {
+    params: [Vec4; SHADER_EFFECT_COUNT],
+    /// Centers of the effects in pixels
+    centers: [Vec4; SHADER_EFFECT_COUNT],
+    resolution: Vec2,
+    pixels_per_unit: f32,
+    time: f32,
+}
+
+#[derive(Component)]
+struct ViewShaderEffects {
+    uniform: UniformBuffer<ShaderEffectsUniform>,
+    /// Bitmask of the passes that have at least one active effect
+    passes: u32,
+}
+
+fn pass_for_effect(effect: ShaderEffect) -> u32 {
+    match effect {
+        ShaderEffect::RadialBlur | ShaderEffect::MotionBlur => 1,
+        ShaderEffect::LensCircle
+        | ShaderEffect::GrayScale
+        | ShaderEffect::Sepia
+        | ShaderEffect::InvertColor
+        | ShaderEffect::Hue
+        | ShaderEffect::EditColor => 2,
+        _ => 0,
+    }
+}
+
+fn prepare_shader_effects(
+    mut commands: Commands,
+    render_device: Res<RenderDevice>,
+    render_queue: Res<RenderQueue>,
+    extracted_shader_state: Res<ExtractedShaderState>,
+    views: Query<(Entity, &ExtractedView)>,
+) {
+    let Some(shader_state) = &extracted_shader_state.0 else {
+        return;
+    };
+
+    let mut passes = 0;
+
+    for (index, layer) in shader_state.layers.iter().enumerate() {
+        let effect = ShaderEffect::from_index(index);
+        if effect.is_active(layer.params) {
+            passes |= 1 << pass_for_effect(effect);
+        }
+    }
+
+    if passes == 0 {
+        return;
+    }
+
+    for (entity, view) in &views {
+        let resolution = view.viewport.zw().as_vec2();
+        let view_proj = view.projection * view.transform.compute_matrix().inverse();
+
+        let mut uniform = ShaderEffectsUniform {
+            resolution,
+            // The projection is orthographic, so the scale is the same everywhere on screen
+            pixels_per_unit: view.projection.x_axis.x * resolution.x / 2.,
+            time: shader_state.time,
+            ..Default::default()
+        };
+
+        for ((layer, params), center) in shader_state
+            .layers
+            .iter()
+            .zip(&mut uniform.params)
+            .zip(&mut uniform.centers)
+        {
+            *params = layer.params;
+
+            let pixel = match layer.center_position {
+                Some(position) => {
+                    let clip = view_proj * position.extend(0.).extend(1.);
+                    let ndc = clip.xy() / clip.w;
+                    Vec2::new(ndc.x + 1., 1. - ndc.y) / 2. * resolution
+                }
+                None => resolution / 2.,
+            };
+
+            *center = pixel.extend(0.).extend(0.);
+        }
+
+        let mut uniform = UniformBuffer::from(uniform);
+        uniform.write_buffer(&render_device, &render_queue);
+
+        commands
+            .entity(entity)
+            .insert(ViewShaderEffects { uniform, passes });
+    }
+}
+
+#[derive(Default)]
+struct ShaderEffectsNode;
+
+impl ViewNode for ShaderEffectsNode {
+    type ViewQuery = (&'static ViewTarget, &'static ViewShaderEffects);
+
+    fn run(
+        &self,
+        _: &mut RenderGraphContext,
+        render_context: &mut RenderContext,
+        (view_target, shader_effects): QueryItem<Self::ViewQuery>,
+        world: &World,
+    ) -> Result<(), NodeRunError> {
+        let shader_effects_pipeline = world.resource::<ShaderEffectsPipeline>();
+        let pipeline_cache = world.resource::<PipelineCache>();
+
+        let Some(uniform) = shader_effects.uniform.binding() else {
+            return Ok(());
+        };
+
+        for (pass, pipeline_id) in shader_effects_pipeline.pipeline_ids.iter().enumerate() {
+            if shader_effects.passes & (1 << pass) == 0 {
+                continue;
+            }
+
+            let Some(pipeline) = pipeline_cache.get_render_pipeline(*pipeline_id) else {
+                continue;
+            };
+
+            // Every pass flips the main texture, so the bind group has to be created per pass
+            let post_process = view_target.post_process_write();
+
+            let bind_group = render_context.render_device().create_bind_group(
+                "shader_effects_bind_group",
+                &shader_effects_pipeline.layout,
+                &BindGroupEntries::sequential((
+                    post_process.source,
+                    &shader_effects_pipeline.sampler,
+                    uniform.clone(),
+                )),
+            );
+
+            let mut render_pass = render_context.begin_tracked_render_pass(RenderPassDescriptor {
+                label: Some(PASSES[pass]),
+                color_attachments: &[Some(RenderPassColorAttachment {
+                    view: post_process.destination,
+                    resolve_target: None,
+                    ops: Operations::default(),
+                })],
+                depth_stencil_attachment: None,
+                timestamp_writes: None,
+                occlusion_query_set: None,
+            });
+
+            render_pass.set_render_pipeline(pipeline);
+            render_pass.set_bind_group(0, &bind_group, &[]);
+            render_pass.draw(0..3, 0..1);
+        }
+
+        Ok(())
+    }
+}
+
+#[derive(Resource)]
+struct ShaderEffectsPipeline {
+    layout: BindGroupLayout,
+    sampler: Sampler,
+    pipeline_ids: [CachedRenderPipelineId; PASSES.len()],
+}
+
+impl FromWorld for ShaderEffectsPipeline {
+    fn from_world(world: &mut World) -> Self {
+        let render_device = world.resource::<RenderDevice>();
+
+        let layout = render_device.create_bind_group_layout(
+            "shader_effects_bind_group_layout",
+            &BindGroupLayoutEntries::sequential(
+                ShaderStages::FRAGMENT,
+                (
+                    texture_2d(TextureSampleType::Float { filterable: true }),
+                    sampler(SamplerBindingType::Filtering),
+                    uniform_buffer::<ShaderEffectsUniform>(false),
+                ),
+            ),
+        );
+
+        let sampler = render_device.create_sampler(&SamplerDescriptor::default());
+
+        let pipeline_cache = world.resource_mut::<PipelineCache>();
+
+        let pipeline_ids = PASSES.map(|entry_point| {
+            pipeline_cache.queue_render_pipeline(RenderPipelineDescriptor {
+                label: Some(format!("shader_effects_{}_pipeline", entry_point).into()),
+                layout: vec![layout.clone()],
+                vertex: fullscreen_shader_vertex_state(),
+                fragment: Some(FragmentState {
+                    shader: SHADER_EFFECTS_SHADER_HANDLE,
+                    shader_defs: vec![],
+                    entry_point: entry_point.into(),
+                    targets: vec![Some(ColorTargetState {
+                        format: TextureFormat::bevy_default(),
+                        blend: None,
+                        write_mask: ColorWrites::ALL,
+                    })],
+                }),
+                primitive: PrimitiveState::default(),
+                depth_stencil: None,
+                multisample: MultisampleState::default(),
+                push_constant_ranges: vec![],
+            })
+        });
+
+        Self {
+            layout,
+            sampler,
+            pipeline_ids,
+        }
+    }
+}
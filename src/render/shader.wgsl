@@ -0,0 +1,194 @@
+#import bevy_core_pipeline::fullscreen_vertex_shader::FullscreenVertexOutput
+
+// Indices of the effects, matching `ShaderEffect`
+const SHOCKWAVE: u32 = 0u;
+const SHOCKLINE: u32 = 1u;
+const GLITCH: u32 = 2u;
+const CHROMATIC: u32 = 3u;
+const CHROMATIC_GLITCH: u32 = 4u;
+const PIXELATE: u32 = 5u;
+const LENS_CIRCLE: u32 = 6u;
+const RADIAL_BLUR: u32 = 7u;
+const MOTION_BLUR: u32 = 8u;
+const BULGE: u32 = 9u;
+const PINCH: u32 = 10u;
+const GRAY_SCALE: u32 = 11u;
+const SEPIA: u32 = 12u;
+const INVERT_COLOR: u32 = 13u;
+const HUE: u32 = 14u;
+const EDIT_COLOR: u32 = 15u;
+const SPLIT_SCREEN: u32 = 16u;
+
+const BLUR_SAMPLES: i32 = 16;
+
+struct ShaderEffects {
+    params: array<vec4<f32>, 17>,
+    centers: array<vec4<f32>, 17>,
+    resolution: vec2<f32>,
+    pixels_per_unit: f32,
+    time: f32,
+}
+
+@group(0) @binding(0)
+var screen_texture: texture_2d<f32>;
+@group(0) @binding(1)
+var texture_sampler: sampler;
+@group(0) @binding(2)
+var<uniform> effects: ShaderEffects;
+
+fn hash(x: f32) -> f32 {
+    return fract(sin(x * 12.9898) * 43758.5453);
+}
+
+fn sample_pixel(pixel: vec2<f32>) -> vec4<f32> {
+    return textureSample(screen_texture, texture_sampler, pixel / effects.resolution);
+}
+
+// Moves the pixel towards or away from the center of an effect within its radius
+fn radial_distort(pixel: vec2<f32>, center: vec2<f32>, radius: f32, strength: vec2<f32>) -> vec2<f32> {
+    let offset = pixel - center;
+    let distance = length(offset);
+    if radius <= 0.0 || distance >= radius {
+        return pixel;
+    }
+    let t = max(distance / radius, 1e-4);
+    return center + offset * pow(vec2<f32>(t), strength);
+}
+
+fn wave_distort(distance: f32, radius: f32, thickness: f32, strength: f32) -> f32 {
+    let from_wave = distance - radius;
+    if thickness <= 0.0 || abs(from_wave) >= thickness {
+        return 0.0;
+    }
+    return sin(from_wave / thickness * 3.14159265) * (1.0 - abs(from_wave) / thickness) * strength * thickness;
+}
+
+@fragment
+fn distort(in: FullscreenVertexOutput) -> @location(0) vec4<f32> {
+    let unit = effects.pixels_per_unit;
+    var pixel = in.uv * effects.resolution;
+
+    // Split screen
+    let split = max(round(effects.params[SPLIT_SCREEN].xy), vec2<f32>(1.0));
+    pixel = fract(pixel / effects.resolution * split) * effects.resolution;
+
+    // Pixelate
+    let pixel_size = max(effects.params[PIXELATE].xy, vec2<f32>(1.0));
+    pixel = (floor(pixel / pixel_size) + 0.5) * pixel_size;
+
+    // Shockwave
+    let shockwave = effects.params[SHOCKWAVE];
+    let shockwave_offset = pixel - effects.centers[SHOCKWAVE].xy;
+    let shockwave_distance = length(shockwave_offset);
+    if shockwave_distance > 0.0 {
+        pixel -= shockwave_offset / shockwave_distance
+            * wave_distort(shockwave_distance, shockwave.x * unit, shockwave.y * unit, shockwave.z);
+    }
+
+    // Shockline
+    let shockline = effects.params[SHOCKLINE];
+    let shockline_axis = select(vec2<f32>(1.0, 0.0), vec2<f32>(0.0, 1.0), shockline.w != 0.0);
+    let shockline_distance = abs(dot(pixel - effects.centers[SHOCKLINE].xy, shockline_axis));
+    pixel -= shockline_axis * wave_distort(shockline_distance, shockline.x * unit, shockline.y * unit, shockline.z);
+
+    // Bulge
+    let bulge = effects.params[BULGE];
+    pixel = radial_distort(pixel, effects.centers[BULGE].xy, bulge.x * unit, vec2<f32>(bulge.y));
+
+    // Pinch
+    let pinch = effects.params[PINCH];
+    pixel = radial_distort(pixel, effects.centers[PINCH].xy, pinch.x * unit, -pinch.yz);
+
+    // Glitch shifts random horizontal slices of the screen
+    let glitch = effects.params[GLITCH];
+    if glitch.x != 0.0 {
+        let slice = floor(pixel.y / max(glitch.z, 1.0));
+        let frame = floor(effects.time * glitch.y * 10.0);
+        if hash(slice + frame * 0.37) > 0.8 {
+            pixel.x += (hash(slice * 1.7 + frame) - 0.5) * glitch.x * 30.0 * unit;
+        }
+    }
+
+    // Chromatic aberration, optionally jittered per line by the chromatic glitch
+    var aberration = effects.params[CHROMATIC].xy * unit;
+    let chromatic_glitch = effects.params[CHROMATIC_GLITCH];
+    if chromatic_glitch.x != 0.0 {
+        let line = floor(pixel.y / max(chromatic_glitch.z, 1.0));
+        let frame = floor(effects.time * chromatic_glitch.y * 10.0);
+        aberration.x += (hash(line + frame * 0.53) - 0.5) * chromatic_glitch.x * 10.0 * unit;
+    }
+
+    let color = sample_pixel(pixel);
+    let red = sample_pixel(pixel + aberration).r;
+    let blue = sample_pixel(pixel - aberration).b;
+
+    return vec4<f32>(red, color.g, blue, color.a);
+}
+
+@fragment
+fn blur(in: FullscreenVertexOutput) -> @location(0) vec4<f32> {
+    let unit = effects.pixels_per_unit;
+    let pixel = in.uv * effects.resolution;
+
+    // Radial blur pulls samples towards its center, fading out at the edge of its size
+    let radial_blur = effects.params[RADIAL_BLUR];
+    let radial_offset = effects.centers[RADIAL_BLUR].xy - pixel;
+    var radial_amount = radial_blur.y * 0.1;
+    if radial_blur.x > 0.0 {
+        radial_amount *= clamp(length(radial_offset) / (radial_blur.x * unit), 0.0, 1.0);
+    }
+
+    let motion_blur = effects.params[MOTION_BLUR].xy * unit;
+
+    var color = vec4<f32>(0.0);
+    for (var i = 0; i < BLUR_SAMPLES; i++) {
+        let t = f32(i) / f32(BLUR_SAMPLES - 1);
+        color += sample_pixel(pixel + radial_offset * radial_amount * t + motion_blur * (t - 0.5));
+    }
+
+    return color / f32(BLUR_SAMPLES);
+}
+
+// Rotation around the gray axis
+fn rotate_hue(color: vec3<f32>, degrees: f32) -> vec3<f32> {
+    let k = vec3<f32>(0.57735);
+    let angle = radians(degrees);
+    let cos_angle = cos(angle);
+    return color * cos_angle + cross(k, color) * sin(angle) + k * dot(k, color) * (1.0 - cos_angle);
+}
+
+@fragment
+fn adjust_color(in: FullscreenVertexOutput) -> @location(0) vec4<f32> {
+    let unit = effects.pixels_per_unit;
+    let pixel = in.uv * effects.resolution;
+
+    let color = sample_pixel(pixel);
+    var rgb = color.rgb;
+
+    let luma = dot(rgb, vec3<f32>(0.299, 0.587, 0.114));
+    rgb = mix(rgb, vec3<f32>(luma), effects.params[GRAY_SCALE].x);
+
+    let sepia = vec3<f32>(
+        dot(rgb, vec3<f32>(0.393, 0.769, 0.189)),
+        dot(rgb, vec3<f32>(0.349, 0.686, 0.168)),
+        dot(rgb, vec3<f32>(0.272, 0.534, 0.131)),
+    );
+    rgb = mix(rgb, min(sepia, vec3<f32>(1.0)), effects.params[SEPIA].x);
+
+    rgb = mix(rgb, vec3<f32>(1.0) - rgb, effects.params[INVERT_COLOR].x);
+
+    rgb = clamp(rotate_hue(rgb, effects.params[HUE].x), vec3<f32>(0.0), vec3<f32>(1.0));
+
+    rgb *= effects.params[EDIT_COLOR].rgb;
+
+    // Lens circle darkens everything outside of its radius
+    let lens_circle = effects.params[LENS_CIRCLE];
+    if lens_circle.y != 0.0 {
+        let radius = lens_circle.x * unit;
+        let distance = length(pixel - effects.centers[LENS_CIRCLE].xy);
+        let fade = smoothstep(radius, radius + 30.0 * unit, distance) * clamp(lens_circle.y, 0.0, 1.0);
+        rgb *= 1.0 - fade;
+    }
+
+    return vec4<f32>(rgb, color.a);
+}
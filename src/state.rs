@@ -9,7 +9,7 @@ use crate::state::startup::StartupStatePlugin;
 pub(crate) mod level;
 mod menu;
 mod prepare;
-mod startup;
+pub(crate) mod startup;
 
 #[derive(Debug, Clone, Copy, Default, Eq, PartialEq, Hash, States)]
 enum GameState {
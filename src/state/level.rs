@@ -3,33 +3,54 @@ use std::time::Duration;
 use bevy::app::{
     App, First, Last, MainScheduleOrder, Plugin, PostUpdate, PreUpdate, RunFixedMainLoop, Update,
 };
-use bevy::asset::{Assets, Handle};
+use bevy::asset::{AssetServer, Assets, Handle};
 use bevy::ecs::schedule::{ExecutorKind, ScheduleLabel};
 use bevy::hierarchy::{DespawnRecursiveExt, Parent};
 use bevy::input::mouse::{MouseMotion, MouseScrollUnit, MouseWheel};
 use bevy::input::ButtonInput;
-use bevy::math::{Vec2, Vec3, Vec3Swizzles, Vec4Swizzles};
+use bevy::log::{error, info, warn};
+use bevy::math::{Quat, Vec2, Vec3, Vec3Swizzles, Vec4Swizzles};
 use bevy::prelude::{
     in_state, Camera, ClearColor, Color, Commands, Component, Entity, EventReader,
     GizmoPrimitive2d, Gizmos, GlobalTransform, IntoSystemConfigs, KeyCode, MouseButton, Mut,
     NextState, OnEnter, OnExit, OrthographicProjection, Query, Res, ResMut, Resource, Schedule,
-    Transform, With, Without,
+    Transform, With, Without, World,
 };
 use bevy::time::{Time, Virtual};
 use bevy_egui::EguiContexts;
-use bevy_kira_audio::{AudioInstance, AudioTween, PlaybackState};
+use bevy_kira_audio::{Audio, AudioInstance, AudioTween, PlaybackState};
+use native_dialog::FileDialog;
 
+use crate::asset::cocos2d_atlas::Cocos2dFrames;
+use crate::level::bot::{Autoplay, AutoplaySettings};
+use crate::level::checkpoint::Checkpoints;
+use crate::level::collision::Hitbox;
 use crate::level::color::{ColorChannelCalculated, GlobalColorChannels, ObjectColorCalculated};
+use crate::level::death::{restart_level, PlayerDeath};
+use crate::level::mode::wave::WaveTrail;
+use crate::level::mode::{GameModeKind, PlayerMode};
 use crate::level::object::Object;
-use crate::level::player::Player;
+use crate::level::player::{GhostTrail, Player, PlayerInput, GHOST_TRAIL_FADE};
+use crate::level::portal::DualPlayer;
+use crate::level::replay::{Replay, ReplayState};
 use crate::level::section::GlobalSections;
 use crate::level::transform::Transform2d;
+use crate::level::trigger::camera::CameraSettings;
+use crate::level::trigger::end::LevelEnd;
+use crate::level::trigger::pickup::PickupValues;
+use crate::level::trigger::settings::LevelSettings;
 use crate::level::trigger::shake::ShakeData;
 use crate::level::trigger::GlobalTriggers;
-use crate::level::{LevelWorld, SongOffset};
+use crate::level::{LevelSource, LevelWorld, SongOffset};
+use crate::state::level::audio::{update_trigger_audio, TriggerAudio};
+use crate::state::level::ghost::{update_ghosts, Ghosts};
+use crate::state::menu::LevelBrowserState;
 use crate::state::GameState;
 use crate::utils::section_index_from_x;
 
+mod audio;
+mod ghost;
+
 pub(crate) struct LevelStatePlugin;
 
 impl Plugin for LevelStatePlugin {
@@ -45,11 +66,19 @@ impl Plugin for LevelStatePlugin {
             });
 
         app.init_resource::<Options>()
+            .init_resource::<TriggerAudio>()
+            .init_resource::<Ghosts>()
             .add_systems(OnEnter(GameState::Level), level_setup)
             .add_systems(Level, update_level_world.run_if(in_state(GameState::Level)))
             .add_systems(
                 Update,
-                (update_controls, render_option_gui).run_if(in_state(GameState::Level)),
+                (
+                    update_controls,
+                    render_option_gui,
+                    render_attempt_counter,
+                    render_level_end,
+                )
+                    .run_if(in_state(GameState::Level)),
             )
             .add_systems(OnExit(GameState::Level), level_cleanup);
     }
@@ -73,6 +102,8 @@ pub(crate) struct Options {
     pause_player: bool,
     camera_limit: f32,
     disable_shake: bool,
+    /// Search for a replay that completes the level, shown as it runs
+    autoplay: Option<Autoplay>,
 }
 
 impl Default for Options {
@@ -88,6 +119,7 @@ impl Default for Options {
             pause_player: false,
             camera_limit: 570.,
             disable_shake: false,
+            autoplay: None,
         }
     }
 }
@@ -101,11 +133,15 @@ fn level_setup(
     mut cameras: Query<(Entity, &mut Transform, &mut OrthographicProjection), With<Camera>>,
     mut level_world: ResMut<LevelWorld>,
     mut audio_instances: ResMut<Assets<AudioInstance>>,
+    mut trigger_audio: ResMut<TriggerAudio>,
     song_players: Query<&SongPlayer>,
 ) {
     *options = Options::default();
+    trigger_audio.stop_all(&mut audio_instances);
     for (entity, mut transform, mut projection) in &mut cameras {
         transform.translation = Vec3::ZERO;
+        transform.scale = Vec3::ONE;
+        transform.rotation = Quat::IDENTITY;
         projection.scale = 1.;
         commands
             .entity(entity)
@@ -114,13 +150,11 @@ fn level_setup(
     let LevelWorld::World(ref mut world) = *level_world else {
         panic!("World is supposed to be created");
     };
-    let mut players = world.query_filtered::<&Transform2d, With<Player>>();
+    let mut players = world.query_filtered::<&Player, Without<DualPlayer>>();
     world.resource_scope(|world, song_offset: Mut<SongOffset>| {
         world.resource_scope(|world, global_triggers: Mut<GlobalTriggers>| {
-            let transform = players.single(world);
-            let mut time = global_triggers
-                .speed_changes
-                .time_for_pos(transform.translation.x);
+            let player = players.single(world);
+            let mut time = global_triggers.speed_changes.time_for_pos(player.distance);
 
             time += song_offset.0;
 
@@ -145,16 +179,34 @@ fn level_setup(
     options.camera_limit += 56.;
 }
 
+fn set_practice(checkpoints: &mut Checkpoints, enabled: bool) {
+    checkpoints.enabled = enabled;
+    if !enabled {
+        checkpoints.list.clear();
+    }
+}
+
 fn render_option_gui(
     mut options: ResMut<Options>,
     mut contexts: EguiContexts,
     mut state: ResMut<NextState<GameState>>,
     mut projections: Query<&mut OrthographicProjection, With<Camera>>,
+    mut level_world: ResMut<LevelWorld>,
+    mut ghosts: ResMut<Ghosts>,
+    cocos2d_frames: Res<Cocos2dFrames>,
 ) {
     if !options.show_options {
         return;
     }
 
+    let LevelWorld::World(ref mut world) = *level_world else {
+        return;
+    };
+    let playing_back = world.resource::<ReplayState>().playback.is_some();
+    let mut replay_action = None;
+    let mut removed_ghost = None;
+    let mut checkpoints = world.resource_mut::<Checkpoints>();
+
     egui::Window::new("Level Options").show(contexts.ctx_mut(), |ui| {
         ui.checkbox(
             &mut options.lock_camera_to_player,
@@ -166,6 +218,67 @@ fn render_option_gui(
         ui.checkbox(&mut options.disable_shake, "Disable shake (K)");
         ui.checkbox(&mut options.pause_player, "Pause player (Esc)");
         ui.separator();
+        let mut practice = checkpoints.enabled;
+        if ui.checkbox(&mut practice, "Practice mode (P)").changed() {
+            set_practice(&mut checkpoints, practice);
+        }
+        ui.add_enabled_ui(checkpoints.enabled, |ui| {
+            ui.horizontal(|ui| {
+                if ui.button("Place checkpoint (Z)").clicked() {
+                    checkpoints.place = true;
+                }
+                if ui.button("Remove last (X)").clicked() {
+                    checkpoints.list.pop();
+                }
+            });
+            ui.horizontal(|ui| {
+                let mut automatic = checkpoints.interval.is_some();
+                if ui.checkbox(&mut automatic, "Place every").changed() {
+                    checkpoints.interval = automatic.then_some(5.);
+                }
+                if let Some(interval) = &mut checkpoints.interval {
+                    ui.add(
+                        egui::DragValue::new(interval)
+                            .clamp_range(0.5..=60.)
+                            .speed(0.1)
+                            .suffix("s"),
+                    );
+                }
+            });
+
+            let mut moved = None;
+            let mut removed = None;
+            let len = checkpoints.list.len();
+            egui::ScrollArea::vertical()
+                .max_height(150.)
+                .show(ui, |ui| {
+                    for (index, checkpoint) in checkpoints.list.iter().enumerate() {
+                        ui.horizontal(|ui| {
+                            ui.label(format!("{}. {:.2}s", index + 1, checkpoint.time));
+                            if ui.add_enabled(index > 0, egui::Button::new("Up")).clicked() {
+                                moved = Some((index, index - 1));
+                            }
+                            if ui
+                                .add_enabled(index + 1 < len, egui::Button::new("Down"))
+                                .clicked()
+                            {
+                                moved = Some((index, index + 1));
+                            }
+                            if ui.button("Delete").clicked() {
+                                removed = Some(index);
+                            }
+                        });
+                    }
+                });
+
+            if let Some((from, to)) = moved {
+                checkpoints.list.swap(from, to);
+            }
+            if let Some(index) = removed {
+                checkpoints.list.remove(index);
+            }
+        });
+        ui.separator();
         ui.horizontal(|ui| {
             if ui.button("Exit to menu").clicked() {
                 state.set(GameState::Menu);
@@ -181,6 +294,168 @@ fn render_option_gui(
                 }
             }
         });
+        ui.separator();
+        ui.horizontal(|ui| {
+            if ui.button("Save replay").clicked() {
+                replay_action = Some(ReplayAction::Save);
+            }
+            if ui.button("Play replay").clicked() {
+                replay_action = Some(ReplayAction::Play);
+            }
+            if ui
+                .add_enabled(options.autoplay.is_none(), egui::Button::new("Autoplay"))
+                .clicked()
+            {
+                replay_action = Some(ReplayAction::Autoplay);
+            }
+            if ui
+                .add_enabled(playing_back, egui::Button::new("Stop playback"))
+                .clicked()
+            {
+                replay_action = Some(ReplayAction::Stop);
+            }
+        });
+        if let Some(autoplay) = &options.autoplay {
+            ui.label(format!("Searching... {:.0}%", autoplay.progress() * 100.));
+        }
+        ui.separator();
+        if ui.button("Add ghost").clicked() {
+            replay_action = Some(ReplayAction::AddGhost);
+        }
+        for (index, ghost) in ghosts.0.iter_mut().enumerate() {
+            ui.horizontal(|ui| {
+                let [r, g, b, _] = ghost.color.as_rgba_u8();
+                ui.checkbox(
+                    &mut ghost.visible,
+                    egui::RichText::new(&ghost.name).color(egui::Color32::from_rgb(r, g, b)),
+                );
+                if ghost.loading() {
+                    ui.label("Loading...");
+                }
+                if ui.button("Remove").clicked() {
+                    removed_ghost = Some(index);
+                }
+            });
+        }
+    });
+
+    match replay_action {
+        Some(ReplayAction::Save) => {
+            let replay = world.resource::<ReplayState>().replay();
+            let path = FileDialog::new()
+                .set_filename("replay.json")
+                .add_filter("Replay", &["json"])
+                .show_save_single_file();
+            if let Ok(Some(path)) = path {
+                if let Err(err) = replay.save(&path) {
+                    error!("Failed to save replay. {}", err);
+                }
+            }
+        }
+        Some(ReplayAction::Play) => {
+            let Some((_, replay)) = load_replay(world) else {
+                return;
+            };
+            world.resource_mut::<ReplayState>().start_playback(replay);
+            restart_level(world);
+        }
+        Some(ReplayAction::Stop) => world.resource_mut::<ReplayState>().playback = None,
+        Some(ReplayAction::Autoplay) => {
+            options.autoplay = Some(Autoplay::new(world, AutoplaySettings::default()))
+        }
+        Some(ReplayAction::AddGhost) => {
+            let Some((name, replay)) = load_replay(world) else {
+                return;
+            };
+            let level_data = world.resource::<LevelSource>().0.clone();
+            ghosts.add(name, replay, level_data, &cocos2d_frames);
+        }
+        None => (),
+    }
+
+    if let Some(index) = removed_ghost {
+        ghosts.0.remove(index);
+    }
+}
+
+enum ReplayAction {
+    Save,
+    Play,
+    Stop,
+    Autoplay,
+    AddGhost,
+}
+
+/// Asks for a replay file, returning its name along with the replay
+fn load_replay(world: &World) -> Option<(String, Replay)> {
+    let path = FileDialog::new()
+        .add_filter("Replay", &["json"])
+        .show_open_single_file();
+    let Ok(Some(path)) = path else {
+        return None;
+    };
+
+    let replay = match Replay::load(&path) {
+        Ok(replay) => replay,
+        Err(err) => {
+            error!("Failed to load replay. {}", err);
+            return None;
+        }
+    };
+
+    if !replay.matches(world.resource::<ReplayState>()) {
+        warn!(
+            "Replay was recorded on different level data, mode or tick rate (version {})",
+            replay.version
+        );
+    }
+
+    let name = path
+        .file_stem()
+        .map(|name| name.to_string_lossy().into_owned())
+        .unwrap_or_default();
+
+    Some((name, replay))
+}
+
+/// Decisions the autoplay search makes every frame
+const AUTOPLAY_DECISIONS_PER_FRAME: usize = 15;
+
+fn render_attempt_counter(level_world: Res<LevelWorld>, mut contexts: EguiContexts) {
+    let LevelWorld::World(ref world) = *level_world else {
+        return;
+    };
+
+    if world.resource::<LevelSettings>().hide_attempts {
+        return;
+    }
+
+    let attempt = world.resource::<PickupValues>().attempts + 1;
+
+    egui::Area::new("Attempt counter")
+        .anchor(egui::Align2::CENTER_TOP, egui::vec2(0., 10.))
+        .show(contexts.ctx_mut(), |ui| {
+            ui.heading(format!("Attempt {}", attempt));
+        });
+}
+
+fn render_level_end(
+    level_world: Res<LevelWorld>,
+    mut contexts: EguiContexts,
+    mut state: ResMut<NextState<GameState>>,
+) {
+    let LevelWorld::World(ref world) = *level_world else {
+        return;
+    };
+
+    if !world.resource::<LevelEnd>().reached {
+        return;
+    }
+
+    egui::Window::new("Level Complete").show(contexts.ctx_mut(), |ui| {
+        if ui.button("Exit to menu").clicked() {
+            state.set(GameState::Menu);
+        }
     });
 }
 
@@ -305,15 +580,39 @@ fn update_level_world(
         &mut ActualCameraTranslation,
     )>,
     mut level_world: ResMut<LevelWorld>,
-    options: ResMut<Options>,
+    mut options: ResMut<Options>,
     mut gizmos: Gizmos,
     song_players: Query<&SongPlayer>,
     mut audio_instances: ResMut<Assets<AudioInstance>>,
+    mut trigger_audio: ResMut<TriggerAudio>,
+    audio: Res<Audio>,
+    asset_server: Res<AssetServer>,
+    browser_state: Res<LevelBrowserState>,
+    keys: Res<ButtonInput<KeyCode>>,
+    mouse_button: Res<ButtonInput<MouseButton>>,
+    time: Res<Time>,
+    mut ghosts: ResMut<Ghosts>,
 ) {
     let LevelWorld::World(ref mut world) = *level_world else {
         panic!("World is supposed to be created");
     };
 
+    let pressed = keys.pressed(KeyCode::Space) || mouse_button.pressed(MouseButton::Left);
+    // Only used by levels in two player mode
+    let pressed2 = keys.pressed(KeyCode::Enter) || mouse_button.pressed(MouseButton::Right);
+
+    let mut checkpoints = world.resource_mut::<Checkpoints>();
+    if keys.just_pressed(KeyCode::KeyP) {
+        let enabled = !checkpoints.enabled;
+        set_practice(&mut checkpoints, enabled);
+    }
+    if keys.just_pressed(KeyCode::KeyZ) {
+        checkpoints.place = true;
+    }
+    if keys.just_pressed(KeyCode::KeyX) {
+        checkpoints.list.pop();
+    }
+
     world.resource_scope(|_, mut time: Mut<Time<Virtual>>| {
         if options.pause_player {
             time.pause();
@@ -322,28 +621,87 @@ fn update_level_world(
         }
     });
 
-    world.run_schedule(First);
-    world.run_schedule(PreUpdate);
-    world.run_schedule(RunFixedMainLoop);
+    let searching = options.autoplay.is_some();
+
+    if let Some(autoplay) = &mut options.autoplay {
+        match autoplay.step(world, AUTOPLAY_DECISIONS_PER_FRAME) {
+            Ok(None) => (),
+            Ok(Some(replay)) => {
+                info!(
+                    "Autoplay found a replay with {} inputs",
+                    replay.inputs.len()
+                );
+                world.resource_mut::<ReplayState>().start_playback(replay);
+                restart_level(world);
+                options.autoplay = None;
+            }
+            Err(err) => {
+                error!("Autoplay failed. {}", err);
+                restart_level(world);
+                options.autoplay = None;
+            }
+        }
+    }
+
+    // The search steps the world by itself
+    let ticks = if searching {
+        0
+    } else if options.pause_player {
+        1
+    } else {
+        world
+            .resource_mut::<ReplayState>()
+            .ticks_for_frame(time.delta_seconds_f64())
+    };
+
+    for tick in 0..ticks {
+        // The last tick is finished after the camera has been updated
+        if tick != 0 {
+            world.run_schedule(PostUpdate);
+            world.run_schedule(Last);
+        }
+
+        if !options.pause_player && world.resource::<ReplayState>().playback.is_none() {
+            world
+                .resource_mut::<PlayerInput>()
+                .set_both(pressed, pressed2);
+        }
+
+        world.run_schedule(First);
+        world.run_schedule(PreUpdate);
+        world.run_schedule(RunFixedMainLoop);
+
+        if !options.pause_player {
+            world.run_schedule(Update);
+        }
+    }
+
+    if !searching {
+        let tick = world.resource::<ReplayState>().tick;
+        update_ghosts(&mut ghosts, tick, &mut gizmos);
+    }
+
+    let mut player_death = world.resource_mut::<PlayerDeath>();
+    let died = player_death.died;
+    let respawned = std::mem::take(&mut player_death.respawned);
 
-    if !options.pause_player {
-        world.run_schedule(Update);
+    if respawned {
+        trigger_audio.stop_all(&mut audio_instances);
     }
 
     if let Ok(song_player) = song_players.get_single() {
         if let Some(instance) = audio_instances.get_mut(&song_player.0) {
-            if options.pause_player {
+            // The song picks up from the respawn position once the player is alive again
+            if options.pause_player || died.is_some() || searching {
                 if let PlaybackState::Playing { .. } = instance.state() {
                     instance.pause(AudioTween::linear(Duration::ZERO));
                 }
-            } else if let PlaybackState::Paused { .. } = instance.state() {
-                let mut players = world.query_filtered::<&Transform2d, With<Player>>();
+            } else if respawned || matches!(instance.state(), PlaybackState::Paused { .. }) {
+                let mut players = world.query_filtered::<&Player, Without<DualPlayer>>();
                 world.resource_scope(|world, song_offset: Mut<SongOffset>| {
                     world.resource_scope(|world, global_triggers: Mut<GlobalTriggers>| {
-                        let transform = players.single(world);
-                        let mut time = global_triggers
-                            .speed_changes
-                            .time_for_pos(transform.translation.x);
+                        let player = players.single(world);
+                        let mut time = global_triggers.speed_changes.time_for_pos(player.distance);
 
                         time += song_offset.0;
 
@@ -355,6 +713,28 @@ fn update_level_world(
         }
     }
 
+    update_trigger_audio(
+        world,
+        &mut trigger_audio,
+        options.pause_player,
+        &audio,
+        &mut audio_instances,
+        &asset_server,
+        &browser_state,
+    );
+
+    draw_players(world, &mut gizmos, Color::WHITE);
+
+    if let Some((position, elapsed)) = died {
+        let respawn_delay = world.resource::<LevelSettings>().respawn_delay();
+        let progress = (elapsed / respawn_delay).min(1.);
+        gizmos.circle_2d(
+            position,
+            15. + progress * 60.,
+            Color::rgba(1., 1., 1., 1. - progress),
+        );
+    }
+
     // Render player line
     let mut players = world.query::<(&Player, &Transform2d)>();
 
@@ -377,7 +757,9 @@ fn update_level_world(
         }
     }
 
-    let (_, player_transform) = players.single(world);
+    let mut primary_players =
+        world.query_filtered::<&Transform2d, (With<Player>, Without<DualPlayer>)>();
+    let player_transform = *primary_players.single(world);
 
     if options.lock_camera_to_player {
         actual_camera_translation.0.x =
@@ -406,8 +788,33 @@ fn update_level_world(
         camera_transform.translation = actual_camera_translation.0.extend(0.);
     }
 
-    let camera_min = camera_transform.translation.x + camera_projection.area.min.x;
-    let camera_max = camera_transform.translation.x + camera_projection.area.max.x;
+    let mut visible_half_width = camera_projection.area.max.x;
+
+    world.resource_scope(|_, mut camera_settings: Mut<CameraSettings>| {
+        let zoom = camera_settings.zoom.max(f32::EPSILON);
+        let half_extents = camera_projection.area.max / zoom;
+
+        let position = camera_settings.constrain(camera_transform.translation.xy(), half_extents);
+
+        camera_transform.translation = position.extend(0.);
+        // Mirror portals flip the view horizontally
+        let mirror = if camera_settings.mirrored { -1. } else { 1. };
+        camera_transform.scale = Vec3::new(mirror / zoom, 1. / zoom, 1.);
+        camera_transform.rotation = Quat::from_rotation_z(camera_settings.angle);
+
+        // A rotated view can reach as far as its corners
+        visible_half_width = if camera_settings.angle != 0. {
+            half_extents.length()
+        } else {
+            half_extents.x
+        };
+
+        camera_settings.position = position;
+        camera_settings.half_extents = half_extents;
+    });
+
+    let camera_min = camera_transform.translation.x - visible_half_width;
+    let camera_max = camera_transform.translation.x + visible_half_width;
 
     let min_section = section_index_from_x(camera_min) as usize;
     let max_section = section_index_from_x(camera_max) as usize;
@@ -415,8 +822,10 @@ fn update_level_world(
     let mut global_sections = world.resource_mut::<GlobalSections>();
     global_sections.visible = min_section.saturating_sub(2)..max_section.saturating_add(3);
 
-    world.run_schedule(PostUpdate);
-    world.run_schedule(Last);
+    if ticks != 0 {
+        world.run_schedule(PostUpdate);
+        world.run_schedule(Last);
+    }
 
     if options.display_hitboxes {
         world.resource_scope(|world, global_sections: Mut<GlobalSections>| {
@@ -433,7 +842,13 @@ fn update_level_world(
                     gizmos.primitive_2d(*hitbox, Vec2::ZERO, 0., Color::BLUE);
                 }
             }
-        })
+        });
+
+        let mut players =
+            world.query_filtered::<&crate::level::collision::GlobalHitbox, With<Player>>();
+        for hitbox in players.iter(world) {
+            gizmos.primitive_2d(*hitbox, Vec2::ZERO, 0., Color::GREEN);
+        }
     }
 
     world.resource_scope(|world, global_color_channels: Mut<GlobalColorChannels>| {
@@ -450,11 +865,62 @@ fn update_level_world(
     world.clear_trackers();
 }
 
+/// Draws the players that aren't hidden, along with their trails
+fn draw_players(world: &mut World, gizmos: &mut Gizmos, color: Color) {
+    let mut players = world.query::<(
+        &Player,
+        &Transform2d,
+        &PlayerMode,
+        &Hitbox,
+        &WaveTrail,
+        &GhostTrail,
+    )>();
+
+    for (player, transform, player_mode, hitbox, wave_trail, ghost_trail) in players.iter(world) {
+        if player.hidden {
+            continue;
+        }
+
+        let Hitbox::Box { half_extents, .. } = *hitbox else {
+            continue;
+        };
+        let size = half_extents * 2. * transform.scale.abs();
+
+        for (image, age) in &ghost_trail.images {
+            let fade = 1. - age / GHOST_TRAIL_FADE;
+            gizmos.rect_2d(
+                image.translation.xy(),
+                image.angle,
+                size,
+                color.with_a(color.a() * fade * 0.5),
+            );
+        }
+
+        if player_mode.kind == GameModeKind::Wave {
+            gizmos.linestrip_2d(
+                wave_trail
+                    .points
+                    .iter()
+                    .copied()
+                    .chain(std::iter::once(transform.translation.xy())),
+                color,
+            );
+        }
+
+        gizmos.rect_2d(transform.translation.xy(), transform.angle, size, color);
+    }
+}
+
 fn level_cleanup(
     mut commands: Commands,
     mut audio_instances: ResMut<Assets<AudioInstance>>,
+    mut trigger_audio: ResMut<TriggerAudio>,
     song_players: Query<(Entity, &SongPlayer)>,
+    mut ghosts: ResMut<Ghosts>,
 ) {
+    trigger_audio.stop_all(&mut audio_instances);
+    ghosts.0.clear();
+
     for (entity, song_player) in &song_players {
         commands.entity(entity).despawn_recursive();
 
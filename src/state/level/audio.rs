@@ -0,0 +1,264 @@
+use std::time::Duration;
+
+use bevy::asset::io::AssetSourceId;
+use bevy::asset::{AssetPath, AssetServer, Assets, Handle};
+use bevy::math::Vec3Swizzles;
+use bevy::prelude::{Resource, Without, World};
+use bevy_kira_audio::{Audio, AudioControl, AudioInstance, AudioSource, AudioTween, PlaybackState};
+
+use crate::level::group::{GlobalGroup, GlobalGroups};
+use crate::level::player::Player;
+use crate::level::portal::DualPlayer;
+use crate::level::transform::Transform2d;
+use crate::level::trigger::audio::{
+    AudioCommand, AudioQueue, SoundAction, SoundKey, SoundSettings, SpatialSettings,
+};
+use crate::level::trigger::GlobalTriggers;
+use crate::state::menu::LevelBrowserState;
+
+/// Songs and sound effects started by triggers
+#[derive(Default, Resource)]
+pub(crate) struct TriggerAudio(Vec<PlayingSound>);
+
+struct PlayingSound {
+    key: SoundKey,
+    instance: Handle<AudioInstance>,
+    settings: SoundSettings,
+    spatial: Option<SpatialSettings>,
+    /// Level time the sound started at
+    level_time: f32,
+    stopping: bool,
+}
+
+impl PlayingSound {
+    fn position(&self, level_time: f32) -> f32 {
+        self.settings.start + (level_time - self.level_time).max(0.) * self.settings.playback_rate
+    }
+}
+
+fn tween(seconds: f32) -> AudioTween {
+    AudioTween::linear(Duration::from_secs_f32(seconds.max(0.)))
+}
+
+fn load_sound(
+    key: SoundKey,
+    sound_id: u64,
+    asset_server: &AssetServer,
+    browser_state: &LevelBrowserState,
+) -> Handle<AudioSource> {
+    let path = match key {
+        SoundKey::Song(_) => {
+            if let Some(handle) = browser_state.stored_songs.get(&sound_id) {
+                return handle.clone();
+            }
+            sound_id.to_string() + ".mp3"
+        }
+        SoundKey::Sfx(_) => format!("s{}.ogg", sound_id),
+    };
+
+    asset_server.load(AssetPath::from(path).with_source(AssetSourceId::from("data")))
+}
+
+impl TriggerAudio {
+    pub(crate) fn stop_all(&mut self, audio_instances: &mut Assets<AudioInstance>) {
+        for sound in self.0.drain(..) {
+            if let Some(instance) = audio_instances.get_mut(&sound.instance) {
+                instance.stop(tween(0.));
+            }
+        }
+    }
+
+    fn run_command(
+        &mut self,
+        command: AudioCommand,
+        level_time: f32,
+        paused: bool,
+        audio: &Audio,
+        audio_instances: &mut Assets<AudioInstance>,
+        asset_server: &AssetServer,
+        browser_state: &LevelBrowserState,
+    ) {
+        let AudioCommand {
+            key,
+            sound_id,
+            action,
+            settings,
+            spatial,
+            level_time: start_time,
+        } = command;
+
+        match action {
+            SoundAction::Play => {
+                // A channel only plays one song at a time
+                if let SoundKey::Song(_) = key {
+                    self.stop(key, 0., audio_instances);
+
+                    if !browser_state.use_song {
+                        return;
+                    }
+                }
+
+                let handle = load_sound(key, sound_id, asset_server, browser_state);
+
+                let mut sound = PlayingSound {
+                    key,
+                    instance: Handle::default(),
+                    settings,
+                    spatial,
+                    level_time: start_time,
+                    stopping: false,
+                };
+
+                let mut play = audio.play(handle);
+                play.with_volume(sound.settings.volume as f64)
+                    .with_playback_rate(sound.settings.playback_rate as f64)
+                    .start_from(sound.position(level_time) as f64);
+                if sound.settings.fade_in > 0. {
+                    play.fade_in(tween(sound.settings.fade_in));
+                }
+                if sound.settings.looped {
+                    play.looped();
+                }
+                if paused {
+                    play.paused();
+                }
+                sound.instance = play.handle();
+
+                self.0.push(sound);
+            }
+            SoundAction::Edit {
+                volume,
+                playback_rate,
+                duration,
+            } => {
+                for sound in self.0.iter_mut().filter(|sound| sound.key == key) {
+                    let Some(instance) = audio_instances.get_mut(&sound.instance) else {
+                        continue;
+                    };
+                    if let Some(volume) = volume {
+                        sound.settings.volume = volume;
+                        instance.set_volume(volume as f64, tween(duration));
+                    }
+                    if let Some(playback_rate) = playback_rate {
+                        // Keep the position consistent for seeking after the rate changes
+                        sound.settings.start = sound.position(start_time);
+                        sound.level_time = start_time;
+                        sound.settings.playback_rate = playback_rate;
+                        instance.set_playback_rate(playback_rate as f64, tween(duration));
+                    }
+                }
+            }
+            SoundAction::Stop => self.stop(key, settings.fade_out, audio_instances),
+        }
+    }
+
+    fn stop(&mut self, key: SoundKey, fade_out: f32, audio_instances: &mut Assets<AudioInstance>) {
+        self.0.retain(|sound| {
+            if sound.key != key {
+                return true;
+            }
+            if let Some(instance) = audio_instances.get_mut(&sound.instance) {
+                instance.stop(tween(fade_out));
+            }
+            false
+        });
+    }
+}
+
+/// Plays back the audio requested by triggers and keeps it paused and seeked with the level
+pub(super) fn update_trigger_audio(
+    world: &mut World,
+    trigger_audio: &mut TriggerAudio,
+    paused: bool,
+    audio: &Audio,
+    audio_instances: &mut Assets<AudioInstance>,
+    asset_server: &AssetServer,
+    browser_state: &LevelBrowserState,
+) {
+    let mut players = world.query_filtered::<(&Player, &Transform2d), Without<DualPlayer>>();
+    let Ok((player, player_transform)) = players.get_single(world) else {
+        return;
+    };
+    let player_position = player_transform.translation.xy();
+
+    let global_triggers = world.resource::<GlobalTriggers>();
+    let level_time = global_triggers.speed_changes.time_for_pos(player.distance);
+
+    let commands = std::mem::take(&mut world.resource_mut::<AudioQueue>().0);
+
+    for command in commands {
+        trigger_audio.run_command(
+            command,
+            level_time,
+            paused,
+            audio,
+            audio_instances,
+            asset_server,
+            browser_state,
+        );
+    }
+
+    let mut groups = world.query::<&GlobalGroup>();
+    let mut objects = world.query::<&Transform2d>();
+    let global_groups = world.resource::<GlobalGroups>();
+
+    trigger_audio.0.retain_mut(|sound| {
+        let Some(instance) = audio_instances.get_mut(&sound.instance) else {
+            // Still waiting for the sound to load
+            return true;
+        };
+
+        match instance.state() {
+            PlaybackState::Stopped => return false,
+            PlaybackState::Playing { .. } if paused => {
+                instance.pause(tween(0.));
+                return true;
+            }
+            PlaybackState::Paused { .. } if !paused => {
+                instance.seek_to(sound.position(level_time) as f64);
+                instance.resume(tween(0.));
+            }
+            _ => (),
+        }
+
+        if paused {
+            return true;
+        }
+
+        let settings = &sound.settings;
+
+        if !settings.looped
+            && !sound.stopping
+            && settings.end > 0.
+            && sound.position(level_time) >= settings.end - settings.fade_out
+        {
+            instance.stop(tween(settings.fade_out));
+            sound.stopping = true;
+        }
+
+        let Some(spatial) = sound.spatial else {
+            return true;
+        };
+
+        let Some(center) = global_groups
+            .0
+            .get(spatial.center_group as usize)
+            .and_then(|group_entity| groups.get(world, *group_entity).ok())
+            .and_then(|group| group.root_entities.first())
+            .and_then(|entity| objects.get(world, *entity).ok())
+            .map(|transform| transform.translation.xy())
+        else {
+            return true;
+        };
+
+        let offset = center - player_position;
+        let range = (spatial.max_distance - spatial.min_distance).max(f32::EPSILON);
+        let attenuation = 1. - ((offset.length() - spatial.min_distance) / range).clamp(0., 1.);
+        let panning = 0.5 + (offset.x / spatial.max_distance.max(f32::EPSILON)).clamp(-1., 1.) / 2.;
+
+        instance.set_volume((settings.volume * attenuation) as f64, tween(0.));
+        instance.set_panning(panning as f64, tween(0.));
+
+        true
+    });
+}
@@ -0,0 +1,139 @@
+use std::sync::Arc;
+
+use bevy::log::error;
+use bevy::prelude::{Color, Gizmos, Resource, World};
+use bevy::tasks::{AsyncComputeTaskPool, Task};
+use futures_lite::future;
+
+use crate::asset::cocos2d_atlas::Cocos2dFrames;
+use crate::level::bot::run_tick;
+use crate::level::death::{restart_level, PlayerDeath};
+use crate::level::replay::{Replay, ReplayState};
+use crate::level::trigger::end::LevelEnd;
+use crate::level::LevelData;
+use crate::state::level::draw_players;
+
+const GHOST_COLORS: [Color; 6] = [
+    Color::CYAN,
+    Color::ORANGE,
+    Color::FUCHSIA,
+    Color::LIME_GREEN,
+    Color::GOLD,
+    Color::VIOLET,
+];
+
+const GHOST_OPACITY: f32 = 0.5;
+
+/// Most ticks a ghost runs in one frame when catching up to the player
+const MAX_CATCH_UP_TICKS: u64 = 2400;
+
+enum GhostWorld {
+    Pending(Task<Result<World, anyhow::Error>>),
+    World(Box<World>),
+    Failed,
+}
+
+/// A replay played back in a world of its own, so it doesn't affect the level being played
+pub(crate) struct Ghost {
+    pub(crate) name: String,
+    pub(crate) color: Color,
+    pub(crate) visible: bool,
+    replay: Replay,
+    world: GhostWorld,
+    /// The replay has run out, the ghost stays where it died or finished
+    finished: bool,
+}
+
+#[derive(Default, Resource)]
+pub(crate) struct Ghosts(pub(crate) Vec<Ghost>);
+
+impl Ghosts {
+    pub(crate) fn add(
+        &mut self,
+        name: String,
+        replay: Replay,
+        level_data: Arc<LevelData>,
+        cocos2d_frames: &Cocos2dFrames,
+    ) {
+        let cocos2d_frames = cocos2d_frames.clone();
+        let task = AsyncComputeTaskPool::get()
+            .spawn(async move { level_data.create_world(&cocos2d_frames) });
+
+        self.0.push(Ghost {
+            name,
+            color: GHOST_COLORS[self.0.len() % GHOST_COLORS.len()].with_a(GHOST_OPACITY),
+            visible: true,
+            replay,
+            world: GhostWorld::Pending(task),
+            finished: false,
+        });
+    }
+}
+
+impl Ghost {
+    pub(crate) fn loading(&self) -> bool {
+        matches!(self.world, GhostWorld::Pending(_))
+    }
+}
+
+fn restart(replay: &Replay, world: &mut World) {
+    world
+        .resource_mut::<ReplayState>()
+        .start_playback(replay.clone());
+    restart_level(world);
+}
+
+/// Keeps every ghost on the same tick as the player and draws them
+pub(crate) fn update_ghosts(ghosts: &mut Ghosts, tick: u64, gizmos: &mut Gizmos) {
+    for ghost in &mut ghosts.0 {
+        if let GhostWorld::Pending(task) = &mut ghost.world {
+            let Some(result) = future::block_on(future::poll_once(task)) else {
+                continue;
+            };
+
+            match result {
+                Ok(mut world) => {
+                    restart(&ghost.replay, &mut world);
+                    ghost.world = GhostWorld::World(Box::new(world));
+                }
+                Err(err) => {
+                    error!(
+                        "Failed to create the world of ghost {}. {}",
+                        ghost.name, err
+                    );
+                    ghost.world = GhostWorld::Failed;
+                    continue;
+                }
+            }
+        }
+
+        let GhostWorld::World(world) = &mut ghost.world else {
+            continue;
+        };
+
+        // The player went back to the start or a checkpoint
+        if world.resource::<ReplayState>().tick > tick {
+            restart(&ghost.replay, world);
+            ghost.finished = false;
+        }
+
+        let mut ran = 0;
+        while !ghost.finished
+            && ran < MAX_CATCH_UP_TICKS
+            && world.resource::<ReplayState>().tick < tick
+        {
+            run_tick(world, false);
+            ran += 1;
+
+            ghost.finished = world.resource::<PlayerDeath>().died.is_some()
+                || world.resource::<LevelEnd>().reached;
+        }
+
+        if !ghost.visible {
+            continue;
+        }
+
+        // Drawn the same way as the player, in the color of the ghost
+        draw_players(world, gizmos, ghost.color);
+    }
+}
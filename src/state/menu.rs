@@ -1,8 +1,8 @@
 use bevy::app::{App, Plugin, Update};
 use bevy::asset::Handle;
-use bevy::log::info;
+use bevy::log::{error, info};
 use bevy::prelude::{
-    in_state, Commands, Entity, IntoSystemConfigs, NextState, Query, ResMut, Resource, Window,
+    in_state, Commands, Entity, IntoSystemConfigs, NextState, Query, Res, ResMut, Resource, Window,
 };
 use bevy::tasks::{AsyncComputeTaskPool, Task};
 use bevy::utils::HashMap;
@@ -11,9 +11,13 @@ use bevy_egui::EguiContexts;
 use bevy_kira_audio::AudioSource;
 use egui::{Button, Color32};
 use futures_lite::future;
+use native_dialog::FileDialog;
 
 use crate::api::robtop::RobtopApi;
 use crate::api::ServerApi;
+use crate::asset::cocos2d_atlas::Cocos2dFrames;
+use crate::level::bot::{autoplay_levels, AutoplaySettings};
+use crate::level::replay::Replay;
 use crate::level::{LevelInfo, SongInfo};
 use crate::state::prepare::LevelToDownload;
 use crate::state::GameState;
@@ -36,6 +40,8 @@ pub(crate) struct LevelBrowserState {
     pub(crate) song_infos: HashMap<u64, SongInfo>,
     pub(crate) stored_songs: HashMap<u64, Handle<AudioSource>>,
     pub(crate) low_detail: bool,
+    autoplay_task: Option<Task<Vec<(u64, Result<Replay, anyhow::Error>)>>>,
+    autoplay_results: HashMap<u64, Result<Replay, String>>,
 }
 
 impl Default for LevelBrowserState {
@@ -48,6 +54,8 @@ impl Default for LevelBrowserState {
             song_infos: HashMap::new(),
             stored_songs: HashMap::new(),
             low_detail: false,
+            autoplay_task: None,
+            autoplay_results: HashMap::new(),
         }
     }
 }
@@ -58,6 +66,7 @@ fn render_menu_gui(
     mut contexts: EguiContexts,
     mut state: ResMut<NextState<GameState>>,
     mut windows: Query<(Entity, &mut Window)>,
+    cocos2d_frames: Res<Cocos2dFrames>,
 ) {
     egui::Window::new("Level Browser")
         .vscroll(true)
@@ -128,6 +137,28 @@ fn render_menu_gui(
                     ui.label("Loading...");
                 }
             } else if !browser_state.response.is_empty() {
+                if let Some(task) = &mut browser_state.autoplay_task {
+                    if let Some(results) = future::block_on(future::poll_once(task)) {
+                        for (id, result) in results {
+                            let result = result.map_err(|err| err.to_string());
+                            browser_state.autoplay_results.insert(id, result);
+                        }
+                        browser_state.autoplay_task = None;
+                    } else {
+                        ui.label("Autoplaying levels...");
+                    }
+                } else if ui.button("Autoplay all").clicked() {
+                    let level_ids = browser_state
+                        .response
+                        .iter()
+                        .map(|level| level.id)
+                        .collect();
+                    let cocos2d_frames = cocos2d_frames.clone();
+                    browser_state.autoplay_task = Some(AsyncComputeTaskPool::get().spawn(
+                        autoplay_levels(level_ids, cocos2d_frames, AutoplaySettings::default()),
+                    ));
+                }
+
                 for level in &browser_state.response {
                     ui.horizontal(|ui| {
                         ui.label(&level.name);
@@ -135,6 +166,18 @@ fn render_menu_gui(
                             commands.insert_resource(LevelToDownload(level.clone()));
                             state.set(GameState::Prepare);
                         }
+                        match browser_state.autoplay_results.get(&level.id) {
+                            Some(Ok(replay)) => {
+                                ui.label("Completable");
+                                if ui.button("Save replay").clicked() {
+                                    save_replay(replay, level.id);
+                                }
+                            }
+                            Some(Err(err)) => {
+                                ui.label(format!("Not completed: {}", err));
+                            }
+                            None => (),
+                        }
                     });
                 }
             } else {
@@ -142,3 +185,15 @@ fn render_menu_gui(
             }
         });
 }
+
+fn save_replay(replay: &Replay, level_id: u64) {
+    let path = FileDialog::new()
+        .set_filename(&format!("{}.json", level_id))
+        .add_filter("Replay", &["json"])
+        .show_save_single_file();
+    if let Ok(Some(path)) = path {
+        if let Err(err) = replay.save(&path) {
+            error!("Failed to save replay. {}", err);
+        }
+    }
+}
@@ -1,3 +1,4 @@
+use std::sync::Arc;
 use std::time::Instant;
 
 use bevy::app::{App, Plugin, Update};
@@ -18,7 +19,8 @@ use futures_lite::future;
 use crate::api::robtop::RobtopApi;
 use crate::api::ServerApi;
 use crate::asset::cocos2d_atlas::Cocos2dFrames;
-use crate::level::{LevelData, LevelInfo, LevelWorld, SongInfo};
+use crate::level::replay::{level_hash, set_level};
+use crate::level::{LevelData, LevelInfo, LevelSource, LevelWorld, SongInfo};
 use crate::state::level::SongPlayer;
 use crate::state::menu::LevelBrowserState;
 use crate::state::GameState;
@@ -182,7 +184,9 @@ fn wait_for_creation(
                 start = Instant::now();
                 let parsed = decompressed.parse()?;
                 info!("Parsing took {:?}", start.elapsed());
-                let world = parsed.create_world(&cocos2d_frames, low_detail);
+                let mut world = parsed.create_world(&cocos2d_frames, low_detail);
+                set_level(&mut world, level_data.id, level_hash(&decompressed.0));
+                world.insert_resource(LevelSource(Arc::new(level_data)));
                 info!("Total time: {:?}", start_all.elapsed());
 
                 Ok(world)
@@ -1,3 +1,5 @@
+use std::path::{Path, PathBuf};
+
 use bevy::asset::io::AssetSourceId;
 use bevy::asset::{AssetPath, LoadState};
 use bevy::prelude::*;
@@ -18,13 +20,21 @@ impl Plugin for StartupStatePlugin {
     }
 }
 
+/// Directory the `resources` asset source reads from
+#[derive(Resource)]
+pub(crate) struct ResourcesDir(pub(crate) PathBuf);
+
 #[derive(Component)]
 struct StartupEntity;
 
 #[derive(Component)]
 struct ListText;
 
-fn startup_setup(mut commands: Commands, server: Res<AssetServer>) {
+fn startup_setup(
+    mut commands: Commands,
+    server: Res<AssetServer>,
+    resources_dir: Res<ResourcesDir>,
+) {
     commands
         .spawn(NodeBundle {
             style: Style {
@@ -64,15 +74,35 @@ fn startup_setup(mut commands: Commands, server: Res<AssetServer>) {
 
     let source = AssetSourceId::from("resources");
 
-    commands.insert_resource(GlobalAssets {
-        assets: vec![
-            server.load(AssetPath::from("GJ_GameSheet-uhd.plist").with_source(source.clone())),
-            server.load(AssetPath::from("GJ_GameSheet02-uhd.plist").with_source(source.clone())),
-            server.load(AssetPath::from("GJ_GameSheet03-uhd.plist").with_source(source.clone())),
-            server.load(AssetPath::from("GJ_GameSheet04-uhd.plist").with_source(source.clone())),
-            server.load(AssetPath::from("GJ_GameSheetGlow-uhd.plist").with_source(source)),
-        ],
-    });
+    let mut assets = vec![
+        server.load(AssetPath::from("GJ_GameSheet-uhd.plist").with_source(source.clone())),
+        server.load(AssetPath::from("GJ_GameSheet02-uhd.plist").with_source(source.clone())),
+        server.load(AssetPath::from("GJ_GameSheet03-uhd.plist").with_source(source.clone())),
+        server.load(AssetPath::from("GJ_GameSheet04-uhd.plist").with_source(source.clone())),
+        server.load(AssetPath::from("GJ_GameSheetGlow-uhd.plist").with_source(source.clone())),
+    ];
+
+    assets.extend(
+        animation_descriptions(&resources_dir.0)
+            .into_iter()
+            .map(|file_name| server.load(AssetPath::from(file_name).with_source(source.clone()))),
+    );
+
+    commands.insert_resource(GlobalAssets { assets });
+}
+
+/// Monster animation descriptions, like `GJBeast01_AnimDesc.plist`
+fn animation_descriptions(resources_dir: &Path) -> Vec<String> {
+    let Ok(entries) = std::fs::read_dir(resources_dir) else {
+        return Vec::new();
+    };
+
+    let mut file_names: Vec<String> = entries
+        .filter_map(|entry| entry.ok()?.file_name().into_string().ok())
+        .filter(|file_name| file_name.starts_with("GJ") && file_name.ends_with("_AnimDesc.plist"))
+        .collect();
+    file_names.sort_unstable();
+    file_names
 }
 
 fn check_assets_ready(